@@ -0,0 +1,54 @@
+// src/attachments.rs
+//! Files attached to reminders. They are copied into an `attachments`
+//! directory next to the store and referred to by their name there, so the
+//! store stays a single small file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::storage;
+
+/// The directory attached files are copied to.
+pub fn dir() -> PathBuf {
+    storage::companion_path("attachments", "attachments")
+}
+
+/// Where the attachment called `name` is kept.
+pub fn path(name: &str) -> PathBuf {
+    dir().join(name)
+}
+
+/// Check that `source` can be attached before anything is changed.
+pub fn check(source: &Path) -> Result<()> {
+    if !source.is_file() {
+        return Err(Error::Invalid(format!("{} is not a file", source.display())));
+    }
+    Ok(())
+}
+
+/// Copy `source` into the attachments directory for reminder `id` and
+/// return the name it was stored under: the ID followed by the original
+/// file name, numbered when a file of that name is already attached.
+pub fn copy_in(id: u32, source: &Path) -> Result<String> {
+    check(source)?;
+    let file_name = source.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| Error::Invalid(format!("{} has no usable file name", source.display())))?;
+
+    let dir = dir();
+    fs::create_dir_all(&dir)
+        .map_err(|e| Error::io(format!("Failed to create attachments directory {}", dir.display()), e))?;
+
+    let mut name = format!("{}-{}", id, file_name);
+    let mut number = 2;
+    while dir.join(&name).exists() {
+        name = format!("{}-{}-{}", id, number, file_name);
+        number += 1;
+    }
+
+    let target = dir.join(&name);
+    fs::copy(source, &target)
+        .map_err(|e| Error::io(format!("Failed to copy {} to {}", source.display(), target.display()), e))?;
+    Ok(name)
+}
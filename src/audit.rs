@@ -0,0 +1,126 @@
+// src/audit.rs
+//! The audit log: an append-only record of every change to the store, with
+//! when it happened, the command line that made it and what it changed, so
+//! it can be found out later what happened to a reminder.
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+use crate::diff::{self, StoreDiff};
+use crate::error::{Error, Result};
+use crate::{clock, crypto, storage, ReminderStore};
+
+/// One change to the store, as logged.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub command: String,
+    pub changes: StoreDiff,
+    /// Content of each reminder the change touched, as it was before the
+    /// change or, for added reminders, after it, so the log reads without
+    /// the store
+    #[serde(default)]
+    pub contents: BTreeMap<u32, String>,
+}
+
+impl AuditEntry {
+    /// Whether the change added, removed or changed the reminder `id`.
+    pub fn touches(&self, id: u32) -> bool {
+        self.contents.contains_key(&id)
+    }
+}
+
+pub fn get_log_path() -> PathBuf {
+    storage::companion_path("audit.log", "audit.log")
+}
+
+/// Append what `command` changed from `before` to `after` to the log;
+/// nothing is logged when nothing changed. While the store is encrypted each
+/// line is too, as base64.
+pub fn record(before: &ReminderStore, after: &ReminderStore, command: &str) -> Result<()> {
+    let changes = diff::diff(before, after);
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let from_before = changes.removed.iter().chain(&changes.archived).chain(&changes.restored)
+        .chain(changes.changed.iter().map(|change| &change.id));
+    let mut contents = BTreeMap::new();
+    for (id, store) in from_before.map(|id| (id, before)).chain(changes.added.iter().map(|id| (id, after))) {
+        if let Some(reminder) = store.find_reminder(*id) {
+            contents.insert(*id, reminder.content.clone());
+        }
+    }
+
+    let entry = AuditEntry { timestamp: clock::now(), command: command.to_string(), changes, contents };
+    let line = to_line(&entry)?;
+    let path = get_log_path();
+    storage::create_parent_dir(&path)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| Error::io(format!("Failed to open {}", path.display()), e))?;
+    writeln!(file, "{}", line).map_err(|e| Error::io(format!("Failed to write {}", path.display()), e))
+}
+
+/// Write the log again, e.g. after encryption was turned on or off. This is
+/// the only time its lines are rewritten.
+pub fn rewrite_log() -> Result<()> {
+    let path = get_log_path();
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut text = String::new();
+    for entry in read(None)? {
+        text.push_str(&to_line(&entry)?);
+        text.push('\n');
+    }
+    storage::write_atomic(&path, text.as_bytes())
+}
+
+/// An entry as a line of the log, encrypted if encryption is on.
+fn to_line(entry: &AuditEntry) -> Result<String> {
+    let json = serde_json::to_string(entry)
+        .map_err(|e| Error::Parse(format!("Failed to serialize audit log entry: {}", e)))?;
+    Ok(if crypto::is_encrypting() {
+        base64::engine::general_purpose::STANDARD.encode(crypto::seal(json.as_bytes())?)
+    } else {
+        json
+    })
+}
+
+/// The logged changes made at or after `since`, oldest first. Lines that
+/// can't be read are skipped with a warning.
+pub fn read(since: Option<DateTime<Utc>>) -> Result<Vec<AuditEntry>> {
+    let path = get_log_path();
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::io(format!("Failed to read {}", path.display()), e)),
+    };
+
+    let mut entries = Vec::new();
+    for (number, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        // Base64 never starts with a brace, so encrypted lines are told apart
+        let json = if line.starts_with('{') {
+            Some(line.as_bytes().to_vec())
+        } else {
+            match base64::engine::general_purpose::STANDARD.decode(line.trim()) {
+                Ok(sealed) => Some(crypto::open(sealed)?),
+                Err(_) => None,
+            }
+        };
+        match json.and_then(|json| serde_json::from_slice::<AuditEntry>(&json).ok()) {
+            Some(entry) if since.is_none_or(|since| entry.timestamp >= since) => entries.push(entry),
+            Some(_) => {}
+            None => eprintln!("Warning: Could not parse line {} of {}, skipping", number + 1, path.display()),
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,235 @@
+// src/backup.rs
+//! Full backups: a `.tar.gz` archive holding the store, the config file, the
+//! attachments and the audit log, with a manifest giving the format version
+//! and a checksum of every file, so a restore can check the whole archive
+//! before anything is replaced. The store is kept as plain JSON whichever
+//! backend it came from, which also makes a backup the way to move to
+//! another backend or machine.
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+
+use crate::audit::{self, AuditEntry};
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::{attachments, clock, settings, storage, ReminderStore};
+
+/// Version of the archive layout; archives of a later version are refused.
+pub const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const STORE: &str = "store.json";
+const CONFIG: &str = "config.toml";
+const AUDIT_LOG: &str = "audit.log";
+const ATTACHMENTS: &str = "attachments/";
+
+/// What a backup holds, written into it as `manifest.json`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Manifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    /// Version of reminder that made the backup
+    pub app_version: String,
+    /// Active and archived reminders in the store
+    pub reminders: usize,
+    /// SHA-256 checksum of every other file in the archive, by path
+    pub files: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// How many attachments the backup holds.
+    pub fn attachments(&self) -> usize {
+        self.files.keys().filter(|name| name.starts_with(ATTACHMENTS)).count()
+    }
+}
+
+/// A backup read back and checked, ready to be restored.
+pub struct Backup {
+    pub manifest: Manifest,
+    pub store: ReminderStore,
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+/// What restoring a backup replaced besides the store.
+#[derive(Serialize, Debug, Default)]
+pub struct Restored {
+    pub config: bool,
+    pub attachments: usize,
+    pub audit_log: bool,
+}
+
+/// Write a backup of `store`, with the config file, the attachments and the
+/// audit log as they are now, to `path`.
+pub fn create(store: &ReminderStore, path: &Path) -> Result<Manifest> {
+    let mut files = BTreeMap::new();
+    let json = serde_json::to_vec_pretty(store)
+        .map_err(|e| Error::Parse(format!("Failed to serialize reminders: {}", e)))?;
+    files.insert(STORE.to_string(), json);
+
+    let config = settings::read_file()?;
+    if !config.is_empty() {
+        files.insert(CONFIG.to_string(), config.into_bytes());
+    }
+
+    // Written without encryption, like the store, so another machine can
+    // read it; restoring encrypts it again if encryption is on there
+    let entries = audit::read(None)?;
+    if !entries.is_empty() {
+        let mut log = Vec::new();
+        for entry in &entries {
+            serde_json::to_writer(&mut log, entry)
+                .map_err(|e| Error::Parse(format!("Failed to serialize audit log entry: {}", e)))?;
+            log.push(b'\n');
+        }
+        files.insert(AUDIT_LOG.to_string(), log);
+    }
+
+    let dir = attachments::dir();
+    match fs::read_dir(&dir) {
+        Ok(entries) => {
+            for entry in entries {
+                let entry = entry.map_err(|e| Error::io(format!("Failed to read {}", dir.display()), e))?;
+                if !entry.path().is_file() {
+                    continue;
+                }
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let data = fs::read(entry.path())
+                    .map_err(|e| Error::io(format!("Failed to read {}", entry.path().display()), e))?;
+                files.insert(format!("{}{}", ATTACHMENTS, name), data);
+            }
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(Error::io(format!("Failed to read {}", dir.display()), e)),
+    }
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        created_at: clock::now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        reminders: store.reminders.len() + store.archived.len(),
+        files: files.iter().map(|(name, data)| (name.clone(), checksum(data))).collect(),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| Error::Parse(format!("Failed to serialize the manifest: {}", e)))?;
+
+    let write_error = |e| Error::io(format!("Failed to write {}", path.display()), e);
+    let mut archive = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (name, data) in [(MANIFEST, &manifest_json)].into_iter().chain(files.iter().map(|(name, data)| (name.as_str(), data))) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(manifest.created_at.timestamp().max(0) as u64);
+        header.set_cksum();
+        archive.append_data(&mut header, name, data.as_slice()).map_err(write_error)?;
+    }
+    let bytes = archive.into_inner().and_then(|encoder| encoder.finish()).map_err(write_error)?;
+    storage::write_atomic(path, &bytes)?;
+    Ok(manifest)
+}
+
+/// Read the backup at `path`, checking that it is one this version can
+/// restore and that every file in it is complete and readable.
+pub fn read(path: &Path) -> Result<Backup> {
+    let file = fs::File::open(path).map_err(|e| Error::io(format!("Failed to open {}", path.display()), e))?;
+    let damaged = |problem: String| Error::Parse(format!("{} is damaged or not a backup: {}", path.display(), problem));
+
+    let mut files = BTreeMap::new();
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    for entry in archive.entries().map_err(|e| damaged(e.to_string()))? {
+        let mut entry = entry.map_err(|e| damaged(e.to_string()))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path().map_err(|e| damaged(e.to_string()))?.to_string_lossy().into_owned();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(|e| damaged(e.to_string()))?;
+        files.insert(name, data);
+    }
+
+    let manifest_json = files.remove(MANIFEST).ok_or_else(|| damaged(format!("it has no {}", MANIFEST)))?;
+    let manifest: Manifest = serde_json::from_slice(&manifest_json)
+        .map_err(|e| damaged(format!("{} can't be read: {}", MANIFEST, e)))?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(Error::Invalid(format!(
+            "{} was made by a newer version of reminder ({}, backup format {}); this one reads up to format {}",
+            path.display(), manifest.app_version, manifest.format_version, FORMAT_VERSION
+        )));
+    }
+    for (name, expected) in &manifest.files {
+        let data = files.get(name).ok_or_else(|| damaged(format!("{} is missing", name)))?;
+        if checksum(data) != *expected {
+            return Err(damaged(format!("{} doesn't match its checksum", name)));
+        }
+    }
+    if let Some(name) = files.keys().find(|name| !manifest.files.contains_key(*name)) {
+        return Err(damaged(format!("{} isn't listed in the manifest", name)));
+    }
+
+    let store = storage::parse_store(files.get(STORE).ok_or_else(|| damaged(format!("{} is missing", STORE)))?, STORE)
+        .map_err(|e| match e {
+            Error::Parse(message) => damaged(message),
+            e => e,
+        })?;
+    if let Some(config) = files.get(CONFIG) {
+        let text = String::from_utf8_lossy(config);
+        Config::parse(&text).map_err(|e| damaged(format!("{} can't be read: {}", CONFIG, e.message())))?;
+    }
+    if let Some(log) = files.get(AUDIT_LOG) {
+        for (number, line) in String::from_utf8_lossy(log).lines().enumerate() {
+            serde_json::from_str::<AuditEntry>(line)
+                .map_err(|e| damaged(format!("line {} of {} can't be read: {}", number + 1, AUDIT_LOG, e)))?;
+        }
+    }
+    for name in files.keys().filter_map(|name| name.strip_prefix(ATTACHMENTS)) {
+        if name.is_empty() || name.contains(['/', '\\']) || name == ".." {
+            return Err(damaged(format!("attachment '{}' has an invalid name", name)));
+        }
+    }
+    if let Some(name) = files.keys().find(|name| ![STORE, CONFIG, AUDIT_LOG].contains(&name.as_str()) && !name.starts_with(ATTACHMENTS)) {
+        return Err(damaged(format!("{} isn't part of a backup", name)));
+    }
+
+    Ok(Backup { manifest, store, files })
+}
+
+impl Backup {
+    /// Put the backup's config file, attachments and audit log in place of
+    /// the current ones; the store is left to the caller, to save it through
+    /// its backend. Attachments the backup doesn't have are left alone, and
+    /// with `keep_config` so is the config file.
+    pub fn restore_files(&self, keep_config: bool) -> Result<Restored> {
+        let mut restored = Restored::default();
+        if let (Some(config), false) = (self.files.get(CONFIG), keep_config) {
+            settings::write_file(&String::from_utf8_lossy(config))?;
+            restored.config = true;
+        }
+
+        let dir = attachments::dir();
+        for (name, data) in &self.files {
+            if let Some(name) = name.strip_prefix(ATTACHMENTS) {
+                storage::write_atomic(&dir.join(name), data)?;
+                restored.attachments += 1;
+            }
+        }
+
+        if let Some(log) = self.files.get(AUDIT_LOG) {
+            storage::write_atomic(&audit::get_log_path(), log)?;
+            // Encrypted again if encryption is on
+            audit::rewrite_log()?;
+            restored.audit_log = true;
+        }
+        Ok(restored)
+    }
+}
+
+fn checksum(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
@@ -0,0 +1,45 @@
+// src/clipboard.rs
+use std::env;
+use std::io::ErrorKind;
+use std::process::{Command, Stdio};
+
+use reminder::{Error, Result};
+
+/// The platform's commands that print the clipboard's text, in the order to
+/// try them.
+fn commands() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        vec![("pbpaste", &[])]
+    } else if cfg!(windows) {
+        vec![("powershell", &["-NoProfile", "-Command", "Get-Clipboard -Raw"])]
+    } else {
+        let mut commands: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-paste", &["--no-newline"]));
+        }
+        commands.push(("xclip", &["-selection", "clipboard", "-out"]));
+        commands.push(("xsel", &["--clipboard", "--output"]));
+        commands
+    }
+}
+
+/// Read the text on the system clipboard, using the first clipboard tool
+/// that is installed.
+pub fn read() -> Result<String> {
+    let commands = commands();
+    for (program, args) in &commands {
+        let output = match Command::new(program).args(*args).stdin(Stdio::null()).stderr(Stdio::null()).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(Error::io(format!("Failed to launch '{}'", program), e)),
+        };
+        if !output.status.success() {
+            return Err(Error::Invalid(format!("'{}' could not read the clipboard ({})", program, output.status)));
+        }
+        return String::from_utf8(output.stdout)
+            .map_err(|_| Error::Invalid("The clipboard doesn't hold text".to_string()));
+    }
+
+    let names: Vec<&str> = commands.iter().map(|(program, _)| *program).collect();
+    Err(Error::Invalid(format!("Reading the clipboard needs one of these installed: {}", names.join(", "))))
+}
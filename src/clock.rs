@@ -0,0 +1,93 @@
+// src/clock.rs
+//! The current time as the library sees it. Scheduling, due checks and the
+//! times recorded in the store all ask [`now`] instead of the system clock
+//! directly, so another [`Clock`] can stop or move time, as the `testing`
+//! harness does.
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock, used unless another one is set.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// The system clock moved by a fixed amount: time passes as usual, from a
+/// pretended start.
+pub struct OffsetClock(pub Duration);
+
+impl OffsetClock {
+    /// A clock that reads `now` at this moment.
+    pub fn starting_at(now: DateTime<Utc>) -> Self {
+        OffsetClock(now - Utc::now())
+    }
+}
+
+impl Clock for OffsetClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now() + self.0
+    }
+}
+
+/// A clock that stands still until it is moved.
+#[derive(Clone)]
+pub struct ManualClock(Arc<Mutex<DateTime<Utc>>>);
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        ManualClock(Arc::new(Mutex::new(now)))
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Clock set with [`set`]; the system clock when `None`.
+static CLOCK: RwLock<Option<Arc<dyn Clock>>> = RwLock::new(None);
+
+/// Take the time from `clock` from now on, for the whole process.
+pub fn set(clock: Arc<dyn Clock>) {
+    *CLOCK.write().unwrap_or_else(|e| e.into_inner()) = Some(clock);
+}
+
+/// Run `f` with the time taken from `clock`, then go back to the clock
+/// before.
+pub fn with<T>(clock: Arc<dyn Clock>, f: impl FnOnce() -> T) -> T {
+    let before = CLOCK.write().unwrap_or_else(|e| e.into_inner()).replace(clock);
+    let result = f();
+    *CLOCK.write().unwrap_or_else(|e| e.into_inner()) = before;
+    result
+}
+
+/// Go back to the system clock.
+pub fn reset() {
+    *CLOCK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// The current time.
+pub fn now() -> DateTime<Utc> {
+    match &*CLOCK.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(clock) => clock.now(),
+        None => Utc::now(),
+    }
+}
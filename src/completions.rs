@@ -0,0 +1,91 @@
+// src/completions.rs
+use clap::CommandFactory;
+use clap_complete::env::Shells;
+use clap_complete::{CompletionCandidate, Shell};
+use std::env;
+use std::io;
+use std::path::Path;
+
+use reminder::config::Config;
+use reminder::storage;
+use reminder::{crypto, get_trimmed_content, profile, Error, Reminder, ReminderStore, Result};
+
+use crate::Cli;
+
+/// Environment variable through which the shell asks `reminder` for
+/// completions.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Longest content shown next to an ID while completing.
+const CANDIDATE_HELP_LENGTH: usize = 40;
+
+/// Print the completion script for `shell`. The default script calls back
+/// into `reminder` to complete IDs and tags from the store; `static_script`
+/// prints a standalone script that only knows the commands and flags.
+pub fn print_script(shell: Shell, static_script: bool) -> Result<()> {
+    let mut stdout = io::stdout();
+
+    if static_script {
+        clap_complete::generate(shell, &mut Cli::command(), "reminder", &mut stdout);
+        return Ok(());
+    }
+
+    let completer = env::current_exe()
+        .map_err(|e| Error::io("Failed to locate the reminder executable", e))?;
+    let shells = Shells::builtins();
+    let env_shell = shells.completer(&shell.to_string())
+        .ok_or_else(|| Error::Invalid(format!("Dynamic completion is not supported for {}, use --static", shell)))?;
+    env_shell
+        .write_registration(COMPLETE_VAR, "reminder", "reminder", &completer.to_string_lossy(), &mut stdout)
+        .map_err(|e| Error::io("Failed to write completion script", e))
+}
+
+/// The store as it is on disk, or an empty one when it can't be read;
+/// completion should never fail loudly.
+fn load_store() -> ReminderStore {
+    crypto::disable_prompt();
+    let config = Config::load();
+    if let Some(name) = &config.profile {
+        let _ = profile::select(name);
+    }
+    if let Some(data_file) = env::var_os("REMINDER_DATA_FILE") {
+        let _ = storage::set_data_file(Path::new(&data_file));
+    }
+    storage::open(config.storage, &config).load().unwrap_or_default()
+}
+
+fn id_candidates<'a>(reminders: impl IntoIterator<Item = &'a Reminder>) -> Vec<CompletionCandidate> {
+    reminders.into_iter()
+        .map(|r| {
+            CompletionCandidate::new(r.id.to_string())
+                .help(Some(get_trimmed_content(&r.content, Some(CANDIDATE_HELP_LENGTH)).into()))
+        })
+        .collect()
+}
+
+/// IDs of reminders in rotation, for arguments taking an ID.
+pub fn reminder_ids() -> Vec<CompletionCandidate> {
+    id_candidates(load_store().get_all_reminders())
+}
+
+/// IDs of archived reminders, for `restore`.
+pub fn archived_ids() -> Vec<CompletionCandidate> {
+    id_candidates(load_store().get_archived_reminders())
+}
+
+/// IDs of removed reminders, for `trash restore`.
+pub fn trashed_ids() -> Vec<CompletionCandidate> {
+    id_candidates(load_store().get_trashed_reminders().into_iter().map(|trashed| &trashed.reminder))
+}
+
+/// Tags in use, for `--tag` and `tag`.
+pub fn tags() -> Vec<CompletionCandidate> {
+    load_store().get_tag_counts()
+        .into_iter()
+        .map(|(tag, count)| CompletionCandidate::new(tag).help(Some(format!("{} reminder(s)", count).into())))
+        .collect()
+}
+
+pub fn profiles() -> Vec<CompletionCandidate> {
+    profile::list().into_iter().map(CompletionCandidate::new).collect()
+}
@@ -0,0 +1,691 @@
+// src/config.rs
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::scheduler::{self, Algorithm};
+use crate::storage::StorageKind;
+use crate::{normalize_tag, SortKey};
+
+/// Default spaced repetition ladder, in days: first review after 1 day,
+/// then 3 days, 1 week and 1 month.
+const DEFAULT_INTERVALS: [u32; 4] = [1, 3, 7, 30];
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Config {
+    /// Review intervals in days. The first entry schedules the first review
+    /// after adding; each review moves one stage up the ladder, and the
+    /// reminder is completed once the ladder is exhausted, unless
+    /// `completion` says otherwise.
+    pub intervals: Vec<u32>,
+    /// Scheduling algorithm used for newly added reminders.
+    pub algorithm: Algorithm,
+    /// When a reminder is done with reviewing and gets completed.
+    pub completion: Completion,
+    /// Target probability of recall used by the FSRS scheduler.
+    pub desired_retention: f64,
+    /// Ease factor SM-2 reminders start with.
+    pub starting_ease: f64,
+    /// Scales how much SM-2 raises the ease after a good grade.
+    pub ease_bonus: f64,
+    /// Scales how much SM-2 lowers the ease after a poor grade.
+    pub ease_penalty: f64,
+    /// Every interval any algorithm picks is multiplied by this, e.g. 0.8 to
+    /// review more often.
+    pub interval_modifier: f64,
+    /// Longest interval in days any algorithm may pick. A hundred years when unset.
+    pub max_interval: Option<u32>,
+    /// Share of an interval a review may come due earlier or later, e.g. 0.1
+    /// for ±10%, so reminders added together don't all come due together.
+    pub fuzz: f64,
+    /// Storage backend for the reminder store.
+    pub storage: StorageKind,
+    /// Profile used unless `--profile` picks another.
+    pub profile: Option<String>,
+    /// Deck `add` puts new reminders in unless `--deck` picks another.
+    pub deck: Option<String>,
+    /// Length reminder content is trimmed to when shown, as with `--trim`.
+    pub trim: Option<usize>,
+    /// Print JSON as with `--json`, unless given `--no-json`.
+    pub json: bool,
+    /// Order `list` shows reminders in unless given `--sort`; by priority
+    /// when unset.
+    pub sort: Option<SortKey>,
+    /// How many rotating backups of the JSON store to keep.
+    pub backups: usize,
+    /// How often `reminder daemon` checks for due reminders, e.g. "1m".
+    pub daemon_interval: String,
+    /// Lapses (failed reviews) after which a reminder is flagged as a leech;
+    /// 0 turns leech detection off.
+    pub leech_threshold: u32,
+    /// What happens to a reminder once it is flagged as a leech.
+    pub leech_action: LeechAction,
+    /// Days removed reminders stay in the trash, where `trash restore` can
+    /// bring them back, before they are deleted for good.
+    pub trash_days: u32,
+    /// What `add` does when the new content duplicates a reminder, unless
+    /// given `--force`.
+    pub duplicate_action: DuplicateAction,
+    /// Most reminders `check` and `session` show per day; the rest wait for
+    /// the following days. Unlimited when unset.
+    pub max_daily_reviews: Option<usize>,
+    /// Render Markdown in reminder content, as with `--render-md`.
+    pub render_markdown: bool,
+    /// File whose text fills the editor `add` opens without content, unless
+    /// `--template` picks another.
+    pub add_template: Option<PathBuf>,
+    /// Hour at which a new reviewing day starts. When set, reviews come due
+    /// at the start of the day they fall on instead of at their exact time.
+    pub day_rollover_hour: Option<u32>,
+    /// UTC offset such as "+02:00" to show and read times in instead of the
+    /// system time zone, e.g. to stay on home time while traveling.
+    pub utc_offset: Option<String>,
+    /// When to color the output, unless overridden with `--color`.
+    pub color: ColorChoice,
+    /// Built-in color theme: "default" (for dark terminals) or "light".
+    pub theme: String,
+    /// Colors overriding single entries of the theme.
+    pub colors: ThemeColors,
+    /// Share of reviews `simulate` gives each rating, configured as a
+    /// `[simulate]` table.
+    pub simulate: GradeShares,
+    /// Per-deck overrides, configured as `[decks.<name>]` tables.
+    pub decks: HashMap<String, Policy>,
+    /// Per-tag overrides, configured as `[tags.<name>]` tables; a deck's
+    /// override wins over its reminders' tags.
+    pub tags: HashMap<String, Policy>,
+    /// URLs to POST to when a reminder becomes due, configured as
+    /// `[[webhooks]]` tables.
+    pub webhooks: Vec<WebhookConfig>,
+    /// ntfy topic to publish due reminders to, configured as an `[ntfy]`
+    /// table.
+    pub ntfy: Option<NtfyConfig>,
+    /// Pushover application and user to push due reminders to, configured as
+    /// a `[pushover]` table.
+    pub pushover: Option<PushoverConfig>,
+    /// Gotify server to push due reminders to, configured as a `[gotify]`
+    /// table.
+    pub gotify: Option<GotifyConfig>,
+    /// MQTT broker to publish due reminders to, configured as an `[mqtt]`
+    /// table.
+    pub mqtt: Option<MqttConfig>,
+    /// SMTP settings for `digest --email`, configured as an `[email]` table.
+    pub email: Option<EmailConfig>,
+    /// Bot settings for `reminder telegram`, configured as a `[telegram]` table.
+    pub telegram: Option<TelegramConfig>,
+    /// Git remote for `reminder sync`, configured as a `[sync]` table.
+    pub sync: Option<SyncConfig>,
+    /// Server for `storage = "webdav"`, configured as a `[webdav]` table.
+    pub webdav: Option<WebdavConfig>,
+}
+
+/// When a reminder is completed, configured as `completion = "never"` or
+/// e.g. `completion = { reviews = 6 }`. Recurring reminders never complete.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Completion {
+    /// Once the simple algorithm has gone through its interval ladder; SM-2
+    /// and FSRS reminders never complete
+    #[default]
+    Ladder,
+    /// Never; past the end of the ladder the intervals keep growing
+    Never,
+    /// After this many reviews
+    Reviews(u32),
+    /// Once a review picks an interval of at least this many days
+    Interval(u32),
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LeechAction {
+    /// Only tag the reminder with "leech"
+    #[default]
+    Tag,
+    /// Tag the reminder and pause it until it is resumed
+    Suspend,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateAction {
+    /// Don't add the reminder
+    #[default]
+    Refuse,
+    /// Add it, printing a warning
+    Warn,
+    /// Add it without checking
+    Allow,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Color when printing to a terminal and NO_COLOR is not set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Color names such as "red" or "bright cyan" for each kind of output.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ThemeColors {
+    /// Reminder IDs.
+    pub id: Option<String>,
+    /// Reminders that are due now.
+    pub due: Option<String>,
+    /// When the next review of a reminder that isn't due yet is.
+    pub upcoming: Option<String>,
+    /// Completed reminders.
+    pub completed: Option<String>,
+    pub tags: Option<String>,
+    pub priority: Option<String>,
+}
+
+/// How often each rating is given, relative to the others, e.g. `good =
+/// 0.7` for seven reviews in ten.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct GradeShares {
+    pub again: f64,
+    pub hard: f64,
+    pub good: f64,
+    pub easy: f64,
+}
+
+impl Default for GradeShares {
+    fn default() -> Self {
+        GradeShares { again: 0.1, hard: 0.15, good: 0.65, easy: 0.1 }
+    }
+}
+
+impl GradeShares {
+    fn is_valid(&self) -> bool {
+        let shares = [self.again, self.hard, self.good, self.easy];
+        shares.iter().all(|share| share.is_finite() && *share >= 0.0) && shares.iter().sum::<f64>() > 0.0
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// What the request body looks like, unless a `template` is given.
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Request body with placeholders such as `{id}` and `{content}`; a JSON
+    /// description of the reminder is sent when unset.
+    pub template: Option<String>,
+    /// Content type of the request body.
+    #[serde(default = "default_content_type")]
+    pub content_type: String,
+    /// Only reminders with one of these tags or in one of the `decks` are
+    /// sent here; all of them when both are empty.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub decks: Vec<String>,
+    /// Most reminders listed in one Slack or Discord message; the rest are
+    /// counted at the end.
+    #[serde(default = "default_webhook_max_reminders")]
+    pub max_reminders: usize,
+    /// Characters of each reminder's content shown in a Slack or Discord
+    /// message.
+    #[serde(default = "default_webhook_content_length")]
+    pub content_length: usize,
+    /// How often a failed request is retried before giving up.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+}
+
+/// The body POSTed to a webhook.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// A JSON description of each reminder, with a `text` and a `content`
+    /// field for Slack and Discord to show
+    #[default]
+    Json,
+    /// One Slack message with blocks listing the reminders due together
+    Slack,
+    /// One Discord message with an embed for each reminder due together
+    Discord,
+}
+
+fn default_webhook_max_reminders() -> usize {
+    10
+}
+
+fn default_webhook_content_length() -> usize {
+    200
+}
+
+fn default_content_type() -> String {
+    "application/json".to_string()
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NtfyConfig {
+    /// Topic name; anyone who knows it can read the topic on a public server.
+    pub topic: String,
+    /// ntfy server, for those running their own.
+    #[serde(default = "default_ntfy_server")]
+    pub server: String,
+    /// Access token for a protected topic.
+    pub token: Option<String>,
+    /// User for a protected topic, as an alternative to a token.
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// How often a failed request is retried before giving up.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+}
+
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PushoverConfig {
+    /// API token of the Pushover application the messages come from.
+    pub token: String,
+    /// User or group key of those to notify.
+    pub user: String,
+    /// Only notify this one of the user's devices.
+    pub device: Option<String>,
+    /// One of Pushover's notification sounds, instead of the user's default.
+    pub sound: Option<String>,
+    /// How often a failed request is retried before giving up.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GotifyConfig {
+    /// Address of the Gotify server, e.g. `https://gotify.example.com`.
+    pub url: String,
+    /// Token of the Gotify application the messages come from.
+    pub token: String,
+    /// How often a failed request is retried before giving up.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MqttConfig {
+    /// Host name or address of the broker.
+    pub host: String,
+    /// Port of the broker; 1883, or 8883 with `tls`, when unset.
+    pub port: Option<u16>,
+    /// Connect over TLS, checking the broker's certificate against the
+    /// system's root certificates.
+    #[serde(default)]
+    pub tls: bool,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Start of the topics published to: `<topic>/due` for every reminder,
+    /// and `<topic>/deck/<deck>` and `<topic>/tag/<tag>` for its deck and
+    /// tags.
+    #[serde(default = "default_mqtt_topic")]
+    pub topic: String,
+    /// 0 to publish at most once, 1 at least once and 2 exactly once.
+    #[serde(default = "default_mqtt_qos")]
+    pub qos: u8,
+    /// Keep the last message of each topic on the broker for clients that
+    /// subscribe later.
+    #[serde(default)]
+    pub retain: bool,
+    /// Client ID to connect with; one of this process's own when unset.
+    pub client_id: Option<String>,
+    /// How often a failed connection is retried before giving up.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+}
+
+fn default_mqtt_topic() -> String {
+    "reminder".to_string()
+}
+
+fn default_mqtt_qos() -> u8 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    /// Defaults to the usual port for `security`.
+    pub smtp_port: Option<u16>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS (port 587)
+    #[default]
+    Starttls,
+    /// TLS from the start (port 465)
+    Tls,
+    /// Unencrypted, only for local relays (port 25)
+    None,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TelegramConfig {
+    /// Bot token from @BotFather.
+    pub token: String,
+    /// The only chat the bot talks to; messages from other chats are ignored.
+    pub chat_id: i64,
+    /// Bot API server, for those running their own.
+    #[serde(default = "default_telegram_api_url")]
+    pub api_url: String,
+}
+
+fn default_telegram_api_url() -> String {
+    "https://api.telegram.org".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SyncConfig {
+    /// Any URL or path `git push` accepts.
+    pub remote: String,
+    #[serde(default = "default_sync_branch")]
+    pub branch: String,
+}
+
+fn default_sync_branch() -> String {
+    "main".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebdavConfig {
+    /// URL of the store file itself, e.g.
+    /// `https://cloud.example.com/remote.php/dav/files/me/reminders.json`.
+    pub url: String,
+    pub username: Option<String>,
+    /// Password, or an app password for Nextcloud.
+    pub password: Option<String>,
+    /// Work from the local cache only, without contacting the server.
+    #[serde(default)]
+    pub offline: bool,
+}
+
+/// Scheduling settings overriding the global ones for the reminders in a
+/// deck or with a tag.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Policy {
+    /// Interval ladder used instead of the global one.
+    pub intervals: Option<Vec<u32>>,
+    /// Daily review limit for these reminders, on top of the global one.
+    pub max_daily_reviews: Option<usize>,
+    /// Scheduling algorithm used for newly added reminders.
+    pub algorithm: Option<Algorithm>,
+    /// When these reminders are completed.
+    pub completion: Option<Completion>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            intervals: DEFAULT_INTERVALS.to_vec(),
+            algorithm: Algorithm::default(),
+            completion: Completion::default(),
+            desired_retention: 0.9,
+            starting_ease: scheduler::DEFAULT_EASE_FACTOR,
+            ease_bonus: 1.0,
+            ease_penalty: 1.0,
+            interval_modifier: 1.0,
+            max_interval: None,
+            fuzz: 0.05,
+            storage: StorageKind::default(),
+            profile: None,
+            deck: None,
+            trim: None,
+            json: false,
+            sort: None,
+            backups: 3,
+            daemon_interval: "1m".to_string(),
+            leech_threshold: 8,
+            leech_action: LeechAction::default(),
+            trash_days: 30,
+            duplicate_action: DuplicateAction::default(),
+            max_daily_reviews: None,
+            render_markdown: false,
+            add_template: None,
+            day_rollover_hour: None,
+            utc_offset: None,
+            color: ColorChoice::default(),
+            theme: "default".to_string(),
+            colors: ThemeColors::default(),
+            simulate: GradeShares::default(),
+            decks: HashMap::new(),
+            tags: HashMap::new(),
+            webhooks: Vec::new(),
+            ntfy: None,
+            pushover: None,
+            gotify: None,
+            mqtt: None,
+            email: None,
+            telegram: None,
+            sync: None,
+            webdav: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let file_path = get_config_file_path();
+
+        if !file_path.exists() {
+            return Self::default();
+        }
+
+        let content = match fs::read_to_string(&file_path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Warning: Could not read config file, using defaults: {}", e);
+                return Self::default();
+            }
+        };
+
+        match Self::parse(&content) {
+            Ok((config, warnings)) => {
+                for warning in warnings {
+                    eprintln!("Warning: {}", warning);
+                }
+                config
+            }
+            Err(e) => {
+                eprintln!("Warning: Could not parse config file, using defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Read the text of a config file. Settings that are out of range fall
+    /// back to their defaults and unknown ones are ignored, each with a
+    /// warning returned alongside the config.
+    pub fn parse(content: &str) -> Result<(Self, Vec<String>), toml::de::Error> {
+        let mut warnings = Vec::new();
+        let mut config: Config = serde_ignored::deserialize(toml::Deserializer::new(content), |path| {
+            // Optional tables show up as a '?' part of the path
+            let path = path.to_string().split('.').filter(|part| *part != "?").collect::<Vec<_>>().join(".");
+            warnings.push(format!("Unknown setting '{}' in the config file, ignoring it", path));
+        })?;
+
+        let defaults = Self::default();
+        if !(config.desired_retention > 0.0 && config.desired_retention < 1.0) {
+            warnings.push("desired_retention must be between 0 and 1, using the default".to_string());
+            config.desired_retention = defaults.desired_retention;
+        }
+        if config.intervals.is_empty() {
+            warnings.push("No review intervals configured, using the default ones".to_string());
+            config.intervals = defaults.intervals.clone();
+        }
+        if config.starting_ease < scheduler::MIN_EASE_FACTOR {
+            warnings.push(format!("starting_ease must be at least {}, using the default", scheduler::MIN_EASE_FACTOR));
+            config.starting_ease = defaults.starting_ease;
+        }
+        for (name, value, default) in [
+            ("ease_bonus", &mut config.ease_bonus, defaults.ease_bonus),
+            ("ease_penalty", &mut config.ease_penalty, defaults.ease_penalty),
+        ] {
+            if !value.is_finite() || *value < 0.0 {
+                warnings.push(format!("{} can't be negative, using the default", name));
+                *value = default;
+            }
+        }
+        if !config.interval_modifier.is_finite() || config.interval_modifier <= 0.0 {
+            warnings.push("interval_modifier must be above 0, using the default".to_string());
+            config.interval_modifier = defaults.interval_modifier;
+        }
+        if !(0.0..=0.5).contains(&config.fuzz) {
+            warnings.push("fuzz must be between 0 and 0.5, using the default".to_string());
+            config.fuzz = defaults.fuzz;
+        }
+        if config.day_rollover_hour.is_some_and(|hour| hour > 23) {
+            warnings.push("day_rollover_hour must be between 0 and 23, reviews are due at their exact time".to_string());
+            config.day_rollover_hour = None;
+        }
+        if config.max_interval == Some(0) {
+            warnings.push("max_interval must be at least 1 day, ignoring it".to_string());
+            config.max_interval = None;
+        }
+        if config.trash_days > scheduler::MAX_INTERVAL {
+            warnings.push(format!("trash_days can be at most {}, using the default", scheduler::MAX_INTERVAL));
+            config.trash_days = defaults.trash_days;
+        }
+        if !is_valid_completion(config.completion) {
+            warnings.push("completion needs at least 1 review or day, using the default".to_string());
+            config.completion = defaults.completion;
+        }
+        if !config.simulate.is_valid() {
+            warnings.push("The [simulate] shares can't be negative and need one above 0, using the defaults".to_string());
+            config.simulate = defaults.simulate;
+        }
+
+        for webhook in &mut config.webhooks {
+            if webhook.template.is_some() && webhook.format != WebhookFormat::Json {
+                warnings.push(format!("The webhook {} has both a template and a format, using the template", webhook.url));
+            }
+            if webhook.max_reminders == 0 || webhook.content_length == 0 {
+                warnings.push(format!(
+                    "max_reminders and content_length of the webhook {} must be at least 1, using the defaults",
+                    webhook.url
+                ));
+                webhook.max_reminders = default_webhook_max_reminders();
+                webhook.content_length = default_webhook_content_length();
+            }
+        }
+
+        if let Some(mqtt) = &mut config.mqtt {
+            if mqtt.qos > 2 {
+                warnings.push("The [mqtt] qos must be 0, 1 or 2, using 1".to_string());
+                mqtt.qos = default_mqtt_qos();
+            }
+            let topic = mqtt.topic.trim_matches('/');
+            if topic.is_empty() || topic.contains(['+', '#']) {
+                warnings.push("The [mqtt] topic can't be empty or hold wildcards, using \"reminder\"".to_string());
+                mqtt.topic = default_mqtt_topic();
+            } else {
+                mqtt.topic = topic.to_string();
+            }
+        }
+
+        // Tags are matched the way reminders store them
+        config.tags = config.tags.into_iter().map(|(name, policy)| (normalize_tag(&name), policy)).collect();
+        for webhook in &mut config.webhooks {
+            webhook.tags = webhook.tags.iter().map(|tag| normalize_tag(tag)).collect();
+        }
+        let policies = config.decks.iter_mut().map(|(name, policy)| ("deck", name, policy))
+            .chain(config.tags.iter_mut().map(|(name, policy)| ("tag", name, policy)));
+        for (kind, name, policy) in policies {
+            if policy.intervals.as_ref().is_some_and(|intervals| intervals.is_empty()) {
+                warnings.push(format!("No review intervals configured for {} '{}', using global ones", kind, name));
+                policy.intervals = None;
+            }
+            if policy.completion.is_some_and(|completion| !is_valid_completion(completion)) {
+                warnings.push(format!("completion for {} '{}' needs at least 1 review or day, using the global one", kind, name));
+                policy.completion = None;
+            }
+        }
+
+        Ok((config, warnings))
+    }
+
+    /// The policies for reminders in `deck` with `tags`, in the order they
+    /// win: the deck's, then the tags' in alphabetical order.
+    fn policies<'a>(&'a self, deck: Option<&str>, tags: &[String]) -> impl Iterator<Item = &'a Policy> {
+        let mut tags: Vec<String> = tags.iter().map(|tag| normalize_tag(tag)).collect();
+        tags.sort();
+        deck.and_then(|deck| self.decks.get(deck))
+            .into_iter()
+            .chain(tags.into_iter().filter_map(|tag| self.tags.get(&tag)))
+    }
+
+    /// The interval ladder for reminders in `deck` with `tags`, falling back
+    /// to the global ladder.
+    pub fn intervals_for(&self, deck: Option<&str>, tags: &[String]) -> &[u32] {
+        self.policies(deck, tags)
+            .find_map(|policy| policy.intervals.as_deref())
+            .unwrap_or(&self.intervals)
+    }
+
+    /// Algorithm for new reminders in `deck` with `tags`, falling back to the
+    /// global one.
+    pub fn algorithm_for(&self, deck: Option<&str>, tags: &[String]) -> Algorithm {
+        self.policies(deck, tags)
+            .find_map(|policy| policy.algorithm)
+            .unwrap_or(self.algorithm)
+    }
+
+    /// When reminders in `deck` with `tags` are completed, falling back to
+    /// the global policy.
+    pub fn completion_for(&self, deck: Option<&str>, tags: &[String]) -> Completion {
+        self.policies(deck, tags)
+            .find_map(|policy| policy.completion)
+            .unwrap_or(self.completion)
+    }
+
+    /// Daily review limit for reminders in `deck`, if any.
+    pub fn deck_daily_limit(&self, deck: &str) -> Option<usize> {
+        self.decks.get(deck).and_then(|deck| deck.max_daily_reviews)
+    }
+
+    /// Daily review limit for reminders tagged `tag`, if any.
+    pub fn tag_daily_limit(&self, tag: &str) -> Option<usize> {
+        self.tags.get(tag).and_then(|tag| tag.max_daily_reviews)
+    }
+
+    /// Interval before the first review of a newly added reminder.
+    pub fn first_interval(&self, deck: Option<&str>, tags: &[String]) -> u32 {
+        self.intervals_for(deck, tags)[0]
+    }
+}
+
+fn is_valid_completion(completion: Completion) -> bool {
+    !matches!(completion, Completion::Reviews(0) | Completion::Interval(0))
+}
+
+pub fn get_config_file_path() -> PathBuf {
+    let mut path = dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from(".")); // Fallback to current directory if config_dir is not available
+    path.push("reminder");
+    path.push("config.toml");
+    path
+}
@@ -0,0 +1,153 @@
+// src/crypto.rs
+//! Passphrase encryption of the files in the data directory: the store, its
+//! backups and the undo journal. Files are encrypted with AES-256-GCM under a
+//! key derived from the passphrase with Argon2id, using a fresh salt and nonce
+//! on every write.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::error::{Error, Result};
+
+/// Environment variable holding the passphrase, for scripts and services
+/// that can't answer a prompt.
+pub const PASSPHRASE_VAR: &str = "REMINDER_PASSPHRASE";
+
+/// Start of every encrypted file, followed by the salt, the nonce and the
+/// ciphertext.
+const MAGIC: &[u8] = b"reminder-encrypted-v1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Passphrase of this invocation, once it was entered or set.
+static PASSPHRASE: Mutex<Option<String>> = Mutex::new(None);
+/// Whether files are written encrypted.
+static ENCRYPTING: AtomicBool = AtomicBool::new(false);
+/// Whether asking for the passphrase on the terminal is allowed.
+static PROMPT: AtomicBool = AtomicBool::new(true);
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Whether the file at `path` is encrypted, judging by its first bytes.
+pub fn is_encrypted_file(path: &Path) -> bool {
+    let mut start = [0u8; MAGIC.len()];
+    File::open(path).and_then(|mut file| file.read_exact(&mut start)).is_ok() && is_encrypted(&start)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key<Aes256Gcm>> {
+    let mut key = Key::<Aes256Gcm>::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| Error::Invalid(format!("Failed to derive the encryption key: {}", e)))?;
+    Ok(key)
+}
+
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(&derive_key(passphrase, &salt)?)
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| Error::Invalid("Failed to encrypt".to_string()))?;
+
+    Ok([MAGIC, &salt, &nonce, &ciphertext].concat())
+}
+
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let body = data.strip_prefix(MAGIC)
+        .filter(|body| body.len() >= SALT_LEN + NONCE_LEN)
+        .ok_or_else(|| Error::Parse("Not an encrypted reminder file".to_string()))?;
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    Aes256Gcm::new(&derive_key(passphrase, salt)?)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| Error::Invalid("Wrong passphrase, or the encrypted file was damaged".to_string()))
+}
+
+/// The passphrase of this invocation: the one already entered, else
+/// `REMINDER_PASSPHRASE`, else one asked for on the terminal.
+pub fn passphrase() -> Result<String> {
+    let mut cached = PASSPHRASE.lock().expect("Passphrase lock poisoned");
+    if let Some(passphrase) = cached.as_ref() {
+        return Ok(passphrase.clone());
+    }
+
+    let passphrase = match std::env::var(PASSPHRASE_VAR) {
+        Ok(passphrase) => passphrase,
+        Err(_) if PROMPT.load(Ordering::Relaxed) => {
+            rpassword::prompt_password("Passphrase for the reminder store: ").map_err(|e| {
+                Error::io(format!("The store is encrypted; set {} or run from a terminal", PASSPHRASE_VAR), e)
+            })?
+        }
+        Err(_) => return Err(Error::Invalid(format!("The store is encrypted; set {} to read it", PASSPHRASE_VAR))),
+    };
+    *cached = Some(passphrase.clone());
+    Ok(passphrase)
+}
+
+/// Ask for a new passphrase twice, unless `REMINDER_PASSPHRASE` provides it.
+pub fn new_passphrase() -> Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_VAR) {
+        return Ok(passphrase);
+    }
+
+    let prompt_error = |e| Error::io("Failed to read the passphrase", e);
+    let passphrase = rpassword::prompt_password("New passphrase: ").map_err(prompt_error)?;
+    if passphrase.is_empty() {
+        return Err(Error::Invalid("The passphrase cannot be empty".to_string()));
+    }
+    if rpassword::prompt_password("Repeat the passphrase: ").map_err(prompt_error)? != passphrase {
+        return Err(Error::Invalid("The passphrases don't match".to_string()));
+    }
+    Ok(passphrase)
+}
+
+/// Use `passphrase` for this invocation instead of asking for one.
+pub fn set_passphrase(passphrase: String) {
+    *PASSPHRASE.lock().expect("Passphrase lock poisoned") = Some(passphrase);
+}
+
+/// Turn encryption of written files on or off for this invocation. It is
+/// turned on by loading an encrypted store.
+pub fn set_encrypting(encrypting: bool) {
+    ENCRYPTING.store(encrypting, Ordering::Relaxed);
+}
+
+pub fn is_encrypting() -> bool {
+    ENCRYPTING.load(Ordering::Relaxed)
+}
+
+/// Fail instead of asking on the terminal when the passphrase is needed,
+/// e.g. while completing a command line.
+pub fn disable_prompt() {
+    PROMPT.store(false, Ordering::Relaxed);
+}
+
+/// The content of a file as read from disk, decrypted if it is encrypted.
+pub fn open(data: Vec<u8>) -> Result<Vec<u8>> {
+    if is_encrypted(&data) {
+        decrypt(&data, &passphrase()?)
+    } else {
+        Ok(data)
+    }
+}
+
+/// The content to write to disk for `plaintext`, encrypted if encryption is
+/// on.
+pub fn seal(plaintext: &[u8]) -> Result<Vec<u8>> {
+    if is_encrypting() {
+        encrypt(plaintext, &passphrase()?)
+    } else {
+        Ok(plaintext.to_vec())
+    }
+}
@@ -0,0 +1,53 @@
+// src/daemon.rs
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::thread;
+
+use crate::notify::{self, Notifier};
+use reminder::storage::Storage;
+use reminder::{clock, dates, lock};
+
+/// Poll the store every `interval` and announce each reminder that has become
+/// due through `notifiers`. A reminder is notified once per due time, so
+/// it is only announced again after it has been reviewed or snoozed and comes
+/// due once more.
+pub fn run(storage: &mut dyn Storage, interval: Duration, notifiers: &[Box<dyn Notifier>]) {
+    let sleep_for = interval.to_std()
+        .unwrap_or(std::time::Duration::from_secs(60));
+    let mut notified: HashSet<(u32, DateTime<Utc>)> = HashSet::new();
+
+    println!("Watching for due reminders every {} seconds (Ctrl+C to stop)", interval.num_seconds());
+
+    loop {
+        // A failed load is retried on the next tick rather than stopping the daemon
+        let store = match lock::acquire().and_then(|_lock| storage.load()) {
+            Ok(store) => store,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to load the store, retrying on the next tick");
+                eprintln!("Error: {}", e);
+                thread::sleep(sleep_for);
+                continue;
+            }
+        };
+        let due_reminders = store.get_due_reminders();
+        tracing::info!(due = due_reminders.len(), already_notified = notified.len(), "checked for due reminders");
+
+        let newly_due: Vec<_> = due_reminders.iter()
+            .copied()
+            .filter(|reminder| notified.insert((reminder.id, reminder.next_review)))
+            .collect();
+        for reminder in &newly_due {
+            println!("[{}] Reminder {} is due", dates::local(clock::now()).format("%Y-%m-%d %H:%M"), reminder.id);
+        }
+        if !newly_due.is_empty() {
+            notify::notify_all(notifiers, &newly_due);
+        }
+
+        // Forget reminders that are no longer due so the set stays small
+        notified.retain(|(id, next_review)| {
+            due_reminders.iter().any(|r| r.id == *id && r.next_review == *next_review)
+        });
+
+        thread::sleep(sleep_for);
+    }
+}
@@ -0,0 +1,261 @@
+// src/dates.rs
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use std::sync::OnceLock;
+
+use crate::{clock, duration};
+use crate::error::{Error, Result};
+
+/// Fixed UTC offset times are shown and entered in, when the config sets one
+/// instead of using the system time zone.
+static UTC_OFFSET: OnceLock<FixedOffset> = OnceLock::new();
+
+/// Hour of the day reviewing days start at, when the config schedules by day.
+static DAY_ROLLOVER: OnceLock<u32> = OnceLock::new();
+
+/// Show and read times at `offset` from UTC instead of in the system time
+/// zone, e.g. to stay on home time while traveling.
+pub fn set_utc_offset(offset: FixedOffset) {
+    let _ = UTC_OFFSET.set(offset);
+}
+
+/// Parse a UTC offset such as `+02:00`, `-0530`, `+9` or `UTC`.
+pub fn parse_utc_offset(input: &str) -> Result<FixedOffset> {
+    let input = input.trim();
+    let invalid = || Error::Parse(format!("Invalid UTC offset '{}' (try \"+02:00\", \"-05:30\" or \"UTC\")", input));
+    if input.eq_ignore_ascii_case("utc") || input.eq_ignore_ascii_case("z") {
+        return Ok(FixedOffset::east_opt(0).expect("Zero is a valid offset"));
+    }
+
+    let (sign, rest) = match input.split_at_checked(1) {
+        Some(("+", rest)) => (1, rest),
+        Some(("-", rest)) => (-1, rest),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes >= 60 {
+        return Err(invalid());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+/// A stored time as the wall-clock time it is where the user is: in the
+/// configured UTC offset, or else the system time zone.
+pub fn local(time: DateTime<Utc>) -> DateTime<FixedOffset> {
+    match UTC_OFFSET.get() {
+        Some(offset) => time.with_timezone(offset),
+        None => time.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+/// Count reviews in whole days starting at `hour` o'clock, instead of at
+/// the exact times they come due.
+pub fn set_day_rollover(hour: u32) {
+    let _ = DAY_ROLLOVER.set(hour);
+}
+
+/// The hour reviewing days start at, when reviews are scheduled by day.
+pub fn day_rollover() -> Option<u32> {
+    DAY_ROLLOVER.get().copied()
+}
+
+/// The day `time` counts towards where the user is. With a day rollover hour
+/// the times before it still belong to the day before.
+pub fn day_of(time: DateTime<Utc>) -> NaiveDate {
+    let hours = day_rollover().unwrap_or(0);
+    (local(time) - Duration::hours(hours as i64)).date_naive()
+}
+
+/// Today's date where the user is.
+pub fn today() -> NaiveDate {
+    day_of(clock::now())
+}
+
+/// Whether something due at `due` is due at `now`: once `due` has passed, or
+/// with a day rollover hour as soon as the day `due` falls on has begun.
+pub fn is_due(due: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    match day_rollover() {
+        Some(_) => day_of(due) <= day_of(now),
+        None => due <= now,
+    }
+}
+
+/// The time a wall-clock time where the user is stands for, or `None` when
+/// the clocks skip it. A time the clocks pass twice means the first one.
+pub fn from_local(datetime: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match UTC_OFFSET.get() {
+        Some(offset) => offset.from_local_datetime(&datetime).earliest().map(|time| time.with_timezone(&Utc)),
+        None => Local.from_local_datetime(&datetime).earliest().map(|time| time.with_timezone(&Utc)),
+    }
+}
+
+/// Parse when something should happen, relative to `now`. Accepts:
+///
+/// - relative durations: `in 2 weeks`, `3 days from now`, `2h`
+/// - days: `today`, `tomorrow`, `monday`, `next friday`, `next week`
+/// - times of day, alone or after a day: `9am`, `tomorrow 18:30`, `next monday at 9am`
+/// - absolute dates: `2024-03-01`, `2024-03-01 09:00`, RFC 3339 timestamps
+///
+/// A day without a time means the start of that day.
+pub fn parse_when(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err(Error::Parse("Date cannot be empty".to_string()));
+    }
+
+    if let Some(datetime) = parse_absolute(&input) {
+        return Ok(datetime);
+    }
+
+    if let Some(relative) = parse_relative(&input) {
+        return relative.and_then(|duration| {
+            now.checked_add_signed(duration)
+                .ok_or_else(|| Error::Parse(format!("The date '{}' is too far away", input)))
+        });
+    }
+
+    parse_day_and_time(&input, now)
+        .ok_or_else(|| Error::Parse(format!(
+            "Could not understand date '{}' (try \"tomorrow 9am\", \"in 2 weeks\" or 2024-03-01)",
+            input
+        )))
+}
+
+/// A point in time given as a duration ago, e.g. `2w`, or as a date.
+pub fn parse_since(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    match duration::parse_duration(input) {
+        Ok(ago) => now.checked_sub_signed(ago)
+            .ok_or_else(|| Error::Invalid(format!("'{}' reaches before the first date reminder can show", input))),
+        Err(_) => parse_when(input, now),
+    }
+}
+
+fn parse_absolute(input: &str) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dt%H:%M", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(input, format) {
+            return from_local(datetime);
+        }
+    }
+
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| from_local(date.and_time(NaiveTime::MIN)))
+}
+
+/// `in <duration>`, `<duration> from now` or a bare duration such as `3d`.
+fn parse_relative(input: &str) -> Option<Result<Duration>> {
+    let spec = input.strip_prefix("in ")
+        .or_else(|| input.strip_suffix(" from now"));
+
+    match spec {
+        Some(spec) => {
+            // "in a week", "in an hour"
+            let spec = spec.trim();
+            let spec = spec.strip_prefix("an ")
+                .or_else(|| spec.strip_prefix("a "))
+                .map(|rest| format!("1 {}", rest))
+                .unwrap_or_else(|| spec.to_string());
+            Some(duration::parse_duration(&spec))
+        }
+        None => duration::parse_duration(input).ok().map(Ok),
+    }
+}
+
+fn parse_day_and_time(input: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    // Glue "9 am" into "9am" and drop filler words
+    let normalized = input.replace(" am", "am").replace(" pm", "pm");
+    let mut tokens: Vec<&str> = normalized.split_whitespace()
+        .filter(|token| *token != "at" && *token != "on")
+        .collect();
+
+    let time = match tokens.last().and_then(|token| parse_time(token)) {
+        Some(time) => {
+            tokens.pop();
+            Some(time)
+        }
+        None => None,
+    };
+
+    let today = local(now).date_naive();
+    let date = match tokens.as_slice() {
+        [] => {
+            // A time on its own means the next time the clock shows it
+            let time = time?;
+            let candidate = from_local(today.and_time(time))?;
+            if candidate > now {
+                return Some(candidate);
+            }
+            return from_local((today + Duration::days(1)).and_time(time));
+        }
+        ["today"] => today,
+        ["tomorrow"] => today + Duration::days(1),
+        ["next", "week"] => today + Duration::weeks(1),
+        ["next", "month"] => today + Duration::days(30),
+        ["next", day] | ["this", day] | [day] => next_weekday(today, parse_weekday(day)?),
+        _ => return None,
+    };
+
+    from_local(date.and_time(time.unwrap_or(NaiveTime::MIN)))
+}
+
+/// The first date after today falling on `weekday`, so "monday" said on a
+/// Monday means a week from today.
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days_ahead = (weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64)
+        .rem_euclid(7);
+    today + Duration::days(if days_ahead == 0 { 7 } else { days_ahead })
+}
+
+fn parse_weekday(token: &str) -> Option<Weekday> {
+    match token {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// `9am`, `9:30pm`, `21:00`, `noon`, `midnight`.
+fn parse_time(token: &str) -> Option<NaiveTime> {
+    match token {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return Some(NaiveTime::MIN),
+        _ => {}
+    }
+
+    let (clock, offset) = if let Some(clock) = token.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = token.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (token, None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        // A bare number is only a time with am/pm, otherwise it's ambiguous
+        None if offset.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
@@ -0,0 +1,151 @@
+// src/diff.rs
+//! What a command changed in the store, reminder by reminder and field by
+//! field, so `--dry-run` can show it instead of saving; and what changed
+//! between two versions of a reminder's content, line by line.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+use crate::ReminderStore;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct StoreDiff {
+    pub added: Vec<u32>,
+    pub removed: Vec<u32>,
+    /// Reminders moved from the active reminders to the archive
+    pub archived: Vec<u32>,
+    /// Reminders moved from the archive back to the active reminders
+    pub restored: Vec<u32>,
+    /// Reminders in both stores whose fields differ
+    pub changed: Vec<ReminderDiff>,
+    /// Other parts of the store that differ, such as `links` or `vacations`
+    pub other: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReminderDiff {
+    pub id: u32,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// One line of two texts compared with [`lines`].
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "change", content = "line", rename_all = "lowercase")]
+pub enum LineChange {
+    Kept(String),
+    Removed(String),
+    Added(String),
+}
+
+impl StoreDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.archived.is_empty() && self.restored.is_empty()
+            && self.changed.is_empty() && self.other.is_empty()
+    }
+}
+
+/// Compare two versions of a store. Fields are compared as they are saved,
+/// so every change that would reach the store file shows up.
+pub fn diff(before: &ReminderStore, after: &ReminderStore) -> StoreDiff {
+    let before_json = to_object(before);
+    let after_json = to_object(after);
+    let mut diff = StoreDiff::default();
+
+    let ids: BTreeSet<u32> = [before, after].iter()
+        .flat_map(|store| store.reminders.keys().chain(store.archived.keys()))
+        .copied()
+        .collect();
+    for id in ids {
+        let old = before.reminders.get(&id).map(|_| false).or(before.archived.get(&id).map(|_| true));
+        let new = after.reminders.get(&id).map(|_| false).or(after.archived.get(&id).map(|_| true));
+        match (old, new) {
+            (None, Some(_)) => diff.added.push(id),
+            (Some(_), None) => diff.removed.push(id),
+            (Some(old_archived), Some(new_archived)) => {
+                if !old_archived && new_archived {
+                    diff.archived.push(id);
+                } else if old_archived && !new_archived {
+                    diff.restored.push(id);
+                }
+                let field = |json: &Map<String, Value>, archived: bool| {
+                    let section = if archived { "archived" } else { "reminders" };
+                    json[section][id.to_string()].as_object().cloned().unwrap_or_default()
+                };
+                let fields = diff_fields(&field(&before_json, old_archived), &field(&after_json, new_archived));
+                if !fields.is_empty() {
+                    diff.changed.push(ReminderDiff { id, fields });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    // A new `next_id` only follows from added reminders
+    for (key, value) in &after_json {
+        if !["reminders", "archived", "next_id"].contains(&key.as_str()) && before_json.get(key) != Some(value) {
+            diff.other.push(key.clone());
+        }
+    }
+    diff
+}
+
+fn to_object(store: &ReminderStore) -> Map<String, Value> {
+    match serde_json::to_value(store).expect("The store always serializes") {
+        Value::Object(object) => object,
+        _ => Map::new(),
+    }
+}
+
+fn diff_fields(before: &Map<String, Value>, after: &Map<String, Value>) -> Vec<FieldChange> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let old = before.get(key).cloned().unwrap_or(Value::Null);
+            let new = after.get(key).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| FieldChange { field: key.clone(), before: old, after: new })
+        })
+        .collect()
+}
+
+/// The lines of `before` and `after`, with the ones only one of them has
+/// marked as removed or added. The lines both keep are as many as can be.
+pub fn lines(before: &str, after: &str) -> Vec<LineChange> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    // kept[i][j]: most lines kept between old[i..] and new[j..]
+    let mut kept = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            kept[i][j] = if old[i] == new[j] {
+                kept[i + 1][j + 1] + 1
+            } else {
+                kept[i + 1][j].max(kept[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut changes = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            changes.push(LineChange::Kept(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || kept[i + 1][j] >= kept[i][j + 1]) {
+            changes.push(LineChange::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            changes.push(LineChange::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    changes
+}
@@ -0,0 +1,143 @@
+// src/digest.rs
+use chrono::{DateTime, Duration, Utc};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use regex::Regex;
+use serde::Serialize;
+
+use reminder::config::{EmailConfig, SmtpSecurity};
+use reminder::{dates, Error, Reminder, ReminderStore, Result};
+
+/// Summary of what is due now and what comes due over the next days.
+#[derive(Serialize)]
+pub struct Digest<'a> {
+    pub due: Vec<&'a Reminder>,
+    pub upcoming: Vec<&'a Reminder>,
+    #[serde(skip)]
+    days: i64,
+}
+
+impl<'a> Digest<'a> {
+    pub fn collect(store: &'a ReminderStore, now: DateTime<Utc>, days: i64) -> Self {
+        let until = now + Duration::days(days);
+        let mut upcoming: Vec<&Reminder> = store.get_all_reminders()
+            .into_iter()
+            .filter(|r| r.is_scheduled() && !r.is_due(now) && r.next_review <= until)
+            .collect();
+        upcoming.sort_by_key(|r| (r.next_review, r.id));
+
+        Digest { due: store.get_due_reminders(), upcoming, days }
+    }
+
+    pub fn subject(&self) -> String {
+        format!("Reminders: {} due, {} coming up", self.due.len(), self.upcoming.len())
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        text.push_str(&format!("Due for review ({}):\n", self.due.len()));
+        if self.due.is_empty() {
+            text.push_str("  Nothing, well done!\n");
+        }
+        for reminder in &self.due {
+            text.push_str(&format!("  [{}] {}\n", reminder.id, reminder.content.replace('\n', "\n      ")));
+        }
+
+        text.push_str(&format!("\nComing up in the next {} days ({}):\n", self.days, self.upcoming.len()));
+        for reminder in &self.upcoming {
+            text.push_str(&format!(
+                "  {} [{}] {}\n",
+                dates::local(reminder.next_review).format("%a %d %b %H:%M"),
+                reminder.id,
+                reminder.content.replace('\n', "\n      ")
+            ));
+        }
+        text
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = String::from("<html><body style=\"font-family: sans-serif\">\n");
+        html.push_str(&format!("<h2>Due for review ({})</h2>\n", self.due.len()));
+        if self.due.is_empty() {
+            html.push_str("<p>Nothing, well done!</p>\n");
+        } else {
+            html.push_str("<ul>\n");
+            for reminder in &self.due {
+                html.push_str(&format!("<li><b>{}</b> {}</li>\n", reminder.id, html_content(&reminder.content)));
+            }
+            html.push_str("</ul>\n");
+        }
+
+        html.push_str(&format!("<h2>Coming up in the next {} days ({})</h2>\n", self.days, self.upcoming.len()));
+        if !self.upcoming.is_empty() {
+            html.push_str("<table cellpadding=\"4\">\n");
+            for reminder in &self.upcoming {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td><b>{}</b></td><td>{}</td></tr>\n",
+                    dates::local(reminder.next_review).format("%a %d %b %H:%M"),
+                    reminder.id,
+                    html_content(&reminder.content)
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body></html>\n");
+        html
+    }
+}
+
+/// Escape reminder content for HTML, turning URLs into links and line breaks
+/// into `<br>`.
+fn html_content(content: &str) -> String {
+    let url = Regex::new(r"https?://[^\s<>]+").expect("URL pattern is valid");
+
+    let mut html = String::with_capacity(content.len());
+    let mut last = 0;
+    for found in url.find_iter(content) {
+        html.push_str(&escape_html(&content[last..found.start()]));
+        let href = escape_html(found.as_str());
+        html.push_str(&format!("<a href=\"{}\">{}</a>", href, href));
+        last = found.end();
+    }
+    html.push_str(&escape_html(&content[last..]));
+    html.replace('\n', "<br>")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Send the digest as a multipart plain-text and HTML email.
+pub fn send_email(digest: &Digest, config: &EmailConfig) -> Result<()> {
+    let address = |value: &str| {
+        value.parse::<Mailbox>()
+            .map_err(|e| Error::Invalid(format!("Invalid email address '{}': {}", value, e)))
+    };
+
+    let message = Message::builder()
+        .from(address(&config.from)?)
+        .to(address(&config.to)?)
+        .subject(digest.subject())
+        .multipart(MultiPart::alternative_plain_html(digest.to_text(), digest.to_html()))
+        .map_err(|e| Error::Invalid(format!("Failed to build email: {}", e)))?;
+
+    let smtp_error = |e: lettre::transport::smtp::Error| Error::Notify(format!("Failed to send email: {}", e));
+    let mut transport = match config.security {
+        SmtpSecurity::Starttls => SmtpTransport::starttls_relay(&config.smtp_host).map_err(smtp_error)?,
+        SmtpSecurity::Tls => SmtpTransport::relay(&config.smtp_host).map_err(smtp_error)?,
+        SmtpSecurity::None => SmtpTransport::builder_dangerous(&config.smtp_host),
+    };
+    if let Some(port) = config.smtp_port {
+        transport = transport.port(port);
+    }
+    if let (Some(username), Some(password)) = (&config.username, &config.password) {
+        transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+
+    transport.build().send(&message).map_err(smtp_error)?;
+    Ok(())
+}
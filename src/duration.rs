@@ -0,0 +1,88 @@
+// src/duration.rs
+use chrono::Duration;
+
+use crate::error::{Error, Result};
+
+/// Parse a human-friendly duration such as `90s`, `30m`, `2h`, `3d`, `1w`,
+/// `2mo`, `1y`, or a combination like `1d12h`. Months count as 30 days and
+/// years as 365 days.
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err(Error::Parse("Duration cannot be empty".to_string()));
+    }
+
+    let mut total = Duration::zero();
+    let mut rest = input.as_str();
+
+    while !rest.is_empty() {
+        let digits_end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits_end == 0 {
+            return Err(Error::Parse(format!("Invalid duration '{}': expected a number", input)));
+        }
+        let amount: i64 = rest[..digits_end].parse()
+            .map_err(|_| Error::Parse(format!("Invalid duration '{}': number too large", input)))?;
+        rest = &rest[digits_end..];
+
+        let unit_end = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let unit = rest[..unit_end].trim();
+        rest = &rest[unit_end..];
+
+        let part = match unit {
+            "s" | "sec" | "secs" | "second" | "seconds" => Duration::try_seconds(amount),
+            "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(amount),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(amount),
+            "d" | "day" | "days" => Duration::try_days(amount),
+            "w" | "week" | "weeks" => Duration::try_weeks(amount),
+            "mo" | "month" | "months" => amount.checked_mul(30).and_then(Duration::try_days),
+            "y" | "year" | "years" => amount.checked_mul(365).and_then(Duration::try_days),
+            "" => {
+                return Err(Error::Parse(format!("Invalid duration '{}': missing unit (e.g. 2h, 3d, 1w)", input)))
+            }
+            other => return Err(Error::Parse(format!("Invalid duration '{}': unknown unit '{}'", input, other))),
+        };
+        total = part.and_then(|part| total.checked_add(&part))
+            .ok_or_else(|| Error::Parse(format!("Invalid duration '{}': duration too large", input)))?;
+    }
+
+    Ok(total)
+}
+
+/// Parse an interval ladder such as `1d,4d,2w,2m` into days. Plain numbers
+/// are days, and as a ladder only counts whole days, `m` means months here
+/// like `mo`.
+pub fn parse_intervals(input: &str) -> Result<Vec<u32>> {
+    let mut intervals = Vec::new();
+    for part in input.split(',').map(str::trim) {
+        let part = part.to_lowercase();
+        let part = if part.ends_with('m') && part[..part.len() - 1].ends_with(|c: char| c.is_ascii_digit()) {
+            format!("{}o", part)
+        } else if !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) {
+            format!("{}d", part)
+        } else {
+            part
+        };
+        let duration = parse_duration(&part)?;
+        if duration.num_seconds() % 86400 != 0 || duration.num_days() < 1 {
+            return Err(Error::Parse(format!("Interval '{}' is not a whole number of days", part)));
+        }
+        let days = u32::try_from(duration.num_days())
+            .map_err(|_| Error::Parse(format!("Interval '{}' is too long", part)))?;
+        intervals.push(days);
+    }
+    Ok(intervals)
+}
+
+/// Write an interval ladder the way [`parse_intervals`] reads it, in the
+/// largest unit that fits each interval, e.g. `1d,4d,2w,2mo`.
+pub fn format_intervals(intervals: &[u32]) -> String {
+    let parts: Vec<String> = intervals.iter()
+        .map(|&days| match days {
+            days if days % 365 == 0 => format!("{}y", days / 365),
+            days if days % 30 == 0 => format!("{}mo", days / 30),
+            days if days % 7 == 0 => format!("{}w", days / 7),
+            days => format!("{}d", days),
+        })
+        .collect();
+    parts.join(",")
+}
@@ -0,0 +1,40 @@
+// src/editor.rs
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use reminder::{Error, Result};
+
+/// Open the user's editor (`$VISUAL`, then `$EDITOR`, falling back to `vi`)
+/// on a temporary file pre-filled with `initial`, and return what was saved.
+pub fn edit_text(initial: &str) -> Result<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    // Editors like "code --wait" come with their own arguments
+    let mut parts = editor.split_whitespace();
+    let program = parts.next()
+        .ok_or_else(|| Error::Invalid("Editor command is empty".to_string()))?;
+
+    let file_path = env::temp_dir().join(format!("reminder-{}.txt", std::process::id()));
+    fs::write(&file_path, initial)
+        .map_err(|e| Error::io(format!("Failed to write {}", file_path.display()), e))?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(&file_path)
+        .status();
+
+    let result = match status {
+        Ok(status) if status.success() => fs::read_to_string(&file_path)
+            .map_err(|e| Error::io(format!("Failed to read {}", file_path.display()), e)),
+        Ok(status) => Err(Error::Invalid(format!("Editor exited with {}", status))),
+        Err(e) => Err(Error::io(format!("Failed to launch editor '{}'", program), e)),
+    };
+
+    let _ = fs::remove_file(&file_path);
+
+    // Editors usually append a final newline that isn't part of the content
+    result.map(|content| content.trim_end_matches(['\n', '\r']).to_string())
+}
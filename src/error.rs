@@ -0,0 +1,78 @@
+// src/error.rs
+use std::io;
+use std::path::PathBuf;
+
+/// Exit status when a reminder, deck or undo entry doesn't exist.
+pub const EXIT_NOT_FOUND: i32 = 3;
+/// Exit status when reading or writing files, the database or remote storage
+/// fails.
+pub const EXIT_IO: i32 = 4;
+/// Exit status when input or stored data cannot be parsed.
+pub const EXIT_PARSE: i32 = 5;
+/// Exit status when another invocation kept the store locked.
+pub const EXIT_LOCKED: i32 = 6;
+/// Exit status for failures that don't fit a more specific class; not 1,
+/// which `count` and `check --quiet` return when something is due.
+pub const EXIT_FAILURE: i32 = 7;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Reminder with ID {0} not found")]
+    NotFound(u32),
+    #[error("Archived reminder with ID {0} not found")]
+    ArchivedNotFound(u32),
+    #[error("No reminder with ID {0} in the trash")]
+    TrashedNotFound(u32),
+    #[error("Deck '{0}' not found")]
+    DeckNotFound(String),
+    #[error("Nothing to undo")]
+    NothingToUndo,
+    /// The request makes no sense for the reminder's current state, such as
+    /// reviewing a completed reminder.
+    #[error("{0}")]
+    Invalid(String),
+    /// A date, duration, pattern or file could not be understood.
+    #[error("{0}")]
+    Parse(String),
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("{0}")]
+    Notify(String),
+    /// A remote storage server could not be reached or refused a request.
+    #[error("{0}")]
+    Remote(String),
+    #[error("The reminder store is in use by another reminder process (lock file {0})")]
+    Locked(PathBuf),
+    /// The store file couldn't be read and was moved aside to the path, so
+    /// that nothing is saved over it before it is repaired.
+    #[error("The reminder store could not be read and was moved to {0}; \
+             run 'reminder repair' to recover what it can of it")]
+    Corrupt(PathBuf),
+}
+
+impl Error {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        Error::Io { context: context.into(), source }
+    }
+
+    /// The process exit status that identifies this class of failure.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::NotFound(_) | Error::ArchivedNotFound(_) | Error::TrashedNotFound(_) | Error::DeckNotFound(_) | Error::NothingToUndo => {
+                EXIT_NOT_FOUND
+            }
+            Error::Io { .. } | Error::Database(_) | Error::Remote(_) => EXIT_IO,
+            Error::Parse(_) | Error::Corrupt(_) => EXIT_PARSE,
+            Error::Locked(_) => EXIT_LOCKED,
+            Error::Invalid(_) | Error::Notify(_) => EXIT_FAILURE,
+        }
+    }
+}
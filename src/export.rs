@@ -0,0 +1,311 @@
+// src/export.rs
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::{clock, dates};
+use crate::{get_trimmed_content, Reminder};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// One row per reminder, see `CsvRecord` for the columns
+    Csv,
+    /// A directory with one Markdown file per reminder, its schedule in YAML
+    /// front matter, as read by Obsidian and static site generators
+    Markdown,
+    /// An Emacs org-mode file with one `:REVIEW:` headline per reminder,
+    /// SCHEDULED for its next review
+    Org,
+}
+
+/// Column layout shared by CSV export and import: `content`, `tags`
+/// (separated by `;`), `created_at`, `next_review` (RFC 3339), `review_count`
+/// and the optional `answer` side of two-sided cards. On import only
+/// `content` is required.
+#[derive(Serialize, Deserialize)]
+pub struct CsvRecord {
+    pub content: String,
+    #[serde(default)]
+    pub tags: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub next_review: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub review_count: Option<u32>,
+    #[serde(default)]
+    pub answer: Option<String>,
+}
+
+pub const CSV_TAG_SEPARATOR: char = ';';
+
+/// Headings that start the answer and notes sections of an exported
+/// Markdown file, after the content.
+pub const MARKDOWN_ANSWER_HEADING: &str = "## Answer";
+pub const MARKDOWN_NOTES_HEADING: &str = "## Notes";
+
+/// Tag of the org-mode headlines that are reminders, and the headlines
+/// below one that hold its answer and notes.
+pub const ORG_REVIEW_TAG: &str = "REVIEW";
+pub const ORG_ANSWER_HEADLINE: &str = "Answer";
+pub const ORG_NOTES_HEADLINE: &str = "Notes";
+
+/// Longest file name stem taken from a reminder's content.
+const MARKDOWN_SLUG_LENGTH: usize = 50;
+
+/// Write all reminders to `path`, a file or for Markdown a directory, in
+/// the given format and return how many were exported.
+pub fn export_all(reminders: &[&Reminder], format: ExportFormat, path: &Path) -> Result<usize> {
+    match format {
+        ExportFormat::Csv => export_csv(reminders, path),
+        ExportFormat::Markdown => export_markdown(reminders, path),
+        ExportFormat::Org => export_org(reminders, path),
+    }
+}
+
+fn export_csv(reminders: &[&Reminder], path: &Path) -> Result<usize> {
+    let mut writer = csv::Writer::from_path(path)
+        .map_err(|e| Error::io(format!("Failed to create {}", path.display()), e.into()))?;
+
+    for reminder in reminders {
+        writer.serialize(CsvRecord {
+            content: reminder.content.clone(),
+            tags: reminder.tags.join(&CSV_TAG_SEPARATOR.to_string()),
+            created_at: Some(reminder.created_at),
+            next_review: Some(reminder.next_review),
+            review_count: Some(reminder.review_count),
+            answer: reminder.answer.clone(),
+        })
+        .map_err(|e| Error::io(format!("Failed to write {}", path.display()), e.into()))?;
+    }
+
+    writer.flush().map_err(|e| Error::io(format!("Failed to write {}", path.display()), e))?;
+    Ok(reminders.len())
+}
+
+/// Write each reminder to `<id>-<first words>.md` in `dir`, replacing the
+/// files of an earlier export of the same reminders.
+fn export_markdown(reminders: &[&Reminder], dir: &Path) -> Result<usize> {
+    fs::create_dir_all(dir).map_err(|e| Error::io(format!("Failed to create {}", dir.display()), e))?;
+    for reminder in reminders {
+        let path = dir.join(format!("{}-{}.md", reminder.id, slug(&reminder.content)));
+        fs::write(&path, markdown_file(reminder))
+            .map_err(|e| Error::io(format!("Failed to write {}", path.display()), e))?;
+    }
+    Ok(reminders.len())
+}
+
+/// A file name stem from the first words of `content`: lowercase letters and
+/// digits joined by dashes.
+fn slug(content: &str) -> String {
+    let words: Vec<String> = content.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut slug = String::new();
+    for word in words {
+        if !slug.is_empty() && slug.len() + word.len() + 1 > MARKDOWN_SLUG_LENGTH {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word);
+    }
+    if slug.is_empty() {
+        return "reminder".to_string();
+    }
+    slug.chars().take(MARKDOWN_SLUG_LENGTH).collect()
+}
+
+/// The Markdown file for one reminder. Strings in the front matter are
+/// written as JSON strings, which YAML reads as double-quoted scalars.
+fn markdown_file(reminder: &Reminder) -> String {
+    let quote = |text: &str| serde_json::to_string(text).expect("Strings always serialize");
+    let mut file = String::from("---\n");
+    file.push_str(&format!("id: {}\n", reminder.id));
+    let tags: Vec<String> = reminder.tags.iter().map(|tag| quote(tag)).collect();
+    file.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    if let Some(deck) = &reminder.deck {
+        file.push_str(&format!("deck: {}\n", quote(deck)));
+    }
+    file.push_str(&format!("priority: {}\n", reminder.priority));
+    file.push_str(&format!("created: {}\n", dates::local(reminder.created_at).to_rfc3339_opts(SecondsFormat::Secs, false)));
+    file.push_str(&format!("next_review: {}\n", dates::local(reminder.next_review).to_rfc3339_opts(SecondsFormat::Secs, false)));
+    if let Some(last_review) = reminder.last_review {
+        file.push_str(&format!("last_review: {}\n", dates::local(last_review).to_rfc3339_opts(SecondsFormat::Secs, false)));
+    }
+    file.push_str(&format!("review_count: {}\n", reminder.review_count));
+    file.push_str(&format!("interval: {}\n", reminder.interval));
+    file.push_str(&format!("algorithm: {}\n", reminder.algorithm));
+    if let Some(recurrence) = &reminder.recurrence {
+        file.push_str(&format!("repeats: {}\n", quote(&recurrence.to_string())));
+    }
+    if reminder.completed {
+        file.push_str("completed: true\n");
+    }
+    if reminder.paused_at.is_some() {
+        file.push_str("paused: true\n");
+    }
+    file.push_str("---\n\n");
+
+    file.push_str(reminder.content.trim_end());
+    file.push('\n');
+    if let Some(answer) = &reminder.answer {
+        file.push_str(&format!("\n{}\n\n{}\n", MARKDOWN_ANSWER_HEADING, answer.trim_end()));
+    }
+    if let Some(notes) = &reminder.notes {
+        file.push_str(&format!("\n{}\n\n{}\n", MARKDOWN_NOTES_HEADING, notes.trim_end()));
+    }
+    file
+}
+
+/// Write one org-mode file with a headline per reminder: the first line of
+/// the content as its title, its tags and `:REVIEW:`, a SCHEDULED timestamp
+/// for the next review, the rest of its state in a property drawer and the
+/// answer and notes in sub-headlines.
+fn export_org(reminders: &[&Reminder], path: &Path) -> Result<usize> {
+    let mut file = String::from("#+TITLE: Reminders\n\n");
+    for reminder in reminders {
+        let mut lines = reminder.content.trim().lines();
+        let title = lines.next().unwrap_or_default();
+        let tags: Vec<String> = reminder.tags.iter()
+            .map(|tag| org_tag(tag))
+            .chain([ORG_REVIEW_TAG.to_string()])
+            .collect();
+        let done = if reminder.completed { "DONE " } else { "" };
+        file.push_str(&format!("* {}{} :{}:\n", done, title, tags.join(":")));
+        if !reminder.completed && reminder.paused_at.is_none() {
+            file.push_str(&format!("  SCHEDULED: <{}>\n", dates::local(reminder.next_review).format("%Y-%m-%d %a %H:%M")));
+        }
+
+        file.push_str("  :PROPERTIES:\n");
+        file.push_str(&format!("  :REMINDER_ID: {}\n", reminder.id));
+        file.push_str(&format!("  :CREATED: [{}]\n", dates::local(reminder.created_at).format("%Y-%m-%d %a %H:%M")));
+        file.push_str(&format!("  :REVIEW_COUNT: {}\n", reminder.review_count));
+        file.push_str(&format!("  :PRIORITY: {}\n", reminder.priority));
+        if let Some(deck) = &reminder.deck {
+            file.push_str(&format!("  :DECK: {}\n", deck));
+        }
+        if let Some(recurrence) = &reminder.recurrence {
+            file.push_str(&format!("  :REPEATS: {}\n", recurrence));
+        }
+        file.push_str("  :END:\n");
+
+        // Indented, a line starting with `*` can't be taken for a headline
+        for line in lines {
+            push_indented(&mut file, "  ", line);
+        }
+        for (headline, text) in [(ORG_ANSWER_HEADLINE, &reminder.answer), (ORG_NOTES_HEADLINE, &reminder.notes)] {
+            if let Some(text) = text {
+                file.push_str(&format!("** {}\n", headline));
+                for line in text.trim().lines() {
+                    push_indented(&mut file, "   ", line);
+                }
+            }
+        }
+    }
+
+    fs::write(path, file).map_err(|e| Error::io(format!("Failed to write {}", path.display()), e))?;
+    Ok(reminders.len())
+}
+
+fn push_indented(file: &mut String, indent: &str, line: &str) {
+    if !line.is_empty() {
+        file.push_str(indent);
+    }
+    file.push_str(line);
+    file.push('\n');
+}
+
+/// A tag in the characters org-mode allows in tags.
+fn org_tag(tag: &str) -> String {
+    tag.chars().map(|c| if c.is_alphanumeric() || "_@#%".contains(c) { c } else { '_' }).collect()
+}
+
+/// How long each review event lasts in the calendar.
+const CALENDAR_EVENT_MINUTES: i64 = 15;
+
+/// Write an iCalendar file with one event per upcoming review. With `alarm`,
+/// each event carries a display alarm that long before the review.
+pub fn export_calendar(reminders: &[&Reminder], path: &Path, alarm: Option<Duration>) -> Result<usize> {
+    let now = clock::now();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//reminder//spaced repetition schedule//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    let upcoming: Vec<&&Reminder> = reminders.iter().filter(|r| r.is_scheduled()).collect();
+    for reminder in &upcoming {
+        let start = reminder.next_review;
+        let end = start + Duration::minutes(CALENDAR_EVENT_MINUTES);
+        let summary = format!("Review: {}", get_trimmed_content(&reminder.content, Some(60)));
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:reminder-{}-{}@reminder", reminder.id, reminder.created_at.timestamp()));
+        lines.push(format!("DTSTAMP:{}", format_ics_time(now)));
+        lines.push(format!("DTSTART:{}", format_ics_time(start)));
+        lines.push(format!("DTEND:{}", format_ics_time(end)));
+        lines.push(format!("SUMMARY:{}", escape_ics_text(&summary)));
+        lines.push(format!("DESCRIPTION:{}", escape_ics_text(&reminder.content)));
+        if !reminder.tags.is_empty() {
+            let categories: Vec<String> = reminder.tags.iter().map(|t| escape_ics_text(t)).collect();
+            lines.push(format!("CATEGORIES:{}", categories.join(",")));
+        }
+
+        if let Some(alarm) = alarm {
+            lines.push("BEGIN:VALARM".to_string());
+            lines.push("ACTION:DISPLAY".to_string());
+            lines.push(format!("DESCRIPTION:{}", escape_ics_text(&summary)));
+            lines.push(format!("TRIGGER:-PT{}M", alarm.num_minutes().max(0)));
+            lines.push("END:VALARM".to_string());
+        }
+
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let content: String = lines.iter()
+        .map(|line| fold_ics_line(line) + "\r\n")
+        .collect();
+    fs::write(path, content)
+        .map_err(|e| Error::io(format!("Failed to write {}", path.display()), e))?;
+
+    Ok(upcoming.len())
+}
+
+fn format_ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold lines longer than 75 octets as required by RFC 5545, without
+/// splitting multi-byte characters.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_len = 0;
+
+    for c in line.chars() {
+        if line_len + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            line_len = 1;
+        }
+        folded.push(c);
+        line_len += c.len_utf8();
+    }
+
+    folded
+}
@@ -0,0 +1,142 @@
+// src/graph.rs
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use reminder::{get_trimmed_content, Reminder, ReminderStore};
+
+use crate::style;
+
+/// How much of a reminder is shown next to its ID when `--trim` isn't given.
+const LABEL_LENGTH: usize = 50;
+
+/// The links between the listed reminders, as printed by `list --graph --json`.
+#[derive(Serialize)]
+pub struct Graph<'a> {
+    pub nodes: Vec<&'a Reminder>,
+    pub edges: Vec<(u32, u32)>,
+}
+
+impl<'a> Graph<'a> {
+    /// The graph of `reminders` and the links between them; links to
+    /// reminders that aren't listed are left out.
+    pub fn new(store: &ReminderStore, reminders: Vec<&'a Reminder>) -> Graph<'a> {
+        let ids: BTreeSet<u32> = reminders.iter().map(|r| r.id).collect();
+        let edges = store.links.iter()
+            .filter(|(a, b)| ids.contains(a) && ids.contains(b))
+            .copied()
+            .collect();
+        Graph { nodes: reminders, edges }
+    }
+}
+
+/// The first line of a reminder's content, shortened for one-line listings.
+pub fn label(reminder: &Reminder, trim: Option<usize>) -> String {
+    let first_line = reminder.content.lines().next().unwrap_or_default();
+    get_trimmed_content(first_line, Some(trim.unwrap_or(LABEL_LENGTH)))
+}
+
+/// The reminders linked to `id` as one line, e.g. for a "Related:" field, or
+/// `None` without any.
+pub fn related_line(store: &ReminderStore, id: u32) -> Option<String> {
+    let related: Vec<String> = store.linked(id).into_iter()
+        .filter_map(|other| store.find_reminder(other))
+        .map(|other| format!("{} \"{}\"", other.id, label(other, Some(30))))
+        .collect();
+    (!related.is_empty()).then(|| related.join(", "))
+}
+
+/// The prerequisites reminder `id` still waits for as one line, e.g. for a
+/// "Waiting for:" field, or `None` when it doesn't wait for any.
+pub fn waiting_line(store: &ReminderStore, id: u32) -> Option<String> {
+    let waiting: Vec<String> = store.blockers(id).into_iter()
+        .filter_map(|dependency| store.find_reminder(dependency.on).map(|on| (dependency, on)))
+        .map(|(dependency, on)| match dependency.reviews {
+            Some(reviews) => format!("{} \"{}\" ({} of {} reviews)", on.id, label(on, Some(30)), on.review_count, reviews),
+            None => format!("{} \"{}\" (to be completed)", on.id, label(on, Some(30))),
+        })
+        .collect();
+    (!waiting.is_empty()).then(|| waiting.join(", "))
+}
+
+/// Print each group of linked reminders as a tree, walking the links from the
+/// lowest ID. A reminder reached again over another link is shown by its ID
+/// only.
+pub fn print_graph(graph: &Graph, trim: Option<usize>) {
+    let reminders: BTreeMap<u32, &Reminder> = graph.nodes.iter().map(|r| (r.id, *r)).collect();
+    let mut neighbours: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    for &(a, b) in &graph.edges {
+        neighbours.entry(a).or_default().push(b);
+        neighbours.entry(b).or_default().push(a);
+    }
+
+    let mut walk = Walk { neighbours: &neighbours, seen: BTreeSet::new(), drawn: BTreeSet::new() };
+    let mut first = true;
+    for (&id, reminder) in &reminders {
+        if walk.seen.contains(&id) || !neighbours.contains_key(&id) {
+            continue;
+        }
+        if !first {
+            println!();
+        }
+        first = false;
+        println!("{} {}", style::id(id), label(reminder, trim));
+        walk.seen.insert(id);
+        let branches = walk.branches(id);
+        print_branches(&branches, &reminders, trim, "");
+    }
+
+    let unlinked = reminders.keys().filter(|id| !neighbours.contains_key(id)).count();
+    if graph.edges.is_empty() {
+        println!("None of the {} reminder(s) are linked; use 'reminder link <ID> <ID>' to link them", unlinked);
+    } else if unlinked > 0 {
+        println!("\n{} reminder(s) without links", unlinked);
+    }
+}
+
+/// A reminder in the printed tree, with the ones reached through it.
+struct Branch {
+    id: u32,
+    /// Whether the reminder was printed earlier in the tree
+    repeated: bool,
+    children: Vec<Branch>,
+}
+
+struct Walk<'a> {
+    neighbours: &'a BTreeMap<u32, Vec<u32>>,
+    /// Reminders already in the tree
+    seen: BTreeSet<u32>,
+    /// Links already in the tree, so a cycle is closed only once
+    drawn: BTreeSet<(u32, u32)>,
+}
+
+impl Walk<'_> {
+    fn branches(&mut self, id: u32) -> Vec<Branch> {
+        let mut branches = Vec::new();
+        for &other in &self.neighbours[&id] {
+            if !self.drawn.insert((id.min(other), id.max(other))) {
+                continue;
+            }
+            if !self.seen.insert(other) {
+                branches.push(Branch { id: other, repeated: true, children: Vec::new() });
+                continue;
+            }
+            let children = self.branches(other);
+            branches.push(Branch { id: other, repeated: false, children });
+        }
+        branches
+    }
+}
+
+fn print_branches(branches: &[Branch], reminders: &BTreeMap<u32, &Reminder>, trim: Option<usize>, prefix: &str) {
+    for (index, branch) in branches.iter().enumerate() {
+        let last = index + 1 == branches.len();
+        let connector = if last { "└── " } else { "├── " };
+        if branch.repeated {
+            println!("{}{}{} (see above)", prefix, connector, style::id(branch.id));
+        } else {
+            println!("{}{}{} {}", prefix, connector, style::id(branch.id), label(reminders[&branch.id], trim));
+        }
+        let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+        print_branches(&branch.children, reminders, trim, &prefix);
+    }
+}
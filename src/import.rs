@@ -0,0 +1,841 @@
+// src/import.rs
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use clap::ValueEnum;
+use regex::Regex;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::config::Config;
+use crate::{clock, dates};
+use crate::error::{Error, Result};
+use crate::export::{
+    CsvRecord, CSV_TAG_SEPARATOR, MARKDOWN_ANSWER_HEADING, MARKDOWN_NOTES_HEADING, ORG_ANSWER_HEADLINE,
+    ORG_NOTES_HEADLINE, ORG_REVIEW_TAG,
+};
+use crate::recurrence::Recurrence;
+use crate::scheduler::Algorithm;
+use crate::{NewReminder, Priority, ReminderStore};
+
+/// Anki stores note fields in a single column separated by this character.
+const ANKI_FIELD_SEPARATOR: char = '\x1f';
+
+/// A note read from an Anki export, before it becomes a reminder.
+struct AnkiNote {
+    front: String,
+    back: Option<String>,
+    tags: Vec<String>,
+    schedule: Option<AnkiSchedule>,
+}
+
+/// Review state of an Anki card that has graduated to the review queue.
+struct AnkiSchedule {
+    next_review: DateTime<Utc>,
+    interval: u32,
+    ease_factor: f64,
+    reviews: u32,
+}
+
+/// Import an Anki `.apkg` package or a "Notes in Plain Text" TSV export.
+/// The first field becomes the front of the card and the second the back.
+/// With `keep_schedule`, cards already in Anki's review queue keep their
+/// interval, ease and due date and are scheduled with SM-2.
+pub fn import_anki(
+    store: &mut ReminderStore,
+    file_path: &Path,
+    keep_schedule: bool,
+    config: &Config,
+) -> Result<usize> {
+    let is_package = file_path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("apkg") || ext.eq_ignore_ascii_case("colpkg"));
+
+    let notes = if is_package {
+        read_apkg(file_path)?
+    } else {
+        let content = fs::read_to_string(file_path)
+            .map_err(|e| Error::io(format!("Failed to read {}", file_path.display()), e))?;
+        read_tsv(&content)
+    };
+
+    let mut imported = 0;
+    for note in notes {
+        if note.front.is_empty() {
+            continue;
+        }
+
+        let schedule = note.schedule.filter(|_| keep_schedule);
+        let new = NewReminder {
+            content: note.front,
+            answer: note.back.filter(|back| !back.is_empty()),
+            algorithm: schedule.is_some().then_some(Algorithm::Sm2),
+            tags: note.tags,
+            ..Default::default()
+        };
+        let id = store.add_reminder(new, config);
+
+        if let Some(schedule) = schedule {
+            let reminder = store.reminders.get_mut(&id).expect("Reminder was just added");
+            reminder.next_review = schedule.next_review;
+            reminder.interval = schedule.interval;
+            reminder.ease_factor = schedule.ease_factor;
+            reminder.review_count = schedule.reviews;
+            reminder.repetitions = schedule.reviews;
+        }
+        imported += 1;
+    }
+
+    Ok(imported)
+}
+
+fn read_apkg(file_path: &Path) -> Result<Vec<AnkiNote>> {
+    let file = File::open(file_path)
+        .map_err(|e| Error::io(format!("Failed to open {}", file_path.display()), e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| Error::Parse(format!("Not a valid Anki package: {}", e)))?;
+
+    if archive.by_name("collection.anki21b").is_ok() && archive.by_name("collection.anki21").is_err() {
+        return Err(Error::Parse("This package uses Anki's compressed collection format; \
+                                 re-export it with \"Support older Anki versions\" enabled".to_string()));
+    }
+
+    // Newer packages carry the real collection as .anki21 next to a stub .anki2
+    let name = ["collection.anki21", "collection.anki2"]
+        .into_iter()
+        .find(|name| archive.by_name(name).is_ok())
+        .ok_or_else(|| Error::Parse("Anki package does not contain a collection".to_string()))?;
+
+    let db_path = std::env::temp_dir().join(format!("reminder-anki-{}.db", std::process::id()));
+    let result = (|| {
+        let mut entry = archive.by_name(name)
+            .map_err(|e| Error::Parse(format!("Not a valid Anki package: {}", e)))?;
+        let extract_error = |e| Error::io("Failed to extract Anki collection", e);
+        let mut db_file = File::create(&db_path).map_err(extract_error)?;
+        io::copy(&mut entry, &mut db_file).map_err(extract_error)?;
+        drop(db_file);
+
+        let conn = Connection::open(&db_path)?;
+        read_collection(&conn).map_err(|e| Error::Parse(format!("Failed to read Anki collection: {}", e)))
+    })();
+
+    let _ = fs::remove_file(&db_path);
+    result
+}
+
+fn read_collection(conn: &Connection) -> rusqlite::Result<Vec<AnkiNote>> {
+    // Review card due dates are counted in days since the collection was created
+    let created: i64 = conn.query_row("SELECT crt FROM col", [], |row| row.get(0))?;
+    let created = Utc.timestamp_opt(created, 0).single().unwrap_or_else(clock::now);
+
+    let mut schedules: HashMap<i64, AnkiSchedule> = HashMap::new();
+    let mut stmt = conn.prepare("SELECT nid, due, ivl, factor, reps FROM cards WHERE type = 2 ORDER BY ord")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?,
+            row.get::<_, i64>(3)?, row.get::<_, i64>(4)?))
+    })?;
+    for row in rows {
+        let (note_id, due, interval, factor, reps) = row?;
+        // Only the first card of each note is kept, matching one reminder per note
+        schedules.entry(note_id).or_insert(AnkiSchedule {
+            next_review: created + Duration::days(due),
+            interval: interval.max(1) as u32,
+            ease_factor: factor as f64 / 1000.0,
+            reviews: reps.max(0) as u32,
+        });
+    }
+
+    let mut stmt = conn.prepare("SELECT id, flds, tags FROM notes ORDER BY id")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    })?;
+
+    let mut notes = Vec::new();
+    for row in rows {
+        let (note_id, fields, tags) = row?;
+        let mut fields = fields.split(ANKI_FIELD_SEPARATOR).map(strip_html);
+        notes.push(AnkiNote {
+            front: fields.next().unwrap_or_default(),
+            back: fields.next(),
+            tags: tags.split_whitespace().map(str::to_string).collect(),
+            schedule: schedules.remove(&note_id),
+        });
+    }
+
+    Ok(notes)
+}
+
+/// Parse Anki's "Notes in Plain Text" export. Header lines such as
+/// `#separator:tab` and `#tags column:3` are honoured; other headers are
+/// skipped.
+fn read_tsv(content: &str) -> Vec<AnkiNote> {
+    let mut separator = '\t';
+    let mut html = true;
+    let mut tags_column: Option<usize> = None;
+    let mut notes = Vec::new();
+
+    for line in content.lines() {
+        if let Some(header) = line.strip_prefix('#') {
+            if let Some((key, value)) = header.split_once(':') {
+                match key.trim() {
+                    "separator" => {
+                        separator = match value.trim().to_lowercase().as_str() {
+                            "tab" => '\t',
+                            "comma" => ',',
+                            "semicolon" => ';',
+                            "space" => ' ',
+                            "pipe" => '|',
+                            "colon" => ':',
+                            other => other.chars().next().unwrap_or('\t'),
+                        }
+                    }
+                    "html" => html = value.trim() == "true",
+                    "tags column" => tags_column = value.trim().parse().ok(),
+                    _ => {}
+                }
+            }
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields: Vec<String> = line.split(separator)
+            .map(unquote)
+            .map(|field| if html { strip_html(&field) } else { field })
+            .collect();
+
+        let tags = tags_column
+            .and_then(|column| fields.get(column.saturating_sub(1)))
+            .map(|tags| tags.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+
+        let mut content_fields = fields.iter().enumerate()
+            .filter(|(index, _)| tags_column != Some(index + 1))
+            .map(|(_, field)| field.clone());
+
+        notes.push(AnkiNote {
+            front: content_fields.next().unwrap_or_default(),
+            back: content_fields.next(),
+            tags,
+            schedule: None,
+        });
+    }
+
+    notes
+}
+
+fn unquote(field: &str) -> String {
+    match field.strip_prefix('"').and_then(|f| f.strip_suffix('"')) {
+        Some(inner) => inner.replace("\"\"", "\""),
+        None => field.to_string(),
+    }
+}
+
+/// Turn Anki's HTML field content into plain text.
+fn strip_html(field: &str) -> String {
+    let with_breaks = field
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("</div>", "\n");
+
+    let mut text = String::with_capacity(with_breaks.len());
+    let mut in_tag = false;
+    for c in with_breaks.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+/// Import reminders from a CSV file with the column layout described on
+/// `CsvRecord`. Schedule columns that are left empty get the defaults of a
+/// newly added reminder.
+pub fn import_csv(store: &mut ReminderStore, file_path: &Path, config: &Config) -> Result<usize> {
+    let mut reader = csv::Reader::from_path(file_path)
+        .map_err(|e| Error::io(format!("Failed to open {}", file_path.display()), e.into()))?;
+
+    // Parse everything up front so a bad row doesn't leave a partial import
+    let records: Vec<CsvRecord> = reader.deserialize()
+        .enumerate()
+        .map(|(index, record)| record.map_err(|e| Error::Parse(format!("Invalid CSV row {}: {}", index + 1, e))))
+        .collect::<Result<_>>()?;
+
+    let mut imported = 0;
+    for record in records {
+        if record.content.trim().is_empty() {
+            continue;
+        }
+
+        let new = NewReminder {
+            content: record.content,
+            answer: record.answer.filter(|answer| !answer.is_empty()),
+            algorithm: None,
+            tags: record.tags.split(CSV_TAG_SEPARATOR).map(str::to_string).collect(),
+            due: record.next_review,
+            ..Default::default()
+        };
+        let id = store.add_reminder(new, config);
+
+        let reminder = store.reminders.get_mut(&id).expect("Reminder was just added");
+        if let Some(created_at) = record.created_at {
+            reminder.created_at = created_at;
+        }
+        if let Some(review_count) = record.review_count {
+            reminder.review_count = review_count;
+        }
+        imported += 1;
+    }
+
+    Ok(imported)
+}
+
+/// Line between the items of a list file whose items span several lines.
+pub const LINES_BLOCK_SEPARATOR: &str = "---";
+
+/// What importing a list file added and skipped.
+#[derive(Serialize, Debug, Default)]
+pub struct LinesSummary {
+    /// IDs of the new reminders, in the order of the file
+    pub added: Vec<u32>,
+    /// Items imported from the file before
+    pub skipped: usize,
+}
+
+/// Import a list file, such as a brain dump: each non-empty line becomes a
+/// reminder, without a leading `-`, `*` or `+` bullet, or each block between
+/// `---` lines when the file has any. Every reminder gets `tags` and records
+/// the file it came from, so importing the file again after adding to it
+/// only adds the new items. A path of `-` reads standard input.
+pub fn import_lines(store: &mut ReminderStore, file_path: &Path, tags: &[String], config: &Config) -> Result<LinesSummary> {
+    let stdin = file_path == Path::new("-");
+    let (text, source) = if stdin {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)
+            .map_err(|e| Error::io("Failed to read standard input", e))?;
+        (text, None)
+    } else {
+        let text = fs::read_to_string(file_path)
+            .map_err(|e| Error::io(format!("Failed to read {}", file_path.display()), e))?;
+        let source = fs::canonicalize(file_path)
+            .map_err(|e| Error::io(format!("Failed to read {}", file_path.display()), e))?
+            .to_string_lossy()
+            .into_owned();
+        (text, Some(source))
+    };
+
+    let mut summary = LinesSummary::default();
+    for content in split_list(&text) {
+        if source.as_ref().is_some_and(|source| imported_from(store, source, Some(&content))) {
+            summary.skipped += 1;
+            continue;
+        }
+        let new = NewReminder { content, tags: tags.to_vec(), ..Default::default() };
+        let id = store.add_reminder(new, config);
+        store.reminders.get_mut(&id).expect("Reminder was just added").source = source.clone();
+        summary.added.push(id);
+    }
+    Ok(summary)
+}
+
+/// The items of a list file, as described on [`import_lines`].
+fn split_list(text: &str) -> Vec<String> {
+    if text.lines().any(|line| line.trim() == LINES_BLOCK_SEPARATOR) {
+        let mut blocks = vec![String::new()];
+        for line in text.lines() {
+            if line.trim() == LINES_BLOCK_SEPARATOR {
+                blocks.push(String::new());
+            } else {
+                let block = blocks.last_mut().expect("There is always a block");
+                block.push_str(line);
+                block.push('\n');
+            }
+        }
+        blocks.iter().map(|block| block.trim().to_string()).filter(|block| !block.is_empty()).collect()
+    } else {
+        text.lines()
+            .map(|line| {
+                let line = line.trim();
+                ["- ", "* ", "+ "].iter().find_map(|bullet| line.strip_prefix(bullet)).unwrap_or(line).trim()
+            })
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Tag that marks a block of a Markdown file to import on its own, unless
+/// another one is given.
+pub const DEFAULT_MARKDOWN_MARKER: &str = "review";
+
+/// What a Markdown file's YAML front matter says about its reminder. Only the
+/// simple `key: value` and list forms are read, which covers what `export-all
+/// --format markdown` and Obsidian write.
+#[derive(Default)]
+struct FrontMatter {
+    tags: Vec<String>,
+    deck: Option<String>,
+    priority: Option<Priority>,
+    created: Option<DateTime<Utc>>,
+    next_review: Option<DateTime<Utc>>,
+    review_count: Option<u32>,
+    recurrence: Option<Recurrence>,
+}
+
+/// Import a directory of Markdown files, such as an Obsidian vault. A file
+/// with blocks (paragraphs or list items) carrying `#marker` gives one
+/// reminder per block, without the marker; any other file becomes one
+/// reminder, its `## Answer` and `## Notes` sections as written by the
+/// Markdown export becoming the answer and notes. Every reminder records the
+/// file it came from, so importing again skips what was imported before. With
+/// `tag_from_folder`, the folders below `dir` become tags.
+pub fn import_markdown(
+    store: &mut ReminderStore,
+    dir: &Path,
+    marker: &str,
+    tag_from_folder: bool,
+    config: &Config,
+) -> Result<usize> {
+    let root = fs::canonicalize(dir).map_err(|e| Error::io(format!("Failed to read {}", dir.display()), e))?;
+    let mut files = Vec::new();
+    collect_markdown_files(&root, &mut files)?;
+
+    let marker = Regex::new(&format!(r"(^|[ \t])#{}([ \t]|$)", regex::escape(marker.trim_start_matches('#'))))
+        .map_err(|e| Error::Invalid(format!("Invalid marker tag: {}", e)))?;
+
+    let mut imported = 0;
+    for path in files {
+        let text = fs::read_to_string(&path).map_err(|e| Error::io(format!("Failed to read {}", path.display()), e))?;
+        let (front_matter, body) = split_front_matter(&text);
+        let front_matter = parse_front_matter(front_matter);
+        let source = path.to_string_lossy().into_owned();
+
+        let mut tags = front_matter.tags.clone();
+        if tag_from_folder {
+            let folders = path.strip_prefix(&root).ok().and_then(Path::parent).into_iter().flat_map(Path::components);
+            tags.extend(folders.map(|folder| folder.as_os_str().to_string_lossy().into_owned()));
+        }
+
+        let blocks: Vec<String> = markdown_blocks(body).into_iter()
+            .filter(|block| marker.is_match(block))
+            .map(|block| marker.replace_all(&block, "$1$2").trim().to_string())
+            .filter(|block| !block.is_empty())
+            .collect();
+        let found = if blocks.is_empty() {
+            let (content, answer, notes) = split_sections(body);
+            let content = if content.is_empty() {
+                path.file_stem().unwrap_or_default().to_string_lossy().into_owned()
+            } else {
+                content
+            };
+            if imported_from(store, &source, None) {
+                continue;
+            }
+            vec![(content, answer, notes)]
+        } else {
+            blocks.into_iter()
+                .filter(|block| !imported_from(store, &source, Some(block)))
+                .map(|block| (block, None, None))
+                .collect()
+        };
+
+        for (content, answer, notes) in found {
+            let new = NewReminder {
+                content,
+                answer,
+                algorithm: None,
+                tags: tags.clone(),
+                due: front_matter.next_review,
+                priority: front_matter.priority.unwrap_or_default(),
+                deck: front_matter.deck.clone(),
+                recurrence: front_matter.recurrence.clone(),
+                notes,
+            };
+            let id = store.add_reminder(new, config);
+
+            let reminder = store.reminders.get_mut(&id).expect("Reminder was just added");
+            reminder.source = Some(source.clone());
+            if let Some(created) = front_matter.created {
+                reminder.created_at = created;
+            }
+            if let Some(review_count) = front_matter.review_count {
+                reminder.review_count = review_count;
+            }
+            imported += 1;
+        }
+    }
+
+    Ok(imported)
+}
+
+/// Whether a reminder was imported from `source` before, with `content` if
+/// given.
+fn imported_from(store: &ReminderStore, source: &str, content: Option<&str>) -> bool {
+    store.reminders.values().chain(store.archived.values())
+        .any(|r| r.source.as_deref() == Some(source) && content.is_none_or(|c| r.content == c))
+}
+
+/// The `.md` files below `dir`, in name order, skipping hidden files and
+/// folders such as `.obsidian` and `.git`.
+fn collect_markdown_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = fs::read_dir(dir).map_err(|e| Error::io(format!("Failed to read {}", dir.display()), e))?;
+    let mut paths: Vec<PathBuf> = entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()
+        .map_err(|e| Error::io(format!("Failed to read {}", dir.display()), e))?;
+    paths.sort();
+
+    for path in paths {
+        if path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_markdown_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md")) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Split a file into its front matter, between `---` lines at the very
+/// start, and the rest.
+fn split_front_matter(text: &str) -> (&str, &str) {
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return ("", text);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (&rest[..offset], &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    ("", text)
+}
+
+fn parse_front_matter(text: &str) -> FrontMatter {
+    let mut values: Vec<(String, Vec<String>)> = Vec::new();
+    for line in text.lines() {
+        // An indented `- item` continues the list of the key before it
+        if let (Some(item), Some((_, list))) = (line.trim_start().strip_prefix("- "), values.last_mut()) {
+            if line.starts_with([' ', '\t', '-']) {
+                list.push(unquote_yaml(item));
+                continue;
+            }
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let list = match value.strip_prefix('[').and_then(|value| value.strip_suffix(']')) {
+            Some(items) => items.split(',').map(unquote_yaml).filter(|item| !item.is_empty()).collect(),
+            None if value.is_empty() => Vec::new(),
+            None => vec![unquote_yaml(value)],
+        };
+        values.push((key.trim().to_lowercase(), list));
+    }
+
+    let time = |value: &str| {
+        DateTime::parse_from_rfc3339(value).ok().map(|time| time.with_timezone(&Utc))
+            .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+                .and_then(|date| dates::from_local(date.and_time(NaiveTime::MIN))))
+    };
+
+    // Values that can't be read, like a date in another format, are left out
+    let mut front_matter = FrontMatter::default();
+    for (key, list) in values {
+        let first = list.first().map(String::as_str).unwrap_or_default();
+        match key.as_str() {
+            "tags" | "tag" => front_matter.tags.extend(list.iter()
+                .flat_map(|tags| tags.split([',', ' ']))
+                .map(|tag| tag.trim_start_matches('#').to_string())
+                .filter(|tag| !tag.is_empty())),
+            "deck" => front_matter.deck = Some(first.to_string()),
+            "priority" => front_matter.priority = Priority::from_str(first, true).ok(),
+            "created" | "created_at" => front_matter.created = time(first),
+            "next_review" | "due" => front_matter.next_review = time(first),
+            "review_count" => front_matter.review_count = first.parse().ok(),
+            "repeats" => front_matter.recurrence = Recurrence::parse(first).ok(),
+            _ => {}
+        }
+    }
+    front_matter
+}
+
+fn unquote_yaml(value: &str) -> String {
+    let value = value.trim();
+    if value.starts_with('"') {
+        if let Ok(value) = serde_json::from_str::<String>(value) {
+            return value;
+        }
+    }
+    match value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')) {
+        Some(value) => value.replace("''", "'"),
+        None => value.to_string(),
+    }
+}
+
+/// The paragraphs and list items of a Markdown body. Fenced code blocks are
+/// left out, so a marker shown in code doesn't count.
+fn markdown_blocks(body: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            if !current.is_empty() {
+                blocks.push(current.join("\n"));
+                current.clear();
+            }
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let item = list_item_text(line);
+        if (line.trim().is_empty() || item.is_some()) && !current.is_empty() {
+            blocks.push(current.join("\n"));
+            current.clear();
+        }
+        if let Some(item) = item {
+            current.push(item);
+        } else if !line.trim().is_empty() {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current.join("\n"));
+    }
+    blocks
+}
+
+/// The text of a top-level list item line, without its `-`, `*`, `+` or
+/// `1.` marker.
+fn list_item_text(line: &str) -> Option<&str> {
+    if let Some(text) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")).or_else(|| line.strip_prefix("+ ")) {
+        return Some(text);
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    (digits > 0).then(|| line[digits..].strip_prefix(". ")).flatten()
+}
+
+/// Split a body into its content and the `## Answer` and `## Notes`
+/// sections the Markdown export writes after it.
+fn split_sections(body: &str) -> (String, Option<String>, Option<String>) {
+    let mut content = Vec::new();
+    let mut answer: Option<Vec<&str>> = None;
+    let mut notes: Option<Vec<&str>> = None;
+    for line in body.lines() {
+        match line.trim_end() {
+            MARKDOWN_ANSWER_HEADING => answer = Some(Vec::new()),
+            MARKDOWN_NOTES_HEADING => notes = Some(Vec::new()),
+            _ => match (&mut answer, &mut notes) {
+                (_, Some(notes)) => notes.push(line),
+                (Some(answer), None) => answer.push(line),
+                (None, None) => content.push(line),
+            },
+        }
+    }
+    let section = |lines: Option<Vec<&str>>| lines.map(|lines| lines.join("\n").trim().to_string()).filter(|text| !text.is_empty());
+    (content.join("\n").trim().to_string(), section(answer), section(notes))
+}
+
+/// One headline of an org-mode file, with the lines up to the next one.
+struct OrgHeadline<'a> {
+    level: usize,
+    title: &'a str,
+    tags: Vec<&'a str>,
+    body: Vec<&'a str>,
+}
+
+/// Import the headlines tagged `:REVIEW:` of an org-mode file as reminders:
+/// the title and the text below it become the content and its other tags
+/// the tags. A SCHEDULED timestamp sets the first review, and sub-headlines
+/// called Answer and Notes, as `export-all --format org` writes them, become
+/// the answer and notes. As with Markdown, each reminder records the file it
+/// came from and importing it again skips the headlines imported before.
+pub fn import_org(store: &mut ReminderStore, file_path: &Path, config: &Config) -> Result<usize> {
+    let text = fs::read_to_string(file_path)
+        .map_err(|e| Error::io(format!("Failed to read {}", file_path.display()), e))?;
+    let source = fs::canonicalize(file_path)
+        .map_err(|e| Error::io(format!("Failed to read {}", file_path.display()), e))?
+        .to_string_lossy()
+        .into_owned();
+    let headlines = parse_org(&text);
+
+    let mut imported = 0;
+    for (index, headline) in headlines.iter().enumerate() {
+        if !headline.tags.iter().any(|tag| tag.eq_ignore_ascii_case(ORG_REVIEW_TAG)) {
+            continue;
+        }
+        let section = parse_org_section(&headline.body);
+        let content = if section.text.is_empty() {
+            headline.title.to_string()
+        } else {
+            format!("{}\n{}", headline.title, section.text)
+        };
+        if headline.title.is_empty() || imported_from(store, &source, Some(&content)) {
+            continue;
+        }
+
+        let children = headlines[index + 1..].iter()
+            .take_while(|child| child.level > headline.level)
+            .filter(|child| child.level == headline.level + 1);
+        let (mut answer, mut notes) = (None, None);
+        for child in children {
+            let text = Some(parse_org_section(&child.body).text).filter(|text| !text.is_empty());
+            match child.title {
+                ORG_ANSWER_HEADLINE => answer = text,
+                ORG_NOTES_HEADLINE => notes = text,
+                _ => {}
+            }
+        }
+
+        let property = |name: &str| section.properties.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| *value);
+        let new = NewReminder {
+            content,
+            answer,
+            algorithm: None,
+            tags: headline.tags.iter().filter(|tag| !tag.eq_ignore_ascii_case(ORG_REVIEW_TAG)).map(|tag| tag.to_string()).collect(),
+            due: section.scheduled,
+            priority: property("PRIORITY").and_then(|priority| Priority::from_str(priority, true).ok()).unwrap_or_default(),
+            deck: property("DECK").map(str::to_string),
+            recurrence: property("REPEATS").and_then(|rule| Recurrence::parse(rule).ok()),
+            notes,
+        };
+        let id = store.add_reminder(new, config);
+
+        let reminder = store.reminders.get_mut(&id).expect("Reminder was just added");
+        reminder.source = Some(source.clone());
+        if let Some(created) = property("CREATED").and_then(parse_org_time) {
+            reminder.created_at = created;
+        }
+        if let Some(review_count) = property("REVIEW_COUNT").and_then(|count| count.parse().ok()) {
+            reminder.review_count = review_count;
+        }
+        imported += 1;
+    }
+
+    Ok(imported)
+}
+
+/// Split an org file into its headlines; text before the first one is
+/// ignored. TODO keywords and priority cookies are dropped from titles.
+fn parse_org(text: &str) -> Vec<OrgHeadline<'_>> {
+    static TAGS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+:([\w@#%:]+):\s*$").expect("Tags pattern is valid"));
+    let mut headlines: Vec<OrgHeadline> = Vec::new();
+    for line in text.lines() {
+        let level = line.len() - line.trim_start_matches('*').len();
+        let Some(title) = (level > 0).then(|| line[level..].strip_prefix(' ')).flatten() else {
+            if let Some(headline) = headlines.last_mut() {
+                headline.body.push(line);
+            }
+            continue;
+        };
+
+        let (title, tags) = match TAGS.captures(title) {
+            Some(captures) => {
+                let start = captures.get(0).expect("Whole match exists").start();
+                let tags = captures.get(1).expect("Tags group always matches").as_str();
+                (&title[..start], tags.split(':').filter(|tag| !tag.is_empty()).collect())
+            }
+            None => (title, Vec::new()),
+        };
+        let mut title = title.trim();
+        for keyword in ["TODO ", "DONE "] {
+            title = title.strip_prefix(keyword).unwrap_or(title);
+        }
+        if title.starts_with("[#") && title.get(3..4) == Some("]") {
+            title = title[4..].trim_start();
+        }
+        headlines.push(OrgHeadline { level, title, tags, body: Vec::new() });
+    }
+    headlines
+}
+
+/// What the lines below a headline hold besides sub-headlines.
+struct OrgSection<'a> {
+    /// The text, without planning lines, drawers and common indentation
+    text: String,
+    scheduled: Option<DateTime<Utc>>,
+    properties: Vec<(&'a str, &'a str)>,
+}
+
+fn parse_org_section<'a>(body: &[&'a str]) -> OrgSection<'a> {
+    let mut lines = Vec::new();
+    let mut scheduled = None;
+    let mut properties = Vec::new();
+    let mut drawer: Option<&str> = None;
+    for line in body {
+        let trimmed = line.trim();
+        if let Some(name) = drawer {
+            if trimmed.eq_ignore_ascii_case(":END:") {
+                drawer = None;
+            } else if name.eq_ignore_ascii_case("PROPERTIES") {
+                if let Some((key, value)) = trimmed.strip_prefix(':').and_then(|property| property.split_once(':')) {
+                    properties.push((key, value.trim()));
+                }
+            }
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix(':').and_then(|name| name.strip_suffix(':')).filter(|name| !name.is_empty() && !name.contains(' ')) {
+            drawer = Some(name);
+            continue;
+        }
+        if ["SCHEDULED:", "DEADLINE:", "CLOSED:"].iter().any(|keyword| trimmed.starts_with(keyword)) {
+            if let Some((_, timestamp)) = trimmed.split_once("SCHEDULED:") {
+                scheduled = parse_org_time(timestamp.trim_start());
+            }
+            continue;
+        }
+        lines.push(*line);
+    }
+
+    let indent = lines.iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let text = lines.iter()
+        .map(|line| line.get(indent..).unwrap_or_default().trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    OrgSection { text, scheduled, properties }
+}
+
+/// An org timestamp such as `<2024-03-01 Fri 09:00>` or `[2024-03-01 Fri]`
+/// at the start of `text`, at midnight without a time.
+fn parse_org_time(text: &str) -> Option<DateTime<Utc>> {
+    static TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^[<\[](\d{4}-\d{2}-\d{2})(?:\s+[^\s\d>\]]+)?(?:\s+(\d{1,2}:\d{2}))?").expect("Timestamp pattern is valid")
+    });
+    let captures = TIMESTAMP.captures(text)?;
+    let date = NaiveDate::parse_from_str(&captures[1], "%Y-%m-%d").ok()?;
+    let time = match captures.get(2) {
+        Some(time) => NaiveTime::parse_from_str(time.as_str(), "%H:%M").ok()?,
+        None => NaiveTime::MIN,
+    };
+    dates::from_local(date.and_time(time))
+}
@@ -0,0 +1,46 @@
+// src/launcher.rs
+use std::ffi::OsStr;
+use std::process::{Command, Stdio};
+
+use reminder::{Error, Result};
+
+/// The platform's command for opening a file or URL with its default
+/// application.
+fn command(target: &OsStr) -> Command {
+    if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg(target);
+        command
+    } else if cfg!(windows) {
+        // The empty argument is the window title `start` expects first
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]).arg(target);
+        command
+    } else {
+        let mut command = Command::new("xdg-open");
+        command.arg(target);
+        command
+    }
+}
+
+/// Open `target`, a file or URL, with the application the desktop uses for
+/// it, such as an image viewer or the web browser.
+pub fn open(target: &OsStr) -> Result<()> {
+    let mut command = command(target);
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|e| Error::io(format!("Failed to launch '{}'", program), e))?;
+
+    if !status.success() {
+        return Err(Error::Invalid(format!(
+            "'{}' could not open {} ({})",
+            program,
+            target.to_string_lossy(),
+            status
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,1403 @@
+// src/lib.rs
+//! The reminder engine behind the `reminder` command: the reminder store,
+//! scheduling algorithms, storage backends and import/export. The binary is a
+//! thin CLI layer on top of this crate.
+pub mod attachments;
+pub mod audit;
+pub mod backup;
+pub mod clock;
+pub mod config;
+pub mod crypto;
+pub mod dates;
+pub mod diff;
+pub mod duration;
+pub mod error;
+pub mod export;
+pub mod import;
+pub mod lock;
+pub mod merge;
+pub mod profile;
+pub mod recurrence;
+pub mod scheduler;
+pub mod search;
+pub mod settings;
+pub mod simulate;
+pub mod stats;
+pub mod storage;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod undo;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use clap::ValueEnum;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::fs;
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+use config::{Config, LeechAction};
+pub use error::{Error, Result};
+use recurrence::Recurrence;
+use scheduler::Algorithm;
+
+/// How important a reminder is; higher priorities are shown first.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+/// Orders `list --sort` shows reminders in; ties go by ID.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    /// Oldest first
+    Created,
+    /// Soonest next review first
+    Due,
+    /// Fewest reviews first
+    Reviews,
+    /// Highest priority first, then soonest next review
+    Priority,
+}
+
+/// Where a reminder is in its life, for `list --status`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Status {
+    /// Waiting for its next review
+    Active,
+    /// Paused, so never due until resumed
+    Paused,
+    /// Done with its interval ladder
+    Completed,
+    /// Active with its next review already due
+    Overdue,
+}
+
+/// How `postpone` moves reminders' next reviews.
+#[derive(Clone, Copy, Debug)]
+pub enum Postponement {
+    /// Spread the reminders over this long, the first `check` would show
+    /// coming back first
+    Spread(Duration),
+    /// Delay each reminder by its interval times this factor less one, so
+    /// longer intervals move further
+    Proportional(f64),
+}
+
+/// How long a due reminder has been waiting; `check` groups its output by
+/// this, the longest waiting first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DueBucket {
+    /// Due more than a week ago
+    LongOverdue,
+    /// Due on an earlier day
+    Overdue,
+    /// Due today
+    Today,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+            Priority::Urgent => "urgent",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Namespace of the UUIDs derived for reminders stored before they had one.
+const LEGACY_UUID_NAMESPACE: Uuid = Uuid::from_u128(0x6f1c_2b7e_4d3a_4e0b_9a35_c1d8_72e4_0f59);
+
+/// Shortest code shown for a reminder, however few reminders there are.
+pub const MIN_CODE_LENGTH: usize = 4;
+
+/// A single reminder together with its scheduling state.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Reminder {
+    pub id: u32,
+    /// Identity that, unlike the ID, survives merges and renumbering;
+    /// `None` for reminders stored before UUIDs, see `Reminder::uuid`
+    #[serde(default)]
+    pub uuid: Option<Uuid>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub next_review: DateTime<Utc>,
+    pub review_count: u32,
+    pub completed: bool,
+    #[serde(default)]
+    pub algorithm: Algorithm,
+    /// Current interval in days
+    #[serde(default)]
+    pub interval: u32,
+    /// SM-2 ease factor
+    #[serde(default = "default_ease_factor")]
+    pub ease_factor: f64,
+    /// SM-2 count of consecutive successful reviews
+    #[serde(default)]
+    pub repetitions: u32,
+    /// FSRS memory stability in days
+    #[serde(default)]
+    pub stability: f64,
+    /// FSRS difficulty between 1 and 10
+    #[serde(default)]
+    pub difficulty: f64,
+    #[serde(default)]
+    pub last_review: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Times the reminder was snoozed since its last review
+    #[serde(default)]
+    pub snooze_count: u32,
+    /// Answer side of a two-sided card; `content` holds the question
+    #[serde(default)]
+    pub answer: Option<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub deck: Option<String>,
+    /// Every review of the reminder, oldest first
+    #[serde(default)]
+    pub history: Vec<ReviewEvent>,
+    /// Names of the attached files in the attachments directory
+    #[serde(default)]
+    pub attachments: Vec<String>,
+    /// Fixed schedule the reminder repeats on forever, instead of the
+    /// spaced repetition ladder
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// When scheduling was paused; a paused reminder is never due
+    #[serde(default)]
+    pub paused_at: Option<DateTime<Utc>>,
+    /// Reviews that failed to recall the reminder
+    #[serde(default)]
+    pub lapses: u32,
+    /// Review count the simple ladder is counted from, moved by "again" and
+    /// "hard" ratings
+    #[serde(default)]
+    pub ladder_start: u32,
+    /// Context, sources or mnemonics kept apart from the content, shown only
+    /// when asked for with `show --full`
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// The file an imported reminder came from, so importing it again can
+    /// skip it
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Interval ladder in days used instead of the deck's or the global one
+    #[serde(default)]
+    pub intervals: Option<Vec<u32>>,
+    /// Earlier contents, oldest first, kept when the content was edited
+    #[serde(default)]
+    pub versions: Vec<ContentVersion>,
+}
+
+/// An earlier content of a reminder, as it was before an edit replaced it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ContentVersion {
+    pub content: String,
+    /// When the edit replaced it
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// One review of a reminder, as recorded in its history.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReviewEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Recall grade, for the graded algorithms
+    pub grade: Option<u8>,
+    /// Interval in days chosen by the review
+    pub interval: u32,
+}
+
+/// Fields a caller can choose when adding a reminder; everything else is
+/// initialised by the store.
+#[derive(Default)]
+pub struct NewReminder {
+    pub content: String,
+    pub answer: Option<String>,
+    /// Scheduling algorithm, instead of the one configured for the deck,
+    /// tags or everything
+    pub algorithm: Option<Algorithm>,
+    pub tags: Vec<String>,
+    /// First review time, instead of the first interval of the ladder
+    pub due: Option<DateTime<Utc>>,
+    pub priority: Priority,
+    pub deck: Option<String>,
+    pub recurrence: Option<Recurrence>,
+    pub notes: Option<String>,
+}
+
+/// Changes requested by `edit`; fields left as `None` are kept.
+#[derive(Default)]
+pub struct ReminderChanges {
+    pub content: Option<String>,
+    pub answer: Option<String>,
+    pub priority: Option<Priority>,
+    pub deck: Option<String>,
+    /// `Some(None)` stops a recurring reminder from repeating
+    pub recurrence: Option<Option<Recurrence>>,
+    /// New notes; empty ones are removed
+    pub notes: Option<String>,
+}
+
+impl ReminderChanges {
+    pub fn is_empty(&self) -> bool {
+        self.content.is_none() && self.answer.is_none() && self.priority.is_none() && self.deck.is_none()
+            && self.recurrence.is_none() && self.notes.is_none()
+    }
+}
+
+impl Reminder {
+    /// The reminder's UUID. Reminders stored without one get a UUID derived
+    /// from their ID and creation time, so it stays the same until saved.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid.unwrap_or_else(|| {
+            let name = format!("{}-{}", self.id, self.created_at.timestamp_micros());
+            Uuid::new_v5(&LEGACY_UUID_NAMESPACE, name.as_bytes())
+        })
+    }
+
+    /// The full code of the reminder: its UUID in base 36. Any prefix of it
+    /// that no other reminder's code starts with stands for the reminder
+    /// wherever an ID is accepted.
+    pub fn code(&self) -> String {
+        // Twelve digits, each as likely as the others to be any of the 36
+        let mut value = self.uuid().as_u128();
+        let mut digits = Vec::new();
+        for _ in 0..12 {
+            digits.push(char::from_digit((value % 36) as u32, 36).expect("below 36"));
+            value /= 36;
+        }
+        digits.iter().collect()
+    }
+
+    /// Whether the reminder has one of the given priorities, or any when empty.
+    pub fn has_priority(&self, priorities: &[Priority]) -> bool {
+        priorities.is_empty() || priorities.contains(&self.priority)
+    }
+
+    /// Whether the reminder has one of the given statuses at `now`, or any
+    /// when empty.
+    pub fn has_status(&self, statuses: &[Status], now: DateTime<Utc>) -> bool {
+        statuses.is_empty() || statuses.iter().any(|status| match status {
+            Status::Active => self.is_scheduled(),
+            Status::Paused => !self.completed && self.paused_at.is_some(),
+            Status::Completed => self.completed,
+            Status::Overdue => self.is_due(now),
+        })
+    }
+
+    /// Whether the reminder is in the given deck, or any when `None`.
+    pub fn in_deck(&self, deck: Option<&str>) -> bool {
+        deck.is_none_or(|deck| self.deck.as_deref() == Some(deck.trim()))
+    }
+
+    /// How many versions the content has had, the current one included;
+    /// versions are numbered from 1, the content the reminder was added with.
+    pub fn version_count(&self) -> usize {
+        self.versions.len() + 1
+    }
+
+    /// The content as it was in version `number`, the last one being the
+    /// current content.
+    pub fn version(&self, number: usize) -> Result<&str> {
+        match number.checked_sub(1) {
+            Some(index) if index < self.versions.len() => Ok(&self.versions[index].content),
+            Some(index) if index == self.versions.len() => Ok(&self.content),
+            _ => Err(Error::Invalid(format!(
+                "Reminder {} has no version {}; its versions are 1 to {}", self.id, number, self.version_count()
+            ))),
+        }
+    }
+
+    /// When version `number` of the content was written.
+    pub fn version_written_at(&self, number: usize) -> Option<DateTime<Utc>> {
+        // Each version after the first was written when the one before was replaced
+        match number.checked_sub(1)? {
+            0 => Some(self.created_at),
+            index => self.versions.get(index - 1).map(|version| version.replaced_at),
+        }
+    }
+
+    /// Whether the reminder carries every one of the given tags.
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter().all(|tag| self.tags.contains(&normalize_tag(tag)))
+    }
+
+    /// Whether the reminder is waiting for its next review, rather than
+    /// completed or paused.
+    pub fn is_scheduled(&self) -> bool {
+        !self.completed && self.paused_at.is_none()
+    }
+
+    /// Whether the reminder is scheduled and its next review has come due at
+    /// `now`.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.is_scheduled() && dates::is_due(self.next_review, now)
+    }
+
+    /// How long the reminder has been due at `now`, if it is.
+    pub fn due_bucket(&self, now: DateTime<Utc>) -> Option<DueBucket> {
+        if !self.is_due(now) {
+            None
+        } else if self.next_review < now - Duration::weeks(1) {
+            Some(DueBucket::LongOverdue)
+        } else if dates::day_of(self.next_review) < dates::day_of(now) {
+            Some(DueBucket::Overdue)
+        } else {
+            Some(DueBucket::Today)
+        }
+    }
+
+    /// Whether the reminder keeps failing and was flagged with
+    /// [`LEECH_TAG`].
+    pub fn is_leech(&self) -> bool {
+        self.tags.iter().any(|tag| tag == LEECH_TAG)
+    }
+
+    /// Whether reviews take a grade: they do with the graded algorithms,
+    /// unless the reminder repeats on a fixed schedule.
+    pub fn is_graded(&self) -> bool {
+        self.recurrence.is_none() && self.algorithm != Algorithm::Simple
+    }
+
+    /// The web addresses in the content and then the answer, in order of
+    /// appearance and without repeats.
+    pub fn urls(&self) -> Vec<&str> {
+        let mut urls: Vec<&str> = Vec::new();
+        for text in std::iter::once(&self.content).chain(&self.answer) {
+            for url in find_urls(text) {
+                if !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+        }
+        urls
+    }
+}
+
+/// Sort `reminders` by `key`, ties by ID.
+pub fn sort_reminders(reminders: &mut [&Reminder], key: SortKey) {
+    match key {
+        SortKey::Created => reminders.sort_by_key(|r| (r.created_at, r.id)),
+        SortKey::Due => reminders.sort_by_key(|r| (r.next_review, r.id)),
+        SortKey::Reviews => reminders.sort_by_key(|r| (r.review_count, r.id)),
+        SortKey::Priority => reminders.sort_by_key(|r| (Reverse(r.priority), r.next_review, r.id)),
+    }
+}
+
+/// The `http` and `https` URLs in `text`. Punctuation right after a URL, as
+/// in "see https://example.com.", and the closing bracket of a Markdown link
+/// are not part of it.
+pub fn find_urls(text: &str) -> Vec<&str> {
+    static URL: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"https?://[^\s<>"`]+"#).expect("URL pattern is valid"));
+
+    URL.find_iter(text)
+        .map(|found| {
+            let mut url = found.as_str();
+            loop {
+                let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '"']);
+                let trimmed = match trimmed.chars().last() {
+                    Some(close @ (')' | ']')) => {
+                        let open = if close == ')' { '(' } else { '[' };
+                        if trimmed.matches(close).count() > trimmed.matches(open).count() {
+                            &trimmed[..trimmed.len() - 1]
+                        } else {
+                            trimmed
+                        }
+                    }
+                    _ => trimmed,
+                };
+                if trimmed == url {
+                    break url;
+                }
+                url = trimmed;
+            }
+        })
+        .filter(|url| !url.ends_with("://"))
+        .collect()
+}
+
+/// Tag given to reminders that keep failing.
+pub const LEECH_TAG: &str = "leech";
+
+/// Whether the reminder's latest lapse makes it a leech: at the threshold,
+/// and again every half threshold after, as in Anki, in case the tag was
+/// taken off after rewriting the reminder.
+fn is_leech_lapse(lapses: u32, threshold: u32) -> bool {
+    match threshold {
+        0 => false,
+        _ if lapses < threshold => false,
+        _ => (lapses - threshold).is_multiple_of((threshold / 2).max(1)),
+    }
+}
+
+pub(crate) fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Trim a deck name, treating an empty one as no deck.
+fn normalize_deck(deck: Option<String>) -> Option<String> {
+    deck.map(|deck| deck.trim().to_string())
+        .filter(|deck| !deck.is_empty())
+}
+
+fn default_ease_factor() -> f64 {
+    scheduler::DEFAULT_EASE_FACTOR
+}
+
+/// Every reminder, keyed by ID. Load and save it through a
+/// [`storage::Storage`] backend.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReminderStore {
+    /// Format the store was saved in; older ones are upgraded when read, see
+    /// [`storage::migrations`]
+    #[serde(default)]
+    pub version: u32,
+    pub reminders: HashMap<u32, Reminder>,
+    pub next_id: u32,
+    /// Reminders set aside with `archive`; they keep their state but are
+    /// never due until restored
+    #[serde(default)]
+    pub archived: HashMap<u32, Reminder>,
+    /// Review activity per local calendar day, used for streaks
+    #[serde(default)]
+    pub days: BTreeMap<NaiveDate, DayRecord>,
+    /// Schedule shifts made with `vacation`, oldest first
+    #[serde(default)]
+    pub vacations: Vec<Vacation>,
+    /// Links between related reminders made with `link`, each pair of IDs
+    /// stored lowest first
+    #[serde(default)]
+    pub links: BTreeSet<(u32, u32)>,
+    /// Prerequisites made with `depend`, ordered by the dependent reminder
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    /// Removed reminders, kept until `trash_days` have passed so they can
+    /// be restored
+    #[serde(default)]
+    pub trash: BTreeMap<u32, TrashedReminder>,
+}
+
+impl Default for ReminderStore {
+    fn default() -> Self {
+        ReminderStore {
+            version: storage::migrations::STORE_VERSION,
+            reminders: HashMap::new(),
+            next_id: 0,
+            archived: HashMap::new(),
+            days: BTreeMap::new(),
+            vacations: Vec::new(),
+            links: BTreeSet::new(),
+            dependencies: Vec::new(),
+            trash: BTreeMap::new(),
+        }
+    }
+}
+
+/// A removed reminder waiting in the trash.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrashedReminder {
+    pub reminder: Reminder,
+    pub removed_at: DateTime<Utc>,
+    /// Whether it was archived, to restore it to the archive
+    #[serde(default)]
+    pub archived: bool,
+    /// The reminders it was linked to, linked again when it is restored
+    #[serde(default)]
+    pub links: Vec<u32>,
+    /// Its prerequisites and the reminders it was a prerequisite of, made
+    /// again when it is restored
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+}
+
+/// Reminder `id` isn't due before reminder `on` has been completed, or
+/// reviewed `reviews` times when that is given.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Dependency {
+    pub id: u32,
+    pub on: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviews: Option<u32>,
+}
+
+/// A time away during which no reviews were scheduled.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Vacation {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// When the schedules were shifted
+    pub recorded_at: DateTime<Utc>,
+    /// How many reminders were moved
+    pub shifted: usize,
+}
+
+/// What happened on one day, keyed by the local date at the time.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DayRecord {
+    pub reviews: usize,
+    /// Whether every due review was done that day
+    pub cleared: bool,
+}
+
+/// The ID of the one reminder whose code starts with `prefix`.
+fn match_code<'a>(reminders: impl Iterator<Item = &'a Reminder>, prefix: &str) -> Result<u32> {
+    let prefix = prefix.trim().to_lowercase();
+    let mut matches: Vec<u32> = reminders
+        .filter(|r| r.code().starts_with(&prefix))
+        .map(|r| r.id)
+        .collect();
+    matches.sort_unstable();
+    match matches.as_slice() {
+        [] => Err(Error::Invalid(format!("No reminder has a code starting with '{}'", prefix))),
+        [id] => Ok(*id),
+        ids => {
+            let listed: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+            Err(Error::Invalid(format!(
+                "Code '{}' is ambiguous, it starts the codes of reminders {}; give more of it",
+                prefix,
+                listed.join(", ")
+            )))
+        }
+    }
+}
+
+impl ReminderStore {
+    pub fn add_reminder(&mut self, new: NewReminder, config: &Config) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let mut tags: Vec<String> = new.tags.iter()
+            .map(|tag| normalize_tag(tag))
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+
+        let deck = normalize_deck(new.deck);
+        let now = clock::now();
+        let interval = scheduler::adjust_interval(config.first_interval(deck.as_deref(), &tags), config);
+        let algorithm = new.algorithm.unwrap_or_else(|| config.algorithm_for(deck.as_deref(), &tags));
+        let reminder = Reminder {
+            id,
+            uuid: Some(Uuid::new_v4()),
+            content: new.content,
+            created_at: now,
+            next_review: new.due.unwrap_or_else(|| match &new.recurrence {
+                Some(recurrence) => recurrence.first_review(now),
+                // Only fails right at the end of representable time, where
+                // it might as well be due
+                None => scheduler::fuzzed_due(id, 0, interval, config).unwrap_or(DateTime::<Utc>::MAX_UTC),
+            }),
+            review_count: 0,
+            completed: false,
+            algorithm,
+            interval,
+            ease_factor: config.starting_ease,
+            repetitions: 0,
+            stability: 0.0,
+            difficulty: 0.0,
+            last_review: None,
+            tags,
+            snooze_count: 0,
+            answer: new.answer,
+            priority: new.priority,
+            deck,
+            history: Vec::new(),
+            attachments: Vec::new(),
+            recurrence: new.recurrence,
+            paused_at: None,
+            lapses: 0,
+            ladder_start: 0,
+            notes: new.notes.filter(|notes| !notes.trim().is_empty()),
+            source: None,
+            intervals: None,
+            versions: Vec::new(),
+        };
+
+        self.reminders.insert(id, reminder);
+        id
+    }
+
+    pub fn review_reminder(&mut self, id: u32, grade: Option<u8>, config: &Config) -> Result<()> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        if reminder.completed {
+            return Err(Error::Invalid("Reminder is already completed".to_string()));
+        }
+        if reminder.paused_at.is_some() {
+            return Err(Error::Invalid(format!("Reminder {} is paused, resume it first", id)));
+        }
+
+        // Reviewed on a copy, so a review that can't be scheduled changes nothing
+        let lapses = reminder.lapses;
+        let mut reviewed = reminder.clone();
+        scheduler::review(&mut reviewed, grade, config)?;
+        *reminder = reviewed;
+        reminder.snooze_count = 0;
+
+        if reminder.lapses > lapses && is_leech_lapse(reminder.lapses, config.leech_threshold) {
+            if !reminder.is_leech() {
+                reminder.tags.push(LEECH_TAG.to_string());
+                reminder.tags.sort();
+            }
+            if config.leech_action == LeechAction::Suspend {
+                reminder.paused_at = Some(clock::now());
+            }
+        }
+
+        let (still_due, _) = self.apply_daily_limit(self.get_due_reminders(), config);
+        let cleared = still_due.is_empty();
+        let day = self.days.entry(dates::today()).or_default();
+        day.reviews += 1;
+        day.cleared |= cleared;
+        Ok(())
+    }
+
+    pub fn edit_reminder(&mut self, id: u32, changes: ReminderChanges) -> Result<()> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        if changes.content.as_ref().is_some_and(|c| c.trim().is_empty()) {
+            return Err(Error::Invalid("Reminder content cannot be empty".to_string()));
+        }
+
+        if let Some(content) = changes.content.filter(|content| *content != reminder.content) {
+            let replaced = std::mem::replace(&mut reminder.content, content);
+            reminder.versions.push(ContentVersion { content: replaced, replaced_at: clock::now() });
+        }
+        if let Some(answer) = changes.answer {
+            reminder.answer = Some(answer).filter(|a| !a.trim().is_empty());
+        }
+        if let Some(priority) = changes.priority {
+            reminder.priority = priority;
+        }
+        if changes.deck.is_some() {
+            reminder.deck = normalize_deck(changes.deck);
+        }
+        if let Some(recurrence) = changes.recurrence {
+            // A reminder that finished its ladder starts repeating again
+            if let (Some(recurrence), true) = (&recurrence, reminder.completed) {
+                reminder.completed = false;
+                reminder.next_review = recurrence.first_review(clock::now());
+            }
+            reminder.recurrence = recurrence;
+        }
+        if let Some(notes) = changes.notes {
+            reminder.notes = Some(notes).filter(|n| !n.trim().is_empty());
+        }
+        Ok(())
+    }
+
+    /// Make version `number` of a reminder's content its content again,
+    /// keeping the current content as the newest earlier version. Returns
+    /// false when it already is the content.
+    pub fn restore_version(&mut self, id: u32, number: usize) -> Result<bool> {
+        let reminder = self.reminders.get(&id).ok_or(Error::NotFound(id))?;
+        let content = reminder.version(number)?.to_string();
+        if content == reminder.content {
+            return Ok(false);
+        }
+        self.edit_reminder(id, ReminderChanges { content: Some(content), ..Default::default() })?;
+        Ok(true)
+    }
+
+    pub fn schedule_reminder(&mut self, id: u32, when: DateTime<Utc>) -> Result<()> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        if reminder.completed {
+            return Err(Error::Invalid("Reminder is already completed".to_string()));
+        }
+
+        reminder.next_review = when;
+        Ok(())
+    }
+
+    /// Give reminder `id` an interval ladder of its own, or with `None` go
+    /// back to its deck's or the global one. Only the simple algorithm
+    /// follows a ladder.
+    pub fn set_intervals(&mut self, id: u32, intervals: Option<Vec<u32>>) -> Result<()> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        if intervals.is_some() {
+            if reminder.recurrence.is_some() {
+                return Err(Error::Invalid(format!("Reminder {} repeats on a fixed schedule, not an interval ladder", id)));
+            }
+            if reminder.algorithm != Algorithm::Simple {
+                return Err(Error::Invalid(format!(
+                    "Reminder {} uses the {} algorithm, which doesn't follow an interval ladder",
+                    id, reminder.algorithm
+                )));
+            }
+        }
+
+        reminder.intervals = intervals;
+        Ok(())
+    }
+
+    /// Record that the file stored as `name` is attached to reminder `id`.
+    pub fn add_attachment(&mut self, id: u32, name: String) -> Result<()> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+        reminder.attachments.push(name);
+        Ok(())
+    }
+
+    pub fn snooze_reminder(&mut self, id: u32, duration: Duration) -> Result<()> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        if reminder.completed {
+            return Err(Error::Invalid("Reminder is already completed".to_string()));
+        }
+        if reminder.paused_at.is_some() {
+            return Err(Error::Invalid(format!("Reminder {} is paused, resume it first", id)));
+        }
+
+        // Overdue reminders are snoozed from now, upcoming ones from their due time
+        reminder.next_review = reminder.next_review.max(clock::now()).checked_add_signed(duration)
+            .ok_or_else(|| Error::Invalid(format!("Reminder {} can't be snoozed that far", id)))?;
+        reminder.snooze_count += 1;
+        Ok(())
+    }
+
+    /// Move the next reviews of `ids` later without counting as a review,
+    /// e.g. to catch up after a long break: overdue reminders from now,
+    /// upcoming ones from their due time. Returns the IDs in the order they
+    /// come back.
+    pub fn postpone_reminders(&mut self, ids: &[u32], how: Postponement) -> Result<Vec<u32>> {
+        match how {
+            Postponement::Spread(over) if over <= Duration::zero() => {
+                return Err(Error::Invalid("Reminders can only be postponed by a positive duration".to_string()));
+            }
+            Postponement::Proportional(factor) if !(factor.is_finite() && factor > 1.0) => {
+                return Err(Error::Invalid(format!("The factor has to be above 1, e.g. 1.2, not {}", factor)));
+            }
+            _ => {}
+        }
+
+        let mut reminders = Vec::with_capacity(ids.len());
+        for &id in ids {
+            let reminder = self.reminders.get(&id).ok_or(Error::NotFound(id))?;
+            if reminder.completed {
+                return Err(Error::Invalid(format!("Reminder {} is already completed", id)));
+            }
+            if reminder.paused_at.is_some() {
+                return Err(Error::Invalid(format!("Reminder {} is paused, resume it first", id)));
+            }
+            reminders.push(reminder);
+        }
+        sort_reminders(&mut reminders, SortKey::Priority);
+        let order: Vec<u32> = reminders.iter().map(|r| r.id).collect();
+
+        // Worked out for all of them first, so none moves when one can't
+        let now = clock::now();
+        let count = order.len() as i32;
+        let mut next_reviews = Vec::with_capacity(order.len());
+        for (index, id) in order.iter().enumerate() {
+            let reminder = &self.reminders[id];
+            let delay = match how {
+                Postponement::Spread(over) => {
+                    over.checked_mul(index as i32 + 1).and_then(|delay| delay.checked_div(count))
+                }
+                Postponement::Proportional(factor) => {
+                    Duration::try_seconds((reminder.interval.max(1) as f64 * 86_400.0 * (factor - 1.0)).round() as i64)
+                }
+            };
+            let next_review = delay.and_then(|delay| reminder.next_review.max(now).checked_add_signed(delay))
+                .ok_or_else(|| Error::Invalid(format!("Reminder {} can't be postponed that far", id)))?;
+            next_reviews.push(next_review);
+        }
+        for (id, next_review) in order.iter().zip(next_reviews) {
+            self.reminders.get_mut(id).expect("checked above").next_review = next_review;
+        }
+        Ok(order)
+    }
+
+    /// Freeze a reminder's schedule until it is resumed.
+    pub fn pause_reminder(&mut self, id: u32) -> Result<()> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        if reminder.completed {
+            return Err(Error::Invalid("Reminder is already completed".to_string()));
+        }
+        if reminder.paused_at.is_some() {
+            return Err(Error::Invalid(format!("Reminder {} is already paused", id)));
+        }
+
+        reminder.paused_at = Some(clock::now());
+        Ok(())
+    }
+
+    /// Continue a paused reminder's schedule, moving its next review back by
+    /// as long as it was paused. Returns how long that was.
+    pub fn resume_reminder(&mut self, id: u32) -> Result<Duration> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        let paused_at = reminder.paused_at
+            .ok_or_else(|| Error::Invalid(format!("Reminder {} is not paused", id)))?;
+        let paused_for = (clock::now() - paused_at).max(Duration::zero());
+        reminder.next_review = reminder.next_review.checked_add_signed(paused_for)
+            .ok_or_else(|| Error::Invalid(format!("Reminder {} can't be moved back by as long as it was paused", id)))?;
+        reminder.paused_at = None;
+        Ok(paused_for)
+    }
+
+    /// Put a completed reminder back into rotation, due at `when`. It keeps
+    /// its reviews, so the next one decides again whether it is done.
+    pub fn reopen_reminder(&mut self, id: u32, when: DateTime<Utc>) -> Result<()> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        if !reminder.completed {
+            return Err(Error::Invalid(format!("Reminder {} isn't completed", id)));
+        }
+
+        reminder.completed = false;
+        reminder.next_review = when;
+        Ok(())
+    }
+
+    /// Start a reminder's schedule over as if it had just been added, due at
+    /// `when`: no reviews, not completed and with the algorithm's state back
+    /// where it starts. Its past reviews and lapses are forgotten too unless
+    /// `keep_history`.
+    pub fn reset_reminder(&mut self, id: u32, when: DateTime<Utc>, keep_history: bool, config: &Config) -> Result<()> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        reminder.review_count = 0;
+        reminder.completed = false;
+        reminder.next_review = when;
+        reminder.interval = scheduler::adjust_interval(scheduler::ladder(reminder, config)[0], config);
+        reminder.ease_factor = config.starting_ease;
+        reminder.repetitions = 0;
+        reminder.stability = 0.0;
+        reminder.difficulty = 0.0;
+        reminder.ladder_start = 0;
+        reminder.snooze_count = 0;
+        if !keep_history {
+            reminder.history.clear();
+            reminder.lapses = 0;
+            reminder.last_review = None;
+        }
+        Ok(())
+    }
+
+    /// Move every review scheduled between `from` and `to` to after `to`,
+    /// keeping the spacing between them so they don't all come due at once.
+    /// Recurring reminders move to their first occurrence after `to`. The
+    /// shift is recorded in [`ReminderStore::vacations`].
+    pub fn take_vacation(&mut self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<&Vacation> {
+        if to <= from {
+            return Err(Error::Invalid("The vacation has to end after it starts".to_string()));
+        }
+
+        // Worked out for all of them first, so none moves when one can't
+        let length = to - from;
+        let mut next_reviews = Vec::new();
+        for reminder in self.reminders.values() {
+            if !reminder.is_scheduled() || reminder.next_review < from || reminder.next_review >= to {
+                continue;
+            }
+            let next_review = match &reminder.recurrence {
+                Some(recurrence) => recurrence.next_review(reminder.next_review, to),
+                None => reminder.next_review.checked_add_signed(length)
+                    .ok_or_else(|| Error::Invalid(format!("Reminder {} can't be moved past the vacation", reminder.id)))?,
+            };
+            next_reviews.push((reminder.id, next_review));
+        }
+        let shifted = next_reviews.len();
+        for (id, next_review) in next_reviews {
+            self.reminders.get_mut(&id).expect("checked above").next_review = next_review;
+        }
+
+        self.vacations.push(Vacation { from, to, recorded_at: clock::now(), shifted });
+        Ok(self.vacations.last().expect("Vacation was just recorded"))
+    }
+
+    pub fn get_due_reminders(&self) -> Vec<&Reminder> {
+        let now = clock::now();
+        let mut reminders: Vec<&Reminder> = self.reminders
+            .values()
+            .filter(|r| r.is_due(now) && !self.is_blocked(r.id))
+            .collect();
+        reminders.sort_by_key(|r| (Reverse(r.priority), r.next_review, r.id));
+        reminders
+    }
+
+    /// Cut `due` down to what fits in today's review limits, counting the
+    /// reviews already done today. The order of `due` decides what is shown,
+    /// so the overflow is the same every time and comes up on later days.
+    /// Returns the reminders to show and how many were deferred.
+    pub fn apply_daily_limit<'a>(&self, due: Vec<&'a Reminder>, config: &Config) -> (Vec<&'a Reminder>, usize) {
+        let today = dates::today();
+        let mut done_total = 0;
+        let mut done_per_deck: HashMap<&str, usize> = HashMap::new();
+        let mut done_per_tag: HashMap<&str, usize> = HashMap::new();
+        for reminder in self.reminders.values() {
+            if reminder.last_review.is_some_and(|last| dates::day_of(last) == today) {
+                done_total += 1;
+                if let Some(deck) = reminder.deck.as_deref() {
+                    *done_per_deck.entry(deck).or_insert(0) += 1;
+                }
+                for tag in &reminder.tags {
+                    *done_per_tag.entry(tag).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let total = due.len();
+        let mut shown = Vec::new();
+        for reminder in due {
+            if config.max_daily_reviews.is_some_and(|limit| done_total >= limit) {
+                break;
+            }
+            // A reminder has to fit in the limits of its deck and of every tag
+            let deck_full = reminder.deck.as_deref().is_some_and(|deck| {
+                config.deck_daily_limit(deck).is_some_and(|limit| done_per_deck.get(deck).copied().unwrap_or(0) >= limit)
+            });
+            let tag_full = reminder.tags.iter().any(|tag| {
+                config.tag_daily_limit(tag).is_some_and(|limit| done_per_tag.get(tag.as_str()).copied().unwrap_or(0) >= limit)
+            });
+            if deck_full || tag_full {
+                continue;
+            }
+            if let Some(deck) = reminder.deck.as_deref() {
+                *done_per_deck.entry(deck).or_insert(0) += 1;
+            }
+            for tag in &reminder.tags {
+                *done_per_tag.entry(tag).or_insert(0) += 1;
+            }
+            done_total += 1;
+            shown.push(reminder);
+        }
+
+        let deferred = total - shown.len();
+        (shown, deferred)
+    }
+
+    pub fn get_all_reminders(&self) -> Vec<&Reminder> {
+        let mut reminders: Vec<&Reminder> = self.reminders.values().collect();
+        sort_reminders(&mut reminders, SortKey::Priority);
+        reminders
+    }
+
+    pub fn get_tag_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for tag in self.reminders.values().flat_map(|r| &r.tags) {
+            *counts.entry(tag.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn get_deck_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for deck in self.reminders.values().filter_map(|r| r.deck.as_deref()) {
+            *counts.entry(deck).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Rename a deck and return how many reminders were moved.
+    pub fn rename_deck(&mut self, old: &str, new: &str) -> Result<usize> {
+        let new = normalize_deck(Some(new.to_string()))
+            .ok_or_else(|| Error::Invalid("Deck name cannot be empty".to_string()))?;
+
+        let mut renamed = 0;
+        for reminder in self.reminders.values_mut().chain(self.archived.values_mut()) {
+            if reminder.deck.as_deref() == Some(old.trim()) {
+                reminder.deck = Some(new.clone());
+                renamed += 1;
+            }
+        }
+
+        if renamed == 0 {
+            return Err(Error::DeckNotFound(old.to_string()));
+        }
+        Ok(renamed)
+    }
+
+    /// Take every reminder out of a deck, or move them to the trash with
+    /// `with_reminders`, and return how many were affected.
+    pub fn remove_deck(&mut self, name: &str, with_reminders: bool) -> Result<usize> {
+        let in_deck = |r: &Reminder| r.deck.as_deref() == Some(name.trim());
+        let ids: Vec<u32> = self.reminders.values().chain(self.archived.values())
+            .filter(|r| in_deck(r))
+            .map(|r| r.id)
+            .collect();
+
+        if ids.is_empty() {
+            return Err(Error::DeckNotFound(name.to_string()));
+        }
+
+        for id in &ids {
+            if with_reminders {
+                self.move_to_trash(*id);
+            } else if let Some(reminder) = self.reminders.get_mut(id).or(self.archived.get_mut(id)) {
+                reminder.deck = None;
+            }
+        }
+        Ok(ids.len())
+    }
+
+    /// Add a tag to a reminder; returns whether it was new.
+    pub fn add_tag(&mut self, id: u32, tag: &str) -> Result<bool> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        let tag = normalize_tag(tag);
+        if tag.is_empty() {
+            return Err(Error::Invalid("Tag cannot be empty".to_string()));
+        }
+        if reminder.tags.contains(&tag) {
+            return Ok(false);
+        }
+        reminder.tags.push(tag);
+        reminder.tags.sort();
+        Ok(true)
+    }
+
+    /// Remove a tag from a reminder; returns whether it had the tag.
+    pub fn remove_tag(&mut self, id: u32, tag: &str) -> Result<bool> {
+        let reminder = self.reminders.get_mut(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        let tag = normalize_tag(tag);
+        let before = reminder.tags.len();
+        reminder.tags.retain(|t| *t != tag);
+        Ok(reminder.tags.len() != before)
+    }
+
+    /// Move a reminder to the trash.
+    pub fn remove_reminder(&mut self, id: u32) -> Result<()> {
+        if !self.reminders.contains_key(&id) {
+            return Err(Error::NotFound(id));
+        }
+        self.move_to_trash(id);
+        Ok(())
+    }
+
+    /// Move an active or archived reminder to the trash, unlinking it.
+    fn move_to_trash(&mut self, id: u32) {
+        let (reminder, archived) = match self.reminders.remove(&id) {
+            Some(reminder) => (reminder, false),
+            None => match self.archived.remove(&id) {
+                Some(reminder) => (reminder, true),
+                None => return,
+            },
+        };
+        let links = self.linked(id);
+        self.links.retain(|(a, b)| *a != id && *b != id);
+        let (dependencies, kept) = self.dependencies.iter().partition(|d| d.id == id || d.on == id);
+        self.dependencies = kept;
+        self.trash.insert(id, TrashedReminder { reminder, removed_at: clock::now(), archived, links, dependencies });
+    }
+
+    /// Removed reminders, most recently removed first.
+    pub fn get_trashed_reminders(&self) -> Vec<&TrashedReminder> {
+        let mut trashed: Vec<&TrashedReminder> = self.trash.values().collect();
+        trashed.sort_by_key(|t| (Reverse(t.removed_at), t.reminder.id));
+        trashed
+    }
+
+    /// Take a reminder out of the trash, with its links to reminders that
+    /// still exist. It gets a new ID if a merge has brought back another
+    /// reminder under its old one; the ID it ends up with is returned.
+    pub fn restore_from_trash(&mut self, id: u32) -> Result<u32> {
+        let TrashedReminder { mut reminder, archived, links, dependencies, .. } = self.trash.remove(&id)
+            .ok_or(Error::TrashedNotFound(id))?;
+        let old_id = id;
+        let id = if self.find_reminder(id).is_some() {
+            let new_id = self.next_id;
+            self.next_id += 1;
+            new_id
+        } else {
+            id
+        };
+        reminder.id = id;
+        if archived {
+            self.archived.insert(id, reminder);
+        } else {
+            self.reminders.insert(id, reminder);
+        }
+        for other in links {
+            if self.find_reminder(other).is_some() {
+                self.links.insert((id.min(other), id.max(other)));
+            }
+        }
+        let renumber = |other: u32| if other == old_id { id } else { other };
+        for dependency in dependencies {
+            // Those that would now close a cycle are dropped
+            let _ = self.depend(renumber(dependency.id), renumber(dependency.on), dependency.reviews);
+        }
+        Ok(id)
+    }
+
+    /// Delete every reminder in the trash for good, returning how many there
+    /// were.
+    pub fn empty_trash(&mut self) -> usize {
+        let count = self.trash.len();
+        self.trash.clear();
+        count
+    }
+
+    /// Delete the reminders that have been in the trash for longer than
+    /// `days`, returning how many.
+    pub fn purge_trash(&mut self, days: u32, now: DateTime<Utc>) -> usize {
+        let count = self.trash.len();
+        self.trash.retain(|_, trashed| now - trashed.removed_at < Duration::days(days as i64));
+        count - self.trash.len()
+    }
+
+    pub fn archive_reminder(&mut self, id: u32) -> Result<()> {
+        let reminder = self.reminders.remove(&id)
+            .ok_or(Error::NotFound(id))?;
+        self.archived.insert(id, reminder);
+        Ok(())
+    }
+
+    pub fn restore_reminder(&mut self, id: u32) -> Result<()> {
+        let reminder = self.archived.remove(&id)
+            .ok_or(Error::ArchivedNotFound(id))?;
+        self.reminders.insert(id, reminder);
+        Ok(())
+    }
+
+    /// Link two reminders as related, returning whether they weren't linked
+    /// yet. Links are undirected, and archived reminders can be linked too.
+    pub fn link(&mut self, id: u32, other: u32) -> Result<bool> {
+        if id == other {
+            return Err(Error::Invalid(format!("Reminder {} can't be linked to itself", id)));
+        }
+        for id in [id, other] {
+            self.find_reminder(id).ok_or(Error::NotFound(id))?;
+        }
+        Ok(self.links.insert((id.min(other), id.max(other))))
+    }
+
+    /// Remove the link between two reminders, returning whether there was one.
+    pub fn unlink(&mut self, id: u32, other: u32) -> Result<bool> {
+        for id in [id, other] {
+            self.find_reminder(id).ok_or(Error::NotFound(id))?;
+        }
+        Ok(self.links.remove(&(id.min(other), id.max(other))))
+    }
+
+    /// The IDs of the reminders linked to `id`, lowest first.
+    pub fn linked(&self, id: u32) -> Vec<u32> {
+        self.links.iter()
+            .filter_map(|&(a, b)| match (a == id, b == id) {
+                (true, _) => Some(b),
+                (_, true) => Some(a),
+                _ => None,
+            })
+            .filter(|other| self.find_reminder(*other).is_some())
+            .collect()
+    }
+
+    /// Make reminder `id` wait for reminder `on`, to be completed or reviewed
+    /// `reviews` times, replacing what it waited for from `on` before. Returns
+    /// whether anything changed. A reminder can't end up waiting for itself,
+    /// not even through others.
+    pub fn depend(&mut self, id: u32, on: u32, reviews: Option<u32>) -> Result<bool> {
+        for id in [id, on] {
+            self.find_reminder(id).ok_or(Error::NotFound(id))?;
+        }
+        if id == on {
+            return Err(Error::Invalid(format!("Reminder {} can't depend on itself", id)));
+        }
+        if self.depends_on(on, id) {
+            return Err(Error::Invalid(format!("Reminder {} can't depend on {}, which waits for it", id, on)));
+        }
+        let dependency = Dependency { id, on, reviews };
+        match self.dependencies.iter().position(|d| (d.id, d.on) == (id, on)) {
+            Some(index) if self.dependencies[index] == dependency => Ok(false),
+            Some(index) => {
+                self.dependencies[index] = dependency;
+                Ok(true)
+            }
+            None => {
+                self.dependencies.push(dependency);
+                self.dependencies.sort();
+                Ok(true)
+            }
+        }
+    }
+
+    /// Stop reminder `id` from waiting for reminder `on`, returning whether it
+    /// did.
+    pub fn undepend(&mut self, id: u32, on: u32) -> Result<bool> {
+        for id in [id, on] {
+            self.find_reminder(id).ok_or(Error::NotFound(id))?;
+        }
+        let count = self.dependencies.len();
+        self.dependencies.retain(|d| (d.id, d.on) != (id, on));
+        Ok(self.dependencies.len() < count)
+    }
+
+    /// Whether reminder `id` waits for `on`, directly or through others.
+    fn depends_on(&self, id: u32, on: u32) -> bool {
+        let mut seen = BTreeSet::from([id]);
+        let mut queue = VecDeque::from([id]);
+        while let Some(current) = queue.pop_front() {
+            for dependency in self.dependencies.iter().filter(|d| d.id == current) {
+                if dependency.on == on {
+                    return true;
+                }
+                if seen.insert(dependency.on) {
+                    queue.push_back(dependency.on);
+                }
+            }
+        }
+        false
+    }
+
+    /// The prerequisites of reminder `id` that haven't been met yet. An
+    /// archived prerequisite has been set aside and doesn't hold it back.
+    pub fn blockers(&self, id: u32) -> Vec<&Dependency> {
+        self.dependencies.iter()
+            .filter(|d| d.id == id)
+            .filter(|d| self.reminders.get(&d.on).is_some_and(|on| {
+                !on.completed && d.reviews.is_none_or(|reviews| on.review_count < reviews)
+            }))
+            .collect()
+    }
+
+    /// Whether reminder `id` waits for a prerequisite, so it isn't due yet.
+    pub fn is_blocked(&self, id: u32) -> bool {
+        !self.blockers(id).is_empty()
+    }
+
+    /// The reminders reachable from `id` over at most `depth` links, each
+    /// with its distance, nearest first.
+    pub fn related(&self, id: u32, depth: usize) -> Vec<(u32, usize)> {
+        let mut seen = BTreeSet::from([id]);
+        let mut queue = VecDeque::from([(id, 0)]);
+        let mut related = Vec::new();
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance == depth {
+                continue;
+            }
+            for next in self.linked(current) {
+                if seen.insert(next) {
+                    related.push((next, distance + 1));
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+        related
+    }
+
+    /// Look up a reminder whether it is active or archived.
+    pub fn find_reminder(&self, id: u32) -> Option<&Reminder> {
+        self.reminders.get(&id).or_else(|| self.archived.get(&id))
+    }
+
+    /// Store the derived UUID of each reminder that was stored without one.
+    pub fn assign_uuids(&mut self) {
+        for reminder in self.reminders.values_mut().chain(self.archived.values_mut()) {
+            reminder.uuid = Some(reminder.uuid());
+        }
+    }
+
+    /// The ID of the active or archived reminder whose code starts with
+    /// `prefix`, which has to pick a single one.
+    pub fn find_by_code(&self, prefix: &str) -> Result<u32> {
+        match_code(self.reminders.values().chain(self.archived.values()), prefix)
+    }
+
+    /// The ID of the reminder in the trash whose code starts with `prefix`.
+    pub fn find_trashed_by_code(&self, prefix: &str) -> Result<u32> {
+        match_code(self.trash.values().map(|trashed| &trashed.reminder), prefix)
+    }
+
+    /// The ID of the active, archived or trashed reminder whose code starts
+    /// with `prefix`.
+    pub fn find_any_by_code(&self, prefix: &str) -> Result<u32> {
+        let trashed = self.trash.values().map(|trashed| &trashed.reminder);
+        match_code(self.reminders.values().chain(self.archived.values()).chain(trashed), prefix)
+    }
+
+    /// The shortest unambiguous code of every active and archived reminder,
+    /// at least `MIN_CODE_LENGTH` long and never all digits, so it can't be
+    /// taken for an ID.
+    pub fn short_codes(&self) -> HashMap<u32, String> {
+        let mut codes: Vec<(String, u32)> = self.reminders.values().chain(self.archived.values())
+            .map(|r| (r.code(), r.id))
+            .collect();
+        codes.sort();
+        let common = |a: &str, b: &str| a.bytes().zip(b.bytes()).take_while(|(x, y)| x == y).count();
+
+        // In sorted order the longest prefix shared with any other code is
+        // shared with a neighbour
+        (0..codes.len())
+            .map(|i| {
+                let code = &codes[i].0;
+                let before = i.checked_sub(1).map_or(0, |j| common(code, &codes[j].0));
+                let after = codes.get(i + 1).map_or(0, |next| common(code, &next.0));
+                let mut length = (before.max(after) + 1).max(MIN_CODE_LENGTH);
+                while length < code.len() && code[..length].bytes().all(|b| b.is_ascii_digit()) {
+                    length += 1;
+                }
+                (codes[i].1, code[..length].to_string())
+            })
+            .collect()
+    }
+
+    /// The shortest unambiguous code of one reminder, see `short_codes`.
+    pub fn short_code(&self, id: u32) -> Option<String> {
+        self.short_codes().remove(&id)
+    }
+
+    pub fn get_archived_reminders(&self) -> Vec<&Reminder> {
+        let mut reminders: Vec<&Reminder> = self.archived.values().collect();
+        reminders.sort_by_key(|r| r.id);
+        reminders
+    }
+
+    pub fn export_to_file(&self, id: u32, file_path: &str) -> Result<()> {
+        let reminder = self.reminders.get(&id)
+            .ok_or(Error::NotFound(id))?;
+
+        fs::write(file_path, &reminder.content)
+            .map_err(|e| Error::io(format!("Failed to write {}", file_path), e))?;
+        Ok(())
+    }
+}
+
+/// Shorten `content` to `trim_length` characters, marking the cut with "...".
+pub fn get_trimmed_content(content: &str, trim_length: Option<usize>) -> String {
+    match trim_length {
+        Some(len) => {
+            if len == 0 { // Handle trim 0 explicitly if desired, e.g., return "..." or empty
+                return "...".to_string(); 
+            }
+            if content.chars().count() > len {
+                format!("{}...", content.chars().take(len).collect::<String>())
+            } else {
+                content.to_string()
+            }
+        }
+        None => content.to_string(),
+    }
+}
@@ -0,0 +1,65 @@
+// src/lock.rs
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::storage;
+
+/// How long to keep retrying before giving up on a lock held elsewhere.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+const MAX_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Exclusive advisory lock on the reminder store, released when dropped.
+/// Every invocation holds it from loading the store until its last save so
+/// overlapping commands can't overwrite each other's changes.
+pub struct StoreLock {
+    _file: File,
+}
+
+fn get_lock_path() -> PathBuf {
+    storage::companion_path("reminders.lock", "lock")
+}
+
+/// Take the store lock, retrying with backoff for a few seconds when another
+/// process holds it.
+pub fn acquire() -> Result<StoreLock> {
+    acquire_at(&get_lock_path(), LOCK_TIMEOUT)
+}
+
+fn acquire_at(lock_path: &Path, timeout: Duration) -> Result<StoreLock> {
+    storage::create_parent_dir(lock_path)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path)
+        .map_err(|e| Error::io(format!("Failed to open {}", lock_path.display()), e))?;
+
+    let started = Instant::now();
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        match file.try_lock() {
+            Ok(()) => {
+                tracing::trace!(path = %lock_path.display(), waited = ?started.elapsed(), "took the store lock");
+                return Ok(StoreLock { _file: file });
+            }
+            Err(TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                if backoff == INITIAL_BACKOFF {
+                    tracing::debug!(path = %lock_path.display(), "the store is locked by another process, waiting");
+                }
+                thread::sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            Err(TryLockError::WouldBlock) => {
+                tracing::warn!(path = %lock_path.display(), waited = ?started.elapsed(), "gave up waiting for the store lock");
+                return Err(Error::Locked(lock_path.to_path_buf()));
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(Error::io(format!("Failed to lock {}", lock_path.display()), e));
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// src/logging.rs
+//! Diagnostics from the storage backends, the daemon, the server and sync,
+//! turned on with `-v` or `--log-file`.
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal};
+use std::panic;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing_subscriber::EnvFilter;
+
+use reminder::{Error, Result};
+
+/// Environment variable with `tracing` filter directives, such as
+/// "reminder=debug", overriding the level picked by `-v`.
+pub const LOG_FILTER_VAR: &str = "REMINDER_LOG";
+
+/// Start writing diagnostics of our own crates: none by default, info with
+/// `-v` or a log file, debug with `-vv` and trace with `-vvv`, which also
+/// covers the libraries underneath; `REMINDER_LOG` overrides the level. They
+/// go to stderr, or are appended to `log_file`. Panics are logged too, with
+/// the span they happened in.
+pub fn init(verbosity: u8, log_file: Option<&Path>) -> Result<()> {
+    if verbosity == 0 && log_file.is_none() && env::var_os(LOG_FILTER_VAR).is_none() {
+        return Ok(());
+    }
+    let filter = EnvFilter::try_from_env(LOG_FILTER_VAR).unwrap_or_else(|_| match verbosity {
+        0 | 1 => EnvFilter::new("warn,reminder=info"),
+        2 => EnvFilter::new("warn,reminder=debug"),
+        _ => EnvFilter::new("trace"),
+    });
+
+    let builder = tracing_subscriber::fmt().with_env_filter(filter).with_target(true);
+    match log_file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| Error::io(format!("Failed to open the log file {}", path.display()), e))?;
+            builder.with_ansi(false).with_writer(Mutex::new(file)).init();
+        }
+        None => builder.with_ansi(io::stderr().is_terminal()).with_writer(io::stderr).init(),
+    }
+
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        tracing::error!(panic = %info, "reminder panicked");
+        default_hook(info);
+    }));
+    Ok(())
+}
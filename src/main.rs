@@ -1,11 +1,53 @@
 // src/main.rs
-use chrono::{DateTime, Duration, Local};
-use clap::{Parser, Subcommand};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+mod clipboard;
+mod completions;
+mod daemon;
+mod digest;
+mod editor;
+mod graph;
+mod launcher;
+mod logging;
+mod markdown;
+mod notify;
+mod pager;
+mod schedule;
+mod select;
+mod server;
+mod session;
+mod style;
+mod sync;
+mod table;
+mod telegram;
+mod tui;
+mod watch;
+
+use chrono::{DateTime, Duration, Utc};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::{ArgValueCandidates, CompleteEnv, Shell};
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+use std::cmp::Reverse;
 use std::fs;
-use std::path::PathBuf;
-use std::io::{self};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use reminder::config::{self, ColorChoice, Config, DuplicateAction};
+use reminder::export::ExportFormat;
+use reminder::recurrence::Recurrence;
+use reminder::scheduler::{self, Algorithm, Rating};
+use reminder::stats::Stats;
+use reminder::storage::{self, Storage, StorageKind};
+use reminder::{
+    attachments, audit, backup, clock, crypto, dates, diff, duration, export, get_trimmed_content, import, lock, merge, profile, search, settings, simulate, stats, undo,
+    DueBucket, Error, NewReminder, Postponement, Priority, Reminder, ReminderChanges, ReminderStore, Result, SortKey, Status,
+};
+
+use markdown::ContentFormat;
+use select::{ReminderRef, Selectors};
+use table::Column;
 
 #[derive(Parser)]
 #[command(name = "reminder")]
@@ -14,176 +56,864 @@ struct Cli {
     #[command(subcommand)]
     command: Commands,
 
-    /// Trim the reminder content to a specific number of characters when displaying
-    #[arg(long, value_name = "NUMBER")]
+    /// Trim the reminder content to a specific number of characters when displaying (0 shows it in full)
+    #[arg(long, value_name = "NUMBER", env = "REMINDER_TRIM")]
     trim: Option<usize>,
+
+    /// Render Markdown in reminder content (headings, emphasis, code, lists)
+    #[arg(long)]
+    render_md: bool,
+
+    /// When to color the output (defaults to the configured choice, "auto")
+    #[arg(long, global = true, value_enum, value_name = "WHEN", env = "REMINDER_COLOR")]
+    color: Option<ColorChoice>,
+
+    /// Print machine-readable JSON instead of formatted text
+    #[arg(long, global = true, env = "REMINDER_JSON", value_parser = clap::builder::FalseyValueParser::new())]
+    json: bool,
+
+    /// Print formatted text even if JSON is configured
+    #[arg(long, global = true)]
+    no_json: bool,
+
+    /// Print long output straight to the terminal instead of through $PAGER
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Show what the command would change in the reminder store, without saving it
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Storage backend to use (defaults to the configured one)
+    #[arg(long, value_enum)]
+    storage: Option<StorageKind>,
+
+    /// Use the local cache of the WebDAV storage without contacting the server
+    #[arg(long)]
+    offline: bool,
+
+    /// Store file to use instead of the one in the data directory; the lock and undo history go next to it
+    #[arg(long, global = true, value_name = "PATH", env = "REMINDER_DATA_FILE")]
+    data_file: Option<PathBuf>,
+
+    /// Profile whose store to use (defaults to the configured one, "default")
+    #[arg(long, global = true, value_name = "NAME", env = "REMINDER_PROFILE", add = ArgValueCandidates::new(completions::profiles))]
+    profile: Option<String>,
+
+    /// Print diagnostics on stderr: -v for what the command does, -vv for details, -vvv for everything
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Append the diagnostics to this file instead of printing them
+    #[arg(long, global = true, value_name = "FILE", env = "REMINDER_LOG_FILE")]
+    log_file: Option<PathBuf>,
+
+    /// Pretend it is this time, e.g. "tomorrow 9am" or 2024-03-01T09:00:00Z; with --dry-run nothing is saved
+    #[arg(long, global = true, hide = true, value_name = "TIMESTAMP", env = "REMINDER_NOW")]
+    now: Option<String>,
+}
+
+impl Cli {
+    /// Fill in the flags given neither on the command line nor in the
+    /// environment from their configured defaults.
+    fn apply_config(&mut self, config: &Config) {
+        self.json = !self.no_json && (self.json || config.json);
+        // --trim 0 overrides a configured trim
+        self.trim = self.trim.or(config.trim).filter(|length| *length > 0);
+        if let Commands::Add { deck: deck @ None, .. } = &mut self.command {
+            deck.clone_from(&config.deck);
+        }
+    }
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Add a new reminder
     Add {
-        /// The content to remember
+        /// The content to remember; $EDITOR opens to write it when neither it, --front nor --clipboard is given
         #[arg(value_name = "CONTENT")]
-        content: String,
+        content: Option<String>,
+        /// Write the content in $EDITOR, e.g. for long multi-line content
+        #[arg(long, conflicts_with_all = ["content", "front", "clipboard"])]
+        edit: bool,
+        /// File whose text fills the editor, instead of the configured add_template
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["content", "front", "clipboard"])]
+        template: Option<PathBuf>,
+        /// Use the text on the clipboard as the content
+        #[arg(long, conflicts_with_all = ["content", "front"])]
+        clipboard: bool,
+        /// Add the reminder even if one with the same content or link exists
+        #[arg(long)]
+        force: bool,
+        /// Question side of a two-sided card (used instead of CONTENT)
+        #[arg(long, conflicts_with = "content", requires = "back")]
+        front: Option<String>,
+        /// Answer side of a two-sided card, hidden until revealed
+        #[arg(long, requires = "front")]
+        back: Option<String>,
+        /// Scheduling algorithm for this reminder (defaults to the configured one)
+        #[arg(long, value_enum)]
+        algorithm: Option<Algorithm>,
+        /// Tag to attach to the reminder (repeatable)
+        #[arg(long = "tag", value_name = "TAG", add = ArgValueCandidates::new(completions::tags))]
+        tags: Vec<String>,
+        /// When the first review is due, e.g. "tomorrow 9am", "next monday", "in 2 weeks"
+        #[arg(long, value_name = "WHEN")]
+        due: Option<String>,
+        /// How important the reminder is
+        #[arg(long, value_enum, default_value_t = Priority::Normal)]
+        priority: Priority,
+        /// Deck to put the reminder in (defaults to the configured one)
+        #[arg(long, env = "REMINDER_DECK")]
+        deck: Option<String>,
+        /// File to attach to the reminder, such as an image or PDF (repeatable)
+        #[arg(long = "attach", value_name = "PATH")]
+        attachments: Vec<PathBuf>,
+        /// Repeat on a fixed schedule forever instead of spaced repetition,
+        /// e.g. "every 7 days" or a cron expression like "0 9 * * mon"
+        #[arg(long, value_name = "RULE")]
+        recur: Option<String>,
+        /// Context, sources or mnemonics to keep with the reminder, shown by `show --full`
+        #[arg(long, value_name = "TEXT")]
+        notes: Option<String>,
+    },
+    /// Show every detail of one reminder, with its full content and review history
+    Show {
+        /// The ID of the reminder, active or archived
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// Also show the notes and the answer side
+        #[arg(long)]
+        full: bool,
     },
     /// Check for due reminders
-    Check,
+    Check {
+        /// Only show reminders with this tag (repeatable, all must match)
+        #[arg(long = "tag", value_name = "TAG", add = ArgValueCandidates::new(completions::tags))]
+        tags: Vec<String>,
+        /// Only show reminders with this priority (repeatable, any may match)
+        #[arg(long = "priority", value_enum, value_name = "PRIORITY")]
+        priorities: Vec<Priority>,
+        /// Only show reminders in this deck
+        #[arg(long)]
+        deck: Option<String>,
+        /// Show the answer side of two-sided cards
+        #[arg(long)]
+        reveal: bool,
+        /// Show every due reminder, ignoring the daily review limit
+        #[arg(long)]
+        no_limit: bool,
+        /// Leave out reminders overdue for longer than this, e.g. 2w
+        #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration)]
+        max_age: Option<Duration>,
+        /// Also announce the due reminders on the desktop and through the configured notifiers
+        #[arg(long)]
+        notify: bool,
+        /// Print nothing; exit with status 1 when a reminder is due, 0 otherwise
+        #[arg(long, short)]
+        quiet: bool,
+    },
     /// List all reminders
-    List,
+    List {
+        /// Only show reminders with this tag (repeatable, all must match)
+        #[arg(long = "tag", value_name = "TAG", add = ArgValueCandidates::new(completions::tags))]
+        tags: Vec<String>,
+        /// Only show reminders with this priority (repeatable, any may match)
+        #[arg(long = "priority", value_enum, value_name = "PRIORITY")]
+        priorities: Vec<Priority>,
+        /// Only show reminders in this deck
+        #[arg(long)]
+        deck: Option<String>,
+        /// Show the answer side of two-sided cards
+        #[arg(long)]
+        reveal: bool,
+        /// Show archived reminders instead
+        #[arg(long)]
+        archived: bool,
+        /// Print one reminder per line as an aligned table
+        #[arg(long)]
+        table: bool,
+        /// Table columns to show, comma-separated (implies --table)
+        #[arg(long, value_enum, value_delimiter = ',', value_name = "COLUMNS")]
+        columns: Vec<Column>,
+        /// Show how the reminders are linked, as a tree per group of related ones
+        #[arg(long, conflicts_with_all = ["table", "columns"])]
+        graph: bool,
+        /// Order to show the reminders in (defaults to the configured one, by priority)
+        #[arg(long, value_enum, value_name = "KEY")]
+        sort: Option<SortKey>,
+        /// Show the reminders in the opposite order
+        #[arg(long)]
+        reverse: bool,
+        /// Only show reminders with this status (repeatable, any may match)
+        #[arg(long = "status", value_enum, value_name = "STATUS")]
+        statuses: Vec<Status>,
+        /// Only show reminders due within this long, e.g. 3d, overdue ones included
+        #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration)]
+        due_within: Option<Duration>,
+        /// Only show reminders added since this date, or this long ago, e.g. 2024-01-01 or 2w
+        #[arg(long, value_name = "WHEN")]
+        created_since: Option<String>,
+        /// Show at most this many reminders
+        #[arg(long, value_name = "NUMBER")]
+        limit: Option<usize>,
+        /// Skip this many reminders first, e.g. to see the next page with --limit
+        #[arg(long, value_name = "NUMBER", default_value_t = 0)]
+        offset: usize,
+    },
+    /// Search reminders by content and tags
+    Search {
+        /// Text to look for (case-insensitive)
+        #[arg(value_name = "QUERY")]
+        query: Option<String>,
+        /// Treat QUERY as a regular expression
+        #[arg(long, requires = "query")]
+        regex: bool,
+        /// Only show reminders with this tag (repeatable, all must match)
+        #[arg(long = "tag", value_name = "TAG", add = ArgValueCandidates::new(completions::tags))]
+        tags: Vec<String>,
+    },
+    /// List the reminders flagged as leeches because they keep being forgotten
+    Leeches {
+        /// Pause the leeches so they stop coming up until resumed
+        #[arg(long)]
+        suspend: bool,
+    },
+    /// Show statistics about reminders and reviews
+    Stats {
+        /// Draw ASCII bar charts of daily reviews and upcoming load
+        #[arg(long)]
+        chart: bool,
+    },
+    /// Show how many reviews come due on each of the coming days
+    Forecast {
+        /// Number of days to look ahead, starting today
+        #[arg(long, default_value_t = 14, value_parser = clap::value_parser!(i64).range(1..=366))]
+        days: i64,
+    },
+    /// Project the daily review workload by running the schedule forward, changing nothing
+    Simulate {
+        /// Number of days to run forward, starting today
+        #[arg(long, default_value_t = 90, value_parser = clap::value_parser!(i64).range(1..=3650))]
+        days: i64,
+        /// Reminders to add on each of the days
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        new_per_day: usize,
+    },
+    /// Summarise due and upcoming reminders, e.g. for a daily email from cron
+    Digest {
+        /// Send the digest by email using the [email] settings of the config file
+        #[arg(long)]
+        email: bool,
+        /// Number of days of upcoming reminders to include
+        #[arg(long, default_value_t = 7, value_parser = clap::value_parser!(i64).range(1..=366))]
+        days: i64,
+    },
+    /// List all tags with the number of reminders using them
+    Tags,
+    /// Print the number of reminders, exiting with status 1 when any are due
+    Count {
+        /// Count due reminders (the default)
+        #[arg(long, conflicts_with = "all")]
+        due: bool,
+        /// Count all reminders
+        #[arg(long)]
+        all: bool,
+        /// Only count reminders with this tag (repeatable, all must match)
+        #[arg(long = "tag", value_name = "TAG", add = ArgValueCandidates::new(completions::tags))]
+        tags: Vec<String>,
+    },
     /// Mark a reminder as reviewed
     Review {
-        /// The ID of the reminder to mark as reviewed
-        #[arg(value_name = "ID")]
-        id: u32,
+        /// IDs or ranges of reminders to mark as reviewed, e.g. 3 5 7-10
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        ids: Vec<String>,
+        #[command(flatten)]
+        selectors: Selectors,
+        /// Recall quality from 0 (blackout) to 5 (perfect)
+        #[arg(long, group = "rating", value_parser = clap::value_parser!(u8).range(0..=5))]
+        grade: Option<u8>,
+        /// Forgotten: the interval ladder starts over (grade 1)
+        #[arg(long, group = "rating")]
+        again: bool,
+        /// Recalled with difficulty: stay on the same ladder stage (grade 3)
+        #[arg(long, group = "rating")]
+        hard: bool,
+        /// Recalled: move up the ladder as usual (grade 4)
+        #[arg(long, group = "rating")]
+        good: bool,
+        /// Recalled effortlessly: move up with a longer interval (grade 5)
+        #[arg(long, group = "rating")]
+        easy: bool,
+    },
+    /// Edit the content of a reminder, keeping its schedule
+    Edit {
+        /// The ID of the reminder to edit
+        #[arg(value_name = "ID", required_unless_present = "matching", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: Option<ReminderRef>,
+        /// Edit the reminder whose content best matches this text instead of giving its ID
+        #[arg(long = "match", value_name = "TEXT", conflicts_with = "id")]
+        matching: Option<String>,
+        /// New content; opens $EDITOR with the current content when omitted
+        #[arg(long)]
+        content: Option<String>,
+        /// New answer side for a two-sided card
+        #[arg(long)]
+        back: Option<String>,
+        /// New priority
+        #[arg(long, value_enum)]
+        priority: Option<Priority>,
+        /// Move the reminder to this deck (an empty name removes it from its deck)
+        #[arg(long)]
+        deck: Option<String>,
+        /// Repeat on this fixed schedule instead of spaced repetition (an empty
+        /// rule stops the repeating)
+        #[arg(long, value_name = "RULE")]
+        recur: Option<String>,
+        /// New notes (empty ones are removed); opens $EDITOR with the current
+        /// notes when given without TEXT
+        #[arg(long, value_name = "TEXT", num_args = 0..=1)]
+        notes: Option<Option<String>>,
     },
-    /// Remove a reminder
+    /// Attach a file to a reminder; it is copied into the data directory
+    Attach {
+        /// The ID of the reminder
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// The file to attach
+        path: PathBuf,
+    },
+    /// Open a reminder's attachments with their default applications
+    OpenAttachment {
+        /// The ID of the reminder
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// Only open the Nth attachment (1 is the first)
+        #[arg(long, value_name = "N")]
+        nth: Option<usize>,
+    },
+    /// Open a web address from a reminder's content in the default browser
+    Open {
+        /// The ID of the reminder
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// Open the Nth address instead of the first
+        #[arg(long, value_name = "N", default_value_t = 1)]
+        nth: usize,
+    },
+    /// Show every recorded review of a reminder
+    History {
+        /// The ID of the reminder
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+    },
+    /// List the versions of a reminder's content kept when it was edited, or go back to one
+    Versions {
+        /// The ID of the reminder
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// Make this version the content again; the current content is kept as a version
+        #[arg(long, value_name = "VERSION")]
+        restore: Option<usize>,
+    },
+    /// Show how two versions of a reminder's content differ, line by line
+    Diff {
+        /// The ID of the reminder
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// The older version, as numbered by 'reminder versions'
+        #[arg(value_name = "V1")]
+        from: usize,
+        /// The newer version; the current content when omitted
+        #[arg(value_name = "V2")]
+        to: Option<usize>,
+    },
+    /// Set when a reminder's next review is due, without recording a review
+    #[command(visible_alias = "reschedule")]
+    Schedule {
+        /// The ID of the reminder to schedule
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// When the next review is due, e.g. "in 2 weeks", "friday 9am", 2024-03-01
+        #[arg(value_name = "WHEN")]
+        when: String,
+    },
+    /// Give a reminder an interval ladder of its own instead of its deck's or the global one
+    SetIntervals {
+        /// The ID of the reminder
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// Intervals between reviews, e.g. 1d,4d,2w,2m (months); "" goes back to the shared ladder
+        #[arg(value_name = "INTERVALS")]
+        intervals: String,
+    },
+    /// Postpone a reminder's next review without counting it as reviewed
+    Snooze {
+        /// IDs or ranges of reminders to snooze, followed by how long to snooze
+        /// for, e.g. `3 5-7 2h`
+        #[arg(value_name = "ID... DURATION", required = true, add = ArgValueCandidates::new(completions::reminder_ids))]
+        args: Vec<String>,
+        #[command(flatten)]
+        selectors: Selectors,
+    },
+    /// Push many reminders' next reviews later at once, spread out so they don't come due together
+    Postpone {
+        /// IDs or ranges of reminders to postpone
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        ids: Vec<String>,
+        #[command(flatten)]
+        selectors: Selectors,
+        /// Spread the reminders over this long, e.g. 3d, the most pressing coming back first
+        #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration, required_unless_present = "proportional")]
+        by: Option<Duration>,
+        /// Delay each reminder by this factor of its interval instead, e.g. 1.2 for a fifth of it
+        #[arg(long, value_name = "FACTOR", conflicts_with = "by")]
+        proportional: Option<f64>,
+    },
+    /// Freeze reminders' schedules so they never come due until resumed
+    Pause {
+        /// IDs or ranges of reminders to pause
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        ids: Vec<String>,
+        #[command(flatten)]
+        selectors: Selectors,
+    },
+    /// Continue paused reminders, moving their next review back by the time they were paused
+    Resume {
+        /// IDs or ranges of reminders to resume
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        ids: Vec<String>,
+        #[command(flatten)]
+        selectors: Selectors,
+    },
+    /// Put a completed reminder back into rotation, keeping its reviews
+    Reopen {
+        /// The ID of the completed reminder
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// When it is due again, e.g. "today", "in 2 weeks" (defaults to its last interval from now)
+        #[arg(long, value_name = "WHEN")]
+        due: Option<String>,
+    },
+    /// Start a reminder's schedule over, to relearn it from the first interval
+    Reset {
+        /// The ID of the reminder
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// When it is due, e.g. "today", "friday 9am" (defaults to tomorrow)
+        #[arg(long, value_name = "WHEN")]
+        due: Option<String>,
+        /// Keep its review history and lapses
+        #[arg(long)]
+        keep_history: bool,
+    },
+    /// Move reviews scheduled during a time away to after it
+    Vacation {
+        /// When the vacation starts, e.g. "friday", 2024-07-01 (defaults to now)
+        #[arg(long, value_name = "WHEN")]
+        from: Option<String>,
+        /// When you're back, e.g. "in 2 weeks", 2024-07-15
+        #[arg(long, value_name = "WHEN", required_unless_present = "list")]
+        to: Option<String>,
+        /// List the recorded vacations instead of adding one
+        #[arg(long, conflicts_with_all = ["from", "to"])]
+        list: bool,
+    },
+    /// Move a reminder to the trash
     Remove {
-        /// The ID of the reminder to remove
-        #[arg(value_name = "ID")]
-        id: u32,
+        /// IDs or ranges of reminders to remove
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        ids: Vec<String>,
+        #[command(flatten)]
+        selectors: Selectors,
+    },
+    /// Set a reminder aside without deleting it
+    Archive {
+        /// IDs or ranges of reminders to archive
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        ids: Vec<String>,
+        #[command(flatten)]
+        selectors: Selectors,
+    },
+    /// Add a tag to reminders, or take it off with --remove
+    Tag {
+        #[arg(value_name = "TAG", add = ArgValueCandidates::new(completions::tags))]
+        tag: String,
+        /// IDs or ranges of reminders to tag
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        ids: Vec<String>,
+        #[command(flatten)]
+        selectors: Selectors,
+        /// Remove the tag instead of adding it
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Link two reminders as related, to see one's neighbours when reviewing the other
+    Link {
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        #[arg(value_name = "OTHER_ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        other: ReminderRef,
+        /// Remove the link instead of adding it
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Keep a reminder from coming due until another has been completed, or reviewed a number of times
+    Depend {
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// The reminder it waits for
+        #[arg(value_name = "ON_ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        on: ReminderRef,
+        /// Reviews of ON_ID that are enough, instead of completing it
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with = "remove")]
+        reviews: Option<u32>,
+        /// Stop it from waiting instead
+        #[arg(long)]
+        remove: bool,
+    },
+    /// List the reminders linked to a reminder
+    Related {
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
+        /// Also follow the links of linked reminders, up to this many steps away
+        #[arg(long, default_value_t = 1, value_name = "STEPS")]
+        depth: usize,
+    },
+    /// Bring an archived reminder back into rotation, or restore everything from a backup
+    Restore {
+        /// The ID of the archived reminder to restore, or a backup made with 'reminder backup'
+        #[arg(value_name = "ID|FILE", add = ArgValueCandidates::new(completions::archived_ids))]
+        target: String,
+        /// When restoring a backup, keep the current config file
+        #[arg(long)]
+        keep_config: bool,
+    },
+    /// Save the store, config, attachments and audit log to one .tar.gz archive
+    Backup {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// List, restore or delete the removed reminders kept in the trash
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+    },
+    /// Revert the most recent change to the reminders
+    Undo,
+    /// Show the changes made to the reminders and the commands that made them, oldest first
+    Log {
+        /// Only changes to this reminder, also once it is removed
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: Option<ReminderRef>,
+        /// Only changes since then, e.g. 2d (two days ago) or 2024-03-01
+        #[arg(long, value_name = "WHEN")]
+        since: Option<String>,
+    },
+    /// Recover what can be read of an unreadable store, or restore one of its rotating backups
+    Repair {
+        /// Restore this backup (1 is the newest) even if the store is readable
+        #[arg(long, value_name = "N")]
+        from: Option<usize>,
+        /// List the available backups instead of restoring one
+        #[arg(long, conflicts_with = "from")]
+        list: bool,
+    },
+    /// Review all due reminders one by one interactively
+    Session {
+        /// Only review reminders in this deck
+        #[arg(long)]
+        deck: Option<String>,
+        /// Review every due reminder, ignoring the daily review limit
+        #[arg(long)]
+        no_limit: bool,
+    },
+    /// Browse, review and edit reminders in a full-screen terminal interface
+    Tui,
+    /// Show and change the settings in the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Manage profiles, each with a store of its own
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommand,
+    },
+    /// Manage decks of reminders
+    Deck {
+        #[command(subcommand)]
+        command: DeckCommand,
+    },
+    /// Run in the foreground and announce due reminders on the desktop and through the configured notifiers
+    Daemon {
+        /// How often to check for due reminders, e.g. 30s, 5m (defaults to the configured one)
+        #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration)]
+        interval: Option<Duration>,
+    },
+    /// Send a test notification through every configured notifier, to check the settings
+    NotifyTest {
+        /// Leave out the desktop notification, as on a machine without a desktop
+        #[arg(long)]
+        no_desktop: bool,
+        /// Priority of the test reminder, to see how it comes through
+        #[arg(long, value_enum, default_value_t = Priority::Normal)]
+        priority: Priority,
+    },
+    /// Keep a list of the due reminders on screen, beeping when more become due
+    Watch {
+        /// How often to refresh the list, e.g. 30s, 5m
+        #[arg(long, value_name = "DURATION", default_value = "10s", value_parser = duration::parse_duration)]
+        interval: Duration,
+        /// Only show reminders with this tag (repeatable, all must match)
+        #[arg(long = "tag", value_name = "TAG", add = ArgValueCandidates::new(completions::tags))]
+        tags: Vec<String>,
+        /// Only show reminders in this deck
+        #[arg(long)]
+        deck: Option<String>,
+        /// Don't ring the terminal bell when reminders become due
+        #[arg(long)]
+        no_beep: bool,
+    },
+    /// Run 'reminder check --notify' periodically from a user systemd timer or the crontab
+    InstallSchedule {
+        /// Install a user-level systemd timer (the default where systemd runs)
+        #[arg(long, conflicts_with = "cron")]
+        systemd: bool,
+        /// Install a crontab entry (the default without systemd)
+        #[arg(long)]
+        cron: bool,
+        /// How often to check for due reminders, e.g. 15m, 1h
+        #[arg(long, value_name = "DURATION", default_value = "30m", value_parser = duration::parse_duration)]
+        every: Duration,
+        /// Remove the installed schedule instead
+        #[arg(long, conflicts_with = "every")]
+        remove: bool,
+    },
+    /// Push due reminders to a Telegram chat and take commands such as /review 12 from it
+    Telegram {
+        /// How often to check for due reminders, e.g. 30s, 5m (defaults to the configured one)
+        #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration)]
+        interval: Option<Duration>,
+    },
+    /// Serve a REST API over the reminder store
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Address to listen on; use 0.0.0.0 to accept connections from the network
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+    },
+    /// Encrypt the store, its backups, the undo history and the audit log with a passphrase
+    Encrypt,
+    /// Turn encryption off again, storing everything in plain text
+    Decrypt,
+    /// Commit the reminders to git and sync them with the configured remote
+    Sync,
+    /// Merge another reminders.json into the local store, e.g. a conflicting copy left by a file sync tool
+    Merge {
+        /// The other store
+        other_file: PathBuf,
+    },
+    /// Import reminders from other tools
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+    /// Export all reminders to a file, or a directory of Markdown files
+    ExportAll {
+        /// Output format
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+        /// The file to write, or the directory for the markdown format
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+    /// Export the upcoming review schedule as an iCalendar file
+    ExportCalendar {
+        #[arg(value_name = "PATH.ics")]
+        path: PathBuf,
+        /// Add an alarm this long before each review, e.g. 15m, 1h
+        #[arg(long, value_name = "DURATION", value_parser = duration::parse_duration)]
+        alarm: Option<Duration>,
+    },
+    /// Print a shell completion script, e.g. `source <(reminder completions bash)`
+    Completions {
+        #[arg(value_enum, value_name = "SHELL")]
+        shell: Shell,
+        /// Print a standalone script that doesn't complete IDs and tags
+        #[arg(long = "static")]
+        static_script: bool,
     },
     Export {
         /// Export content of specific reminder with ID
-        #[arg(value_name = "ID")]
-        id: u32,
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::reminder_ids))]
+        id: ReminderRef,
         #[arg(value_name = "PATH")]
         path: String
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-struct Reminder {
-    id: u32,
-    content: String,
-    created_at: DateTime<Local>,
-    next_review: DateTime<Local>,
-    review_count: u32,
-    completed: bool,
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// List every setting with the value in effect
+    List,
+    /// Show a setting, or all settings in a table such as `email`
+    Get {
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+    /// Change a setting in the config file, e.g. `set email.smtp_port 587`
+    Set {
+        #[arg(value_name = "KEY")]
+        key: String,
+        /// A TOML value such as 0.85, true or [1, 3, 7]; anything else is text
+        #[arg(value_name = "VALUE", allow_hyphen_values = true)]
+        value: String,
+    },
+    /// Remove a setting from the config file, so its default applies
+    Unset {
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+    /// Open the config file in $VISUAL or $EDITOR, checking it before saving
+    Edit,
 }
 
-#[derive(Serialize, Deserialize, Default)]
-struct ReminderStore {
-    reminders: HashMap<u32, Reminder>,
-    next_id: u32,
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// List all profiles, marking the one in use
+    List,
+    /// Create an empty profile
+    Create {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+    /// Delete a profile
+    Delete {
+        #[arg(value_name = "NAME", add = ArgValueCandidates::new(completions::profiles))]
+        name: String,
+        /// Delete the profile even if it still holds reminders
+        #[arg(long)]
+        with_reminders: bool,
+    },
 }
 
-impl ReminderStore {
-    fn load() -> Self {
-        let file_path = get_data_file_path();
-        
-        if file_path.exists() {
-            let content = fs::read_to_string(&file_path)
-                .expect("Failed to read reminder file");
-            
-            serde_json::from_str(&content)
-                .unwrap_or_else(|_| {
-                    eprintln!("Warning: Could not parse reminder file, starting fresh");
-                    Self::default()
-                })
-        } else {
-            Self::default()
-        }
-    }
-
-    fn save(&self) {
-        let file_path = get_data_file_path();
-        
-        // Create directory if it doesn't exist
-        if let Some(parent) = file_path.parent() {
-            fs::create_dir_all(parent)
-                .expect("Failed to create data directory");
-        }
-        
-        let content = serde_json::to_string_pretty(self)
-            .expect("Failed to serialize reminders");
-        
-        fs::write(&file_path, content)
-            .expect("Failed to write reminder file");
-    }
-
-    fn add_reminder(&mut self, content: String) -> u32 {
-        let id = self.next_id;
-        self.next_id += 1;
-
-        let now = Local::now();
-        let reminder = Reminder {
-            id,
-            content,
-            created_at: now,
-            next_review: now + Duration::days(1), // First review after 1 day
-            review_count: 0,
-            completed: false,
-        };
-
-        self.reminders.insert(id, reminder);
-        id
-    }
-
-    fn review_reminder(&mut self, id: u32) -> Result<(), String> {
-        let reminder = self.reminders.get_mut(&id)
-            .ok_or_else(|| format!("Reminder with ID {} not found", id))?;
-
-        if reminder.completed {
-            return Err("Reminder is already completed".to_string());
-        }
-
-        reminder.review_count += 1;
-
-        // Schedule next review based on spaced repetition intervals
-        let next_interval = match reminder.review_count {
-            1 => Duration::days(3),   // 3 days after first review
-            2 => Duration::weeks(1),  // 1 week after second review
-            3 => Duration::days(30),  // 1 month after third review
-            _ => {
-                reminder.completed = true;
-                return Ok(());
-            }
-        };
+#[derive(Subcommand)]
+enum DeckCommand {
+    /// List all decks with the number of reminders in each
+    List,
+    /// Rename a deck
+    Rename {
+        #[arg(value_name = "OLD")]
+        old: String,
+        #[arg(value_name = "NEW")]
+        new: String,
+    },
+    /// Remove a deck, keeping its reminders outside any deck
+    Remove {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// Move the deck's reminders to the trash as well
+        #[arg(long)]
+        with_reminders: bool,
+    },
+}
 
-        reminder.next_review = Local::now() + next_interval;
-        Ok(())
-    }
+#[derive(Subcommand)]
+enum TrashCommand {
+    /// List the reminders in the trash, most recently removed first
+    List,
+    /// Bring a removed reminder back
+    Restore {
+        #[arg(value_name = "ID", add = ArgValueCandidates::new(completions::trashed_ids))]
+        id: ReminderRef,
+    },
+    /// Delete every reminder in the trash for good
+    Empty,
+}
 
-    fn get_due_reminders(&self) -> Vec<&Reminder> {
-        let now = Local::now();
-        self.reminders
-            .values()
-            .filter(|r| !r.completed && r.next_review <= now)
-            .collect()
-    }
+#[derive(Subcommand)]
+enum ImportSource {
+    /// Import an Anki .apkg package or "Notes in Plain Text" export
+    Anki {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Keep the interval, ease and due date of cards Anki already reviews
+        #[arg(long)]
+        keep_schedule: bool,
+    },
+    /// Import a CSV file (columns: content, tags, created_at, next_review, review_count, answer)
+    Csv {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Import a directory of Markdown files such as an Obsidian vault, one
+    /// reminder per file or per block marked with #review
+    Markdown {
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+        /// Tag each reminder with the folders its file is in
+        #[arg(long)]
+        tag_from_folder: bool,
+        /// Tag marking the blocks to import on their own
+        #[arg(long, value_name = "TAG", default_value = import::DEFAULT_MARKDOWN_MARKER)]
+        marker: String,
+    },
+    /// Import the headlines tagged :REVIEW: of an org-mode file
+    Org {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Import a list file, one reminder per non-empty line or per block between "---" lines
+    Lines {
+        /// The file, or - to read standard input
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Tag every imported reminder (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+    },
+}
 
-    fn get_all_reminders(&self) -> Vec<&Reminder> {
-        let mut reminders: Vec<&Reminder> = self.reminders.values().collect();
-        reminders.sort_by_key(|r| r.next_review);
-        reminders
-    }
 
-    fn remove_reminder(&mut self, id: u32) -> Result<(), String> {
-        self.reminders.remove(&id)
-            .ok_or_else(|| format!("Reminder with ID {} not found", id))?;
-        Ok(())
+/// When the next review is, colored by whether it is already due.
+fn format_next_review(reminder: &Reminder) -> ColoredString {
+    if let Some(paused_at) = reminder.paused_at {
+        return style::upcoming(&format!("paused since {}", dates::local(paused_at).format("%Y-%m-%d %H:%M")));
     }
-
-    fn export_to_file(&self, id: u32, file_path: String) -> io::Result<()> {
-        let reminder = self.reminders.get(&id)
-            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "ID not found"))?;
-
-        fs::write(file_path, &reminder.content)?;
-        Ok(())
+    let when = format_duration_until(reminder.next_review);
+    if dates::is_due(reminder.next_review, clock::now()) {
+        style::due(&when)
+    } else {
+        style::upcoming(&when)
     }
 }
 
-fn get_data_file_path() -> PathBuf {
-    let mut path = dirs::data_dir()
-        .unwrap_or_else(|| PathBuf::from(".")); // Fallback to current directory if data_dir is not available
-    path.push("reminder");
-    path.push("reminders.json");
-    path
+/// How long a reminder was paused, in the largest unit that fits.
+fn format_paused_for(duration: Duration) -> String {
+    if duration.num_days() > 0 {
+        format_days(duration.num_days() as u32)
+    } else if duration.num_hours() > 0 {
+        format!("{} hour(s)", duration.num_hours())
+    } else {
+        format!("{} minute(s)", duration.num_minutes())
+    }
 }
 
-fn format_duration_until(datetime: DateTime<Local>) -> String {
-    let now = Local::now();
+fn format_duration_until(datetime: DateTime<Utc>) -> String {
+    let now = clock::now();
+    if dates::day_rollover().is_some() {
+        // Reviews scheduled by day are due on a day, not at a time
+        return match (dates::day_of(datetime) - dates::day_of(now)).num_days() {
+            0 => "today".to_string(),
+            1 => "tomorrow".to_string(),
+            -1 => "yesterday".to_string(),
+            days if days < 0 => format!("{} days ago", -days),
+            days => format!("in {} days", days),
+        };
+    }
     let duration = datetime.signed_duration_since(now);
 
     if duration.num_seconds() < 0 {
@@ -210,121 +940,2101 @@ fn format_duration_until(datetime: DateTime<Local>) -> String {
     }
 }
 
-fn get_trimmed_content(content: &str, trim_length: Option<usize>) -> String {
-    match trim_length {
-        Some(len) => {
-            if len == 0 { // Handle trim 0 explicitly if desired, e.g., return "..." or empty
-                return "...".to_string(); 
-            }
-            if content.chars().count() > len {
-                format!("{}...", content.chars().take(len).collect::<String>())
-            } else {
-                content.to_string()
-            }
-        }
-        None => content.to_string(),
+/// How often the daemon and the Telegram bot check for due reminders.
+fn polling_interval(interval: Option<Duration>, config: &Config) -> Result<Duration> {
+    let interval = match interval {
+        Some(interval) => interval,
+        None => duration::parse_duration(&config.daemon_interval)
+            .map_err(|e| Error::Parse(format!("Invalid daemon_interval in config: {}", e)))?,
+    };
+    if interval < Duration::seconds(1) {
+        return Err(Error::Invalid("Daemon interval must be at least one second".to_string()));
     }
+    Ok(interval)
 }
 
-fn main() {
-    let cli = Cli::parse();
-    let mut store = ReminderStore::load();
-    let trim_opt = cli.trim;
+/// Rewrite the store, its backups, the undo journal and the audit log with
+/// encryption turned on or off.
+fn set_encryption(storage: &mut dyn Storage, store: &ReminderStore, encrypt: bool) -> Result<()> {
+    crypto::set_encrypting(encrypt);
+    storage.save(store)?;
+    for path in storage.backups() {
+        let backup = storage::read_json_store(&path)?;
+        storage::write_json_store(&path, &backup)?;
+    }
+    undo::rewrite_journal()?;
+    audit::rewrite_log()
+}
 
-    match cli.command {
-        Commands::Add { content } => {
-            let id = store.add_reminder(content.clone()); // Store full content
-            store.save();
-            let displayed_content = get_trimmed_content(&content, trim_opt);
-            println!("Added reminder with ID {}: \"{}\"", id, displayed_content);
-            println!("Next review: 1 day from now");
-        }
+/// Set by `--dry-run`: commands then only report what they would save.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
 
-        Commands::Check => {
-            let due_reminders = store.get_due_reminders();
-            
-            if due_reminders.is_empty() {
-                println!("No reminders due for review!");
-            } else {
-                println!("Reminders due for review:");
-                println!("{}", "=".repeat(50));
-                
-                for reminder in due_reminders {
-                    let displayed_content = get_trimmed_content(&reminder.content, trim_opt);
-                    println!("ID: {}", reminder.id);
-                    println!("Content: {}", displayed_content);
-                    println!("Review count: {}", reminder.review_count);
-                    println!("Due: {}", format_duration_until(reminder.next_review));
-                    println!("{}", "-".repeat(30));
-                }
-                
-                println!("\nUse 'reminder review <ID>' to mark a reminder as reviewed");
-            }
+/// Save the store after a mutating command, keeping `before` so the change
+/// can be reverted with `reminder undo`, and logging the change in the audit
+/// log. In a dry run the change is only described.
+fn save_store(storage: &mut dyn Storage, store: &ReminderStore, before: &ReminderStore) -> Result<()> {
+    if DRY_RUN.load(Ordering::Relaxed) {
+        print_dry_run(&diff::diff(before, store), before, store);
+        return Ok(());
+    }
+    undo::record(before, &undo::current_command())?;
+    audit::record(before, store, &undo::current_command())?;
+    storage.save(store)
+}
+
+/// Describe the changes a dry run didn't save, on stderr so the command's
+/// own output, JSON included, stays as it would be.
+fn print_dry_run(diff: &diff::StoreDiff, before: &ReminderStore, after: &ReminderStore) {
+    if diff.is_empty() {
+        eprintln!("Dry run: nothing would change");
+        return;
+    }
+    let label = |id: u32| {
+        let store = if diff.added.contains(&id) { after } else { before };
+        store.find_reminder(id).map(|reminder| format!("{} \"{}\"", id, graph::label(reminder, Some(40)))).unwrap_or_default()
+    };
+
+    eprintln!("Dry run, nothing was saved. This would:");
+    for line in describe_changes(diff, &label) {
+        eprintln!("  {}", line);
+    }
+}
+
+/// One line per change in `diff`, naming reminders with `label`.
+fn describe_changes(diff: &diff::StoreDiff, label: &dyn Fn(u32) -> String) -> Vec<String> {
+    // Long values such as the history are cut short
+    let value = |value: &serde_json::Value| get_trimmed_content(&value.to_string(), Some(60));
+
+    let mut lines = Vec::new();
+    for &id in &diff.added {
+        lines.push(format!("add reminder {}", label(id)));
+    }
+    for &id in &diff.removed {
+        lines.push(format!("remove reminder {}", label(id)));
+    }
+    for &id in &diff.archived {
+        lines.push(format!("archive reminder {}", label(id)));
+    }
+    for &id in &diff.restored {
+        lines.push(format!("restore reminder {}", label(id)));
+    }
+    for change in &diff.changed {
+        lines.push(format!("change reminder {}:", label(change.id)));
+        for field in &change.fields {
+            lines.push(format!("    {}: {} -> {}", field.field, value(&field.before), value(&field.after)));
         }
+    }
+    if !diff.other.is_empty() {
+        lines.push(format!("change the store's {}", diff.other.join(", ")));
+    }
+    lines
+}
 
-        Commands::List => {
-            let reminders = store.get_all_reminders();
-            
-            if reminders.is_empty() {
-                println!("No reminders found!");
-            } else {
-                println!("All reminders:");
-                println!("{}", "=".repeat(70));
-                
-                for reminder in reminders {
-                    let status = if reminder.completed {
-                        "✓ Completed"
-                    } else {
-                        "⏳ Active"
-                    };
-                    
-                    let displayed_content = get_trimmed_content(&reminder.content, trim_opt);
-                    println!("ID: {} | {} | Reviews: {}", 
-                             reminder.id, status, reminder.review_count);
-                    println!("Content: {}", displayed_content);
-                    
-                    if !reminder.completed {
-                        println!("Next review: {}", format_duration_until(reminder.next_review));
-                    }
-                    
-                    println!("{}", "-".repeat(50));
-                }
-            }
+/// The name of a command that does more than change the store, such as
+/// copying files or sending messages, which a dry run couldn't hold back.
+fn effects_beyond_store(command: &Commands) -> Option<&'static str> {
+    Some(match command {
+        Commands::Add { attachments, .. } if !attachments.is_empty() => "add --attach",
+        Commands::Check { notify: true, .. } => "check --notify",
+        Commands::Attach { .. } => "attach",
+        Commands::OpenAttachment { .. } => "open-attachment",
+        Commands::Open { .. } => "open",
+        Commands::Digest { .. } => "digest",
+        Commands::Backup { .. } => "backup",
+        Commands::Restore { target, .. } if backup_path(target).is_some() => "restore",
+        Commands::Undo => "undo",
+        Commands::Repair { .. } => "repair",
+        Commands::Session { .. } => "session",
+        Commands::Tui => "tui",
+        Commands::Daemon { .. } => "daemon",
+        Commands::NotifyTest { .. } => "notify-test",
+        Commands::InstallSchedule { .. } => "install-schedule",
+        Commands::Config { command: ConfigCommand::Set { .. } | ConfigCommand::Unset { .. } | ConfigCommand::Edit } => "config",
+        Commands::Telegram { .. } => "telegram",
+        Commands::Serve { .. } => "serve",
+        Commands::Encrypt => "encrypt",
+        Commands::Decrypt => "decrypt",
+        Commands::Sync => "sync",
+        Commands::ExportAll { .. } => "export-all",
+        Commands::ExportCalendar { .. } => "export-calendar",
+        Commands::Export { .. } => "export",
+        _ => return None,
+    })
+}
+
+/// The backup archive `restore` was given, when it wasn't given a reminder:
+/// anything but an ID or code, or a code naming an existing file.
+fn backup_path(target: &str) -> Option<PathBuf> {
+    let is_id = target.trim().bytes().all(|b| b.is_ascii_digit());
+    let path = PathBuf::from(target);
+    (!is_id && (target.parse::<ReminderRef>().is_err() || path.is_file())).then_some(path)
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) {
+    println!("{}", serde_json::to_string_pretty(value).expect("Failed to serialize output"));
+}
+
+/// Print the result of a bulk command: a single object when only one
+/// reminder was selected, as before bulk selection existed, otherwise a list.
+fn print_json_selection<T: Serialize>(items: &[T]) {
+    match items {
+        [item] => print_json(item),
+        items => print_json(items),
+    }
+}
+
+fn report_error(error: &Error, json: bool) {
+    if json {
+        eprintln!("{}", serde_json::json!({ "error": error.to_string(), "exit_code": error.exit_code() }));
+    } else {
+        eprintln!("Error: {}", error);
+    }
+}
+
+fn print_answer(reminder: &Reminder, format: ContentFormat, reveal: bool) {
+    if let Some(answer) = &reminder.answer {
+        if reveal {
+            println!("Answer: {}", format.apply(answer));
+        } else {
+            println!("Answer: (hidden, use --reveal to show)");
         }
+    }
+}
 
-        Commands::Review { id } => {
-            match store.review_reminder(id) {
-                Ok(()) => {
-                    let reminder = &store.reminders[&id]; // Assumes reminder exists after successful review
-                    if reminder.completed {
-                        println!("Reminder {} completed! 🎉", id);
-                        println!("You've successfully reviewed this {} times.", reminder.review_count);
-                    } else {
-                        println!("Reminder {} reviewed!", id);
-                        println!("Next review: {}", format_duration_until(reminder.next_review));
-                    }
-                    store.save();
-                }
-                Err(e) => eprintln!("Error: {}", e),
-            }
+fn print_due_reminder(store: &ReminderStore, reminder: &Reminder, format: ContentFormat, reveal: bool) {
+    let displayed_content = format.apply(&reminder.content);
+    println!("ID: {}", style::id(reminder.id));
+    println!("Content: {}", displayed_content);
+    print_answer(reminder, format, reveal);
+    if reminder.priority != Priority::Normal {
+        println!("Priority: {}", style::priority(reminder.priority));
+    }
+    if let Some(deck) = &reminder.deck {
+        println!("Deck: {}", deck);
+    }
+    if !reminder.tags.is_empty() {
+        println!("Tags: {}", style::tags(&reminder.tags.join(", ")));
+    }
+    if !reminder.attachments.is_empty() {
+        println!("Attachments: {}", reminder.attachments.join(", "));
+    }
+    if let Some(recurrence) = &reminder.recurrence {
+        println!("Repeats: {}", recurrence);
+    }
+    if let Some(related) = graph::related_line(store, reminder.id) {
+        println!("Related: {}", related);
+    }
+    println!("Review count: {}", reminder.review_count);
+    if reminder.snooze_count > 0 {
+        println!("Snoozed: {} time(s)", reminder.snooze_count);
+    }
+    if reminder.lapses > 0 {
+        println!("Lapses: {}", reminder.lapses);
+    }
+    println!("Due: {}", format_next_review(reminder));
+    println!("{}", "-".repeat(30));
+}
+
+fn print_reminder(store: &ReminderStore, reminder: &Reminder, format: ContentFormat, reveal: bool) {
+    let status = if reminder.completed {
+        style::completed("✓ Completed")
+    } else if reminder.paused_at.is_some() {
+        "⏸ Paused".normal()
+    } else {
+        "⏳ Active".normal()
+    };
+
+    let displayed_content = format.apply(&reminder.content);
+    if reminder.priority == Priority::Normal {
+        println!("ID: {} | {} | Reviews: {}",
+                 style::id(reminder.id), status, reminder.review_count);
+    } else {
+        println!("ID: {} | {} | Reviews: {} | Priority: {}",
+                 style::id(reminder.id), status, reminder.review_count, style::priority(reminder.priority));
+    }
+    println!("Content: {}", displayed_content);
+    print_answer(reminder, format, reveal);
+
+    if let Some(deck) = &reminder.deck {
+        println!("Deck: {}", deck);
+    }
+
+    if !reminder.tags.is_empty() {
+        println!("Tags: {}", style::tags(&reminder.tags.join(", ")));
+    }
+    if !reminder.attachments.is_empty() {
+        println!("Attachments: {}", reminder.attachments.join(", "));
+    }
+    if let Some(recurrence) = &reminder.recurrence {
+        println!("Repeats: {}", recurrence);
+    }
+    if let Some(related) = graph::related_line(store, reminder.id) {
+        println!("Related: {}", related);
+    }
+    if let Some(waiting) = graph::waiting_line(store, reminder.id) {
+        println!("Waiting for: {}", waiting);
+    }
+
+    if !reminder.completed {
+        println!("Next review: {}", format_next_review(reminder));
+    }
+
+    println!("{}", "-".repeat(50));
+}
+
+/// Print every field of one reminder for `show`, untrimmed; the answer and
+/// notes only with `full`.
+fn print_shown_reminder(store: &ReminderStore, reminder: &Reminder, config: &Config, format: ContentFormat, full: bool) {
+    const TIME: &str = "%Y-%m-%d %H:%M";
+    let status = if store.archived.contains_key(&reminder.id) {
+        "Archived".normal()
+    } else if reminder.completed {
+        style::completed("Completed")
+    } else if reminder.paused_at.is_some() {
+        "Paused".normal()
+    } else {
+        "Active".normal()
+    };
+
+    println!("ID: {} | Code: {} | {}", style::id(reminder.id), store.short_code(reminder.id).unwrap_or_default(), status);
+    println!("Content: {}", format.apply(&reminder.content));
+    if let Some(answer) = &reminder.answer {
+        if full {
+            println!("Answer: {}", format.apply(answer));
+        } else {
+            println!("Answer: (hidden, use --full to show)");
         }
+    }
+    println!("Priority: {}", style::priority(reminder.priority));
+    if let Some(deck) = &reminder.deck {
+        println!("Deck: {}", deck);
+    }
+    if !reminder.tags.is_empty() {
+        println!("Tags: {}", style::tags(&reminder.tags.join(", ")));
+    }
+    if !reminder.attachments.is_empty() {
+        println!("Attachments: {}", reminder.attachments.join(", "));
+    }
+    if let Some(related) = graph::related_line(store, reminder.id) {
+        println!("Related: {}", related);
+    }
+    if let Some(waiting) = graph::waiting_line(store, reminder.id) {
+        println!("Waiting for: {}", waiting);
+    }
+    if let Some(source) = &reminder.source {
+        println!("Source: {}", source);
+    }
 
-        Commands::Remove { id } => {
-            match store.remove_reminder(id) {
-                Ok(()) => {
-                    println!("Reminder {} removed successfully", id);
-                    store.save();
-                }
-                Err(e) => eprintln!("Error: {}", e),
-            }
+    println!("Created: {}", dates::local(reminder.created_at).format(TIME));
+    if reminder.completed {
+        println!("Next review: {}", style::completed("none, completed"));
+    } else {
+        println!("Next review: {} ({})", format_next_review(reminder), dates::local(reminder.next_review).format(TIME));
+    }
+    if let Some(last_review) = reminder.last_review {
+        println!("Last review: {}", dates::local(last_review).format(TIME));
+    }
+    match &reminder.recurrence {
+        Some(recurrence) => println!("Repeats: {}", recurrence),
+        None => match reminder.algorithm {
+            Algorithm::Simple => println!(
+                "Algorithm: simple, interval {}, ladder {}{}",
+                format_days(reminder.interval),
+                duration::format_intervals(scheduler::ladder(reminder, config)),
+                if reminder.intervals.is_some() { " (its own)" } else { "" }
+            ),
+            Algorithm::Sm2 => println!(
+                "Algorithm: sm2, interval {}, ease factor {:.2}, {} repetition(s) in a row",
+                format_days(reminder.interval), reminder.ease_factor, reminder.repetitions
+            ),
+            Algorithm::Fsrs => println!(
+                "Algorithm: fsrs, interval {}, stability {:.2} days, difficulty {:.2}",
+                format_days(reminder.interval), reminder.stability, reminder.difficulty
+            ),
+        },
+    }
+    println!("Review count: {}", reminder.review_count);
+    if reminder.snooze_count > 0 {
+        println!("Snoozed: {} time(s) since the last review", reminder.snooze_count);
+    }
+    if reminder.lapses > 0 {
+        println!("Lapses: {}", reminder.lapses);
+    }
+
+    if let Some(notes) = &reminder.notes {
+        if full {
+            println!("Notes:\n{}", format.apply(notes));
+        } else {
+            println!("Notes: (hidden, use --full to show)");
         }
-        Commands::Export {id, path} => {
-            match store.export_to_file(id, path) {
-                Ok(()) => {
-                    println!("Reminder {} export successfully", id);
-                }
-                Err(e) => eprintln!("Error: {}", e),
-            }
+    }
+    if !reminder.history.is_empty() {
+        println!("History:");
+        for index in 0..reminder.history.len() {
+            println!("  {}", format_review_event(reminder, index));
+        }
+    }
+}
+
+fn print_review_result(reminder: &Reminder) {
+    if reminder.completed {
+        println!("Reminder {} {}", style::id(reminder.id), style::completed("completed! 🎉"));
+        println!("You've successfully reviewed this {} times.", reminder.review_count);
+    } else {
+        println!("Reminder {} reviewed!", style::id(reminder.id));
+        println!("Next review: {}", format_next_review(reminder));
+    }
+    let lapsed = reminder.history.last().and_then(|event| event.grade).is_some_and(|grade| grade < scheduler::PASSING_GRADE);
+    if lapsed && reminder.is_leech() {
+        println!("This is a leech: it was forgotten {} times; consider rewriting or splitting it", reminder.lapses);
+    }
+}
+
+/// Widest bar drawn by `stats --chart`.
+const CHART_WIDTH: usize = 40;
+
+fn print_stats(stats: &Stats, chart: bool) {
+    println!("Reminders: {} total, {} active, {} completed, {} archived",
+             stats.total, stats.active, stats.completed, stats.archived);
+    println!("Due now: {}", stats.due);
+    println!("Average interval: {:.1} days", stats.average_interval);
+    println!("Streak: {} day(s), longest {}", stats.streak.current, stats.streak.longest);
+
+    let reviews: usize = stats.reviews_per_day.iter().map(|day| day.count).sum();
+    println!("\nReviews in the last {} days: {}", stats::HISTORY_DAYS, reviews);
+    // Without a chart, days without reviews would only be noise
+    print_day_counts(&stats.reviews_per_day, chart, !chart);
+
+    println!("\nUpcoming reviews in the next {} days:", stats::FORECAST_DAYS);
+    print_day_counts(&stats.upcoming_per_day, chart, false);
+
+    if !stats.tags.is_empty() {
+        println!("\nTags:");
+        for (tag, tag_stats) in &stats.tags {
+            println!("  {}: {} reminder(s), {} active, {} completed, {} review(s)",
+                     tag, tag_stats.total, tag_stats.active, tag_stats.completed, tag_stats.reviews);
         }
     }
 }
+
+fn print_day_counts(days: &[stats::DayCount], chart: bool, skip_empty: bool) {
+    let max = days.iter().map(|day| day.count).max().unwrap_or(0).max(1);
+    for day in days.iter().filter(|day| !skip_empty || day.count > 0) {
+        if chart {
+            let width = (day.count * CHART_WIDTH).div_ceil(max);
+            println!("  {} {:>3} {}", day.date.format("%a %m-%d"), day.count, "█".repeat(width));
+        } else {
+            println!("  {} {}", day.date.format("%a %Y-%m-%d"), day.count);
+        }
+    }
+}
+
+/// One line of a reminder's review history: when, the grade and what it
+/// scheduled.
+fn format_review_event(reminder: &Reminder, index: usize) -> String {
+    let event = &reminder.history[index];
+    let grade = event.grade.map(|grade| grade.to_string()).unwrap_or_else(|| "-".to_string());
+    let outcome = if reminder.completed && index + 1 == reminder.history.len() {
+        "completed".to_string()
+    } else {
+        format!("next in {}", format_days(event.interval))
+    };
+    format!("{}  grade {}  {}", dates::local(event.timestamp).format("%Y-%m-%d %H:%M"), grade, outcome)
+}
+
+fn format_days(days: u32) -> String {
+    if days == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{} days", days)
+    }
+}
+
+
+/// Exit status of `count` and `check --quiet` when at least one reminder is
+/// due.
+const EXIT_DUE: i32 = 1;
+
+fn main() {
+    // Answers completion requests from the script printed by `completions`
+    CompleteEnv::with_factory(Cli::command).var(completions::COMPLETE_VAR).complete();
+
+    let mut cli = Cli::parse();
+    let config = Config::load();
+    cli.apply_config(&config);
+    let json = cli.json;
+    if let Err(e) = logging::init(cli.verbose, cli.log_file.as_deref()) {
+        report_error(&e, json);
+        process::exit(e.exit_code());
+    }
+
+    if let Err(e) = run(cli, config) {
+        tracing::error!(error = %e, "command failed");
+        report_error(&e, json);
+        process::exit(e.exit_code());
+    }
+}
+
+/// Config commands work on the config file, without loading it as the config
+/// of this invocation.
+fn run_config_command(command: ConfigCommand, json: bool) -> Result<()> {
+    match command {
+        ConfigCommand::List => {
+            let settings = settings::list()?;
+            if json {
+                print_json(&settings);
+            } else {
+                print_settings(&settings);
+            }
+        }
+        ConfigCommand::Get { key } => {
+            let settings = settings::get(&key)?;
+            match settings.as_slice() {
+                [setting] if setting.key == key => {
+                    if json {
+                        print_json(setting);
+                    } else if let Some(text) = setting.value.as_str() {
+                        // Unquoted, to use in scripts
+                        println!("{}", text);
+                    } else if !setting.value.is_null() {
+                        println!("{}", format_setting(&setting.value));
+                    }
+                }
+                settings if json => print_json(settings),
+                settings => print_settings(settings),
+            }
+        }
+        ConfigCommand::Set { key, value } => {
+            settings::set(&key, &value)?;
+            let setting = settings::get(&key).ok()
+                .and_then(|settings| settings.into_iter().find(|setting| setting.key == key));
+            if json {
+                print_json(&setting);
+            } else if let Some(setting) = setting {
+                println!("Set {} = {}", key, format_setting(&setting.value));
+            } else {
+                println!("Set {}", key);
+            }
+        }
+        ConfigCommand::Unset { key } => {
+            let removed = settings::unset(&key)?;
+            if json {
+                print_json(&serde_json::json!({ "key": key, "removed": removed }));
+            } else if removed {
+                println!("Removed {} from the config file, it's back to its default", key);
+            } else {
+                println!("{} isn't set in the config file", key);
+            }
+        }
+        ConfigCommand::Edit => {
+            let original = settings::read_file()?;
+            let mut text = original.clone();
+            let warnings = loop {
+                text = editor::edit_text(&text)?;
+                if text == original {
+                    println!("No changes made");
+                    return Ok(());
+                }
+                match settings::write_file(&text) {
+                    Ok(warnings) => break warnings,
+                    // The edits aren't thrown away over a typo
+                    Err(e) if io::stdin().is_terminal() => {
+                        eprintln!("Error: {}", e);
+                        eprint!("Press Enter to edit the file again, or Ctrl+C to discard the changes ");
+                        let _ = io::stderr().flush();
+                        let mut line = String::new();
+                        io::stdin().read_line(&mut line).map_err(|e| Error::io("Failed to read the answer", e))?;
+                    }
+                    Err(e) => return Err(e),
+                }
+            };
+            for warning in &warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            if json {
+                print_json(&serde_json::json!({ "saved": config::get_config_file_path(), "warnings": warnings }));
+            } else {
+                println!("Saved {}", config::get_config_file_path().display());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_settings(settings: &[settings::Setting]) {
+    for setting in settings {
+        if setting.value.is_null() {
+            println!("{} {}", setting.key, "(not set)".dimmed());
+        } else if setting.set {
+            println!("{} = {}", setting.key, format_setting(&setting.value));
+        } else {
+            println!("{} = {} {}", setting.key, format_setting(&setting.value), "(default)".dimmed());
+        }
+    }
+}
+
+/// A setting's value the way it is written in the config file.
+fn format_setting(value: &serde_json::Value) -> String {
+    toml::Value::try_from(value)
+        .map(|value| value.to_string())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// Profile commands work on the data directories, without loading a store.
+fn run_profile_command(command: ProfileCommand, json: bool) -> Result<()> {
+    match command {
+        ProfileCommand::List => {
+            let profiles = profile::list();
+            if json {
+                let profiles: Vec<_> = profiles.iter()
+                    .map(|name| serde_json::json!({
+                        "name": name,
+                        "current": name == profile::current(),
+                        "path": profile::data_dir(name),
+                    }))
+                    .collect();
+                print_json(&profiles);
+            } else {
+                for name in profiles {
+                    let marker = if name == profile::current() { "*" } else { " " };
+                    println!("{} {} ({})", marker, name, profile::data_dir(&name).display());
+                }
+            }
+        }
+        ProfileCommand::Create { name } => {
+            profile::create(&name)?;
+            if json {
+                print_json(&serde_json::json!({ "created": name, "path": profile::data_dir(&name) }));
+            } else {
+                println!("Created profile '{}'; use it with --profile {} or profile = \"{}\" in the config", name, name, name);
+            }
+        }
+        ProfileCommand::Delete { name, with_reminders } => {
+            if name == profile::current() {
+                return Err(Error::Invalid(format!("Profile '{}' is in use; switch to another one first", name)));
+            }
+            if profile::has_store(&name) && !with_reminders {
+                return Err(Error::Invalid(format!(
+                    "Profile '{}' still holds reminders; pass --with-reminders to delete them too",
+                    name
+                )));
+            }
+            profile::delete(&name)?;
+            if json {
+                print_json(&serde_json::json!({ "deleted": name }));
+            } else {
+                println!("Deleted profile '{}'", name);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_install_schedule(cli: &Cli, systemd: bool, cron: bool, every: Duration, remove: bool) -> Result<()> {
+    let scheduler = if systemd {
+        schedule::Scheduler::Systemd
+    } else if cron {
+        schedule::Scheduler::Cron
+    } else {
+        schedule::Scheduler::detect()?
+    };
+
+    // The scheduled check uses the same store as this command
+    let mut args = Vec::new();
+    if let Some(profile) = &cli.profile {
+        args.extend(["--profile".to_string(), profile.clone()]);
+    }
+    if let Some(data_file) = &cli.data_file {
+        let path = fs::canonicalize(data_file).unwrap_or_else(|_| data_file.clone());
+        args.extend(["--data-file".to_string(), path.to_string_lossy().into_owned()]);
+    }
+    if let Some(kind) = cli.storage.and_then(|kind| kind.to_possible_value()) {
+        args.extend(["--storage".to_string(), kind.get_name().to_string()]);
+    }
+    if cli.offline {
+        args.push("--offline".to_string());
+    }
+    let name = match profile::current() {
+        profile::DEFAULT_PROFILE => "reminder-check".to_string(),
+        name => format!("reminder-check-{}", name),
+    };
+    let schedule = schedule::Schedule { name, args, every };
+
+    if remove {
+        let removed = schedule.remove(scheduler)?;
+        if cli.json {
+            print_json(&serde_json::json!({ "scheduler": scheduler.name(), "name": schedule.name, "removed": removed }));
+        } else if removed {
+            println!("Removed the {} schedule '{}'", scheduler.name(), schedule.name);
+        } else {
+            println!("No {} schedule '{}' is installed", scheduler.name(), schedule.name);
+        }
+        return Ok(());
+    }
+
+    schedule.install(scheduler)?;
+    if cli.json {
+        print_json(&serde_json::json!({
+            "scheduler": scheduler.name(),
+            "name": schedule.name,
+            "every_seconds": every.num_seconds(),
+        }));
+    } else {
+        println!(
+            "Installed the {} schedule '{}': due reminders are announced every {}",
+            scheduler.name(),
+            schedule.name,
+            schedule::describe(every)
+        );
+        if scheduler == schedule::Scheduler::Systemd {
+            println!("See its runs with 'journalctl --user -u {}.service'", schedule.name);
+        }
+    }
+    Ok(())
+}
+
+fn run(cli: Cli, mut config: Config) -> Result<()> {
+    if let Commands::Completions { shell, static_script } = cli.command {
+        return completions::print_script(shell, static_script);
+    }
+    if cli.dry_run {
+        if let Some(name) = effects_beyond_store(&cli.command) {
+            return Err(Error::Invalid(format!("'{}' does more than change the reminder store and can't be dry run", name)));
+        }
+        DRY_RUN.store(true, Ordering::Relaxed);
+    }
+    if let Commands::Config { command } = cli.command {
+        return run_config_command(command, cli.json);
+    }
+
+    if let Some(offset) = &config.utc_offset {
+        match dates::parse_utc_offset(offset) {
+            Ok(offset) => dates::set_utc_offset(offset),
+            Err(e) => eprintln!("Warning: {} in config, using the system time zone", e),
+        }
+    }
+    if let Some(hour) = config.day_rollover_hour {
+        dates::set_day_rollover(hour);
+    }
+    if let Some(now) = &cli.now {
+        // Read after the time zone settings, so "tomorrow 9am" is where the user is
+        let now = dates::parse_when(now, Utc::now())?;
+        tracing::info!(now = %now, "pretending the time");
+        clock::set(Arc::new(clock::OffsetClock::starting_at(now)));
+    }
+    let profile_name = cli.profile.as_deref().or(config.profile.as_deref()).unwrap_or(profile::DEFAULT_PROFILE);
+    profile::select(profile_name)?;
+    if let Commands::Profile { command } = cli.command {
+        return run_profile_command(command, cli.json);
+    }
+    if let Some(data_file) = &cli.data_file {
+        storage::set_data_file(data_file)?;
+    } else if !profile::exists(profile::current()) {
+        return Err(Error::Invalid(format!(
+            "Profile '{0}' does not exist; create it with 'reminder profile create {0}'",
+            profile::current()
+        )));
+    }
+    if let Commands::InstallSchedule { systemd, cron, every, remove } = cli.command {
+        return run_install_schedule(&cli, systemd, cron, every, remove);
+    }
+    if cli.offline {
+        if let Some(webdav) = config.webdav.as_mut() {
+            webdav.offline = true;
+        }
+    }
+    let storage_kind = cli.storage.unwrap_or(config.storage);
+    tracing::info!(command = %undo::current_command(), profile = profile::current(), "running");
+    let mut storage = storage::open(storage_kind, &config);
+    let lock = lock::acquire()?;
+    let mut store = match storage.load() {
+        // Repairing it is all that can be done with a store that was moved aside
+        Err(Error::Corrupt(_)) if matches!(cli.command, Commands::Repair { .. }) => ReminderStore::default(),
+        result => result?,
+    };
+    // Neither is a change of its own: the derived UUIDs are saved, and the
+    // expired reminders in the trash deleted, with the next one
+    store.assign_uuids();
+    store.purge_trash(config.trash_days, clock::now());
+    let original = store.clone();
+    let trim_opt = cli.trim;
+    let colored = style::init(cli.color.unwrap_or(config.color), &config);
+    let format = ContentFormat {
+        trim: trim_opt,
+        markdown: cli.render_md || config.render_markdown,
+        styled: colored,
+    };
+    let json = cli.json;
+    let paging = !cli.no_pager;
+
+    match cli.command {
+        Commands::Add { content, edit: _, template, clipboard, force, front, back, algorithm, tags, due, priority, deck, attachments: files, recur, notes } => {
+            let due = due.map(|due| dates::parse_when(&due, clock::now())).transpose()?;
+            let recurrence = recur.map(|rule| Recurrence::parse(&rule)).transpose()?;
+            for file in &files {
+                attachments::check(file)?;
+            }
+
+            let content = if clipboard {
+                let text = clipboard::read()?.trim().to_string();
+                if text.is_empty() {
+                    return Err(Error::Invalid("The clipboard is empty".to_string()));
+                }
+                text
+            } else if let Some(content) = content.or(front) {
+                content
+            } else {
+                // Nothing given, or --edit: write the content in $EDITOR
+                let template = match template.or_else(|| config.add_template.clone()) {
+                    Some(path) => fs::read_to_string(&path)
+                        .map_err(|e| Error::io(format!("Failed to read template {}", path.display()), e))?,
+                    None => String::new(),
+                };
+                let text = editor::edit_text(&template)?;
+                if text.trim().is_empty() || text.trim() == template.trim() {
+                    return Err(Error::Invalid("Nothing was written, no reminder added".to_string()));
+                }
+                text.trim().to_string()
+            };
+
+            if !force && config.duplicate_action != DuplicateAction::Allow {
+                let duplicates = search::find_duplicates(store.reminders.values().chain(store.archived.values()), &content);
+                if !duplicates.is_empty() {
+                    let described: Vec<String> = duplicates.iter().take(3)
+                        .map(|(reminder, duplicate)| format!("reminder {} {} (\"{}\")", reminder.id, duplicate.describe(), graph::label(reminder, Some(40))))
+                        .collect();
+                    if config.duplicate_action == DuplicateAction::Refuse {
+                        return Err(Error::Invalid(format!("Not added: {}; use --force to add it anyway", described.join("; "))));
+                    }
+                    eprintln!("Warning: {}", described.join("; "));
+                }
+            }
+            let new = NewReminder {
+                content: content.clone(), // Store full content
+                answer: back,
+                algorithm,
+                tags,
+                due,
+                priority,
+                deck,
+                recurrence,
+                notes,
+            };
+            let id = store.add_reminder(new, &config);
+            for file in &files {
+                store.add_attachment(id, attachments::copy_in(id, file)?)?;
+            }
+            save_store(storage.as_mut(), &store, &original)?;
+            if json {
+                print_json(&store.reminders[&id]);
+            } else {
+                let displayed_content = get_trimmed_content(&content, trim_opt);
+                let code = store.short_code(id).unwrap_or_default();
+                println!("Added reminder with ID {} (code {}): \"{}\"", id, code, displayed_content);
+                let reminder = &store.reminders[&id];
+                if due.is_some() || reminder.recurrence.is_some() {
+                    println!("Next review: {}", dates::local(reminder.next_review).format("%Y-%m-%d %H:%M"));
+                } else {
+                    println!("Next review: {} from now", format_days(reminder.interval));
+                }
+            }
+        }
+
+        Commands::Show { id, full } => {
+            let id = id.resolve(&store)?;
+            let reminder = store.find_reminder(id).ok_or(Error::NotFound(id))?;
+            if json {
+                print_json(reminder);
+            } else {
+                // Showing one reminder is the way to read all of it
+                print_shown_reminder(&store, reminder, &config, ContentFormat { trim: None, ..format }, full);
+            }
+        }
+
+        Commands::Check { tags, priorities, deck, reveal, no_limit, max_age, notify, quiet } => {
+            let now = clock::now();
+            let oldest = max_age
+                .map(|age| now.checked_sub_signed(age)
+                    .ok_or_else(|| Error::Invalid("The --max-age reaches before the first date reminder can show".to_string())))
+                .transpose()?;
+            let due_reminders: Vec<&Reminder> = store.get_due_reminders()
+                .into_iter()
+                .filter(|r| r.has_tags(&tags) && r.has_priority(&priorities) && r.in_deck(deck.as_deref()))
+                .filter(|r| oldest.is_none_or(|oldest| r.next_review >= oldest))
+                .collect();
+            let (due_reminders, deferred) = if no_limit {
+                (due_reminders, 0)
+            } else {
+                store.apply_daily_limit(due_reminders, &config)
+            };
+
+            if notify {
+                notify::notify_all(&notify::notifiers(&config, true), &due_reminders);
+            }
+
+            if quiet {
+                process::exit(if due_reminders.is_empty() { 0 } else { EXIT_DUE });
+            } else if json {
+                print_json(&due_reminders);
+            } else if due_reminders.is_empty() {
+                if deferred > 0 {
+                    println!("Daily review limit reached, {} reminder(s) deferred to later days", deferred);
+                } else {
+                    println!("No reminders due for review!");
+                }
+            } else {
+
+                println!("Reminders due for review:");
+                println!("{}", "=".repeat(50));
+
+                // The longest waiting first, each group in the usual order
+                for bucket in [DueBucket::LongOverdue, DueBucket::Overdue, DueBucket::Today] {
+                    let in_bucket: Vec<&Reminder> = due_reminders.iter()
+                        .copied()
+                        .filter(|r| r.due_bucket(now).unwrap_or(DueBucket::Today) == bucket)
+                        .collect();
+                    if in_bucket.is_empty() {
+                        continue;
+                    }
+                    let heading = match bucket {
+                        DueBucket::LongOverdue => style::due(&format!("Overdue by more than a week ({}):", in_bucket.len())).bold(),
+                        DueBucket::Overdue => style::due(&format!("Overdue ({}):", in_bucket.len())),
+                        DueBucket::Today => format!("Due today ({}):", in_bucket.len()).bold(),
+                    };
+                    println!("\n{}", heading);
+                    for reminder in in_bucket {
+                        print_due_reminder(&store, reminder, format, reveal);
+                    }
+                }
+                
+                if deferred > 0 {
+                    println!("\n{} more due reminder(s) deferred to later days by the daily review limit", deferred);
+                }
+                println!("\nUse 'reminder review <ID>' to mark a reminder as reviewed");
+                println!("or 'reminder snooze <ID> <DURATION>' to postpone it");
+            }
+        }
+
+        Commands::List { tags, priorities, deck, reveal, archived, table, columns, graph, sort, reverse, statuses, due_within, created_since, limit, offset } => {
+            let now = clock::now();
+            let created_since = created_since.map(|since| dates::parse_since(&since, now)).transpose()?;
+            let due_by = due_within
+                .map(|within| now.checked_add_signed(within)
+                    .ok_or_else(|| Error::Invalid("The --due-within window reaches past the last date reminder can show".to_string())))
+                .transpose()?;
+            let reminders = if archived {
+                store.get_archived_reminders()
+            } else {
+                store.get_all_reminders()
+            };
+            let mut reminders: Vec<&Reminder> = reminders
+                .into_iter()
+                .filter(|r| r.has_tags(&tags) && r.has_priority(&priorities) && r.in_deck(deck.as_deref()))
+                .filter(|r| r.has_status(&statuses, now))
+                .filter(|r| due_by.is_none_or(|by| r.is_due(by)))
+                .filter(|r| created_since.is_none_or(|since| r.created_at >= since))
+                .collect();
+            if let Some(key) = sort.or(config.sort) {
+                reminder::sort_reminders(&mut reminders, key);
+            }
+            if reverse {
+                reminders.reverse();
+            }
+            let total = reminders.len();
+            let reminders: Vec<&Reminder> = reminders.into_iter().skip(offset).take(limit.unwrap_or(usize::MAX)).collect();
+            let shown = reminders.len();
+            let _pager = pager::Pager::start(paging);
+            
+            if graph {
+                let graph = graph::Graph::new(&store, reminders);
+                if json {
+                    print_json(&graph);
+                } else if graph.nodes.is_empty() {
+                    println!("No reminders found!");
+                } else {
+                    graph::print_graph(&graph, trim_opt);
+                }
+            } else if json {
+                print_json(&reminders);
+            } else if reminders.is_empty() {
+                println!("No reminders found!");
+            } else if table || !columns.is_empty() {
+                let columns = if columns.is_empty() { table::DEFAULT_COLUMNS.to_vec() } else { columns };
+                table::print_table(&reminders, &columns, trim_opt);
+            } else {
+                println!("{}", if archived { "Archived reminders:" } else { "All reminders:" });
+                println!("{}", "=".repeat(70));
+                
+                for reminder in reminders {
+                    print_reminder(&store, reminder, format, reveal);
+                }
+            }
+            if !json && shown > 0 && shown < total {
+                let next = if offset + shown < total {
+                    format!("; --offset {} shows the next ones", offset + shown)
+                } else {
+                    String::new()
+                };
+                println!("Showing {}-{} of {} reminders{}", offset + 1, offset + shown, total, next);
+            }
+        }
+
+        Commands::Search { query, regex, tags } => {
+            let query = query.map(|q| search::Query::new(&q, regex)).transpose()?;
+
+            let matches: Vec<&Reminder> = store.get_all_reminders()
+                .into_iter()
+                .filter(|r| r.has_tags(&tags))
+                .filter(|r| query.as_ref().is_none_or(|q| q.matches(r)))
+                .collect();
+            let _pager = pager::Pager::start(paging);
+
+            if json {
+                print_json(&matches);
+            } else if matches.is_empty() {
+                println!("No matching reminders found!");
+            } else {
+                println!("Found {} matching reminder(s):", matches.len());
+                println!("{}", "=".repeat(70));
+
+                for reminder in matches {
+                    print_reminder(&store, reminder, format, false);
+                }
+            }
+        }
+
+        Commands::Stats { chart } => {
+            let stats = Stats::compute(&store, clock::now());
+
+            if json {
+                print_json(&stats);
+            } else {
+                print_stats(&stats, chart);
+            }
+        }
+
+        Commands::Forecast { days } => {
+            let forecast = stats::forecast(&store, clock::now(), days);
+
+            if json {
+                print_json(&forecast);
+            } else {
+                let total: usize = forecast.iter().map(|day| day.count).sum();
+                println!("{} review(s) due in the next {} (overdue ones count today):", total, format_days(days as u32));
+                print_day_counts(&forecast, true, false);
+            }
+        }
+
+        Commands::Simulate { days, new_per_day } => {
+            let simulation = simulate::run(&store, &config, days, new_per_day);
+
+            if json {
+                print_json(&simulation);
+            } else {
+                let shares = config.simulate;
+                let total_share = shares.again + shares.hard + shares.good + shares.easy;
+                let percent = |share: f64| (share / total_share * 100.0).round();
+                println!(
+                    "Simulated {} with {} new reminder(s) a day, rating reviews again {}%, hard {}%, good {}%, easy {}%:",
+                    format_days(days as u32),
+                    new_per_day,
+                    percent(shares.again),
+                    percent(shares.hard),
+                    percent(shares.good),
+                    percent(shares.easy)
+                );
+                let max = simulation.days.iter().map(|day| day.reviews).max().unwrap_or(0).max(1);
+                for day in &simulation.days {
+                    let width = (day.reviews * CHART_WIDTH).div_ceil(max);
+                    println!("  {} {:>4} {}", day.date.format("%a %m-%d"), day.reviews, "█".repeat(width));
+                }
+                let total = simulation.total_reviews();
+                print!("{} review(s), {:.1} a day on average", total, total as f64 / days as f64);
+                match simulation.busiest_day() {
+                    Some(busiest) if busiest.reviews > 0 => {
+                        println!(" and at most {} on {}", busiest.reviews, busiest.date.format("%a %Y-%m-%d"));
+                    }
+                    _ => println!(),
+                }
+                let forgotten: usize = simulation.days.iter().map(|day| day.forgotten).sum();
+                println!(
+                    "{} forgotten, {} reminder(s) completed and {} still active at the end",
+                    forgotten, simulation.completed, simulation.active
+                );
+            }
+        }
+
+        Commands::Digest { email, days } => {
+            let digest = digest::Digest::collect(&store, clock::now(), days);
+
+            if email {
+                let email_config = config.email.as_ref()
+                    .ok_or_else(|| Error::Invalid("No [email] section in the config file".to_string()))?;
+                digest::send_email(&digest, email_config)?;
+            }
+
+            if json {
+                print_json(&digest);
+            } else if email {
+                let to = config.email.as_ref().map(|email| email.to.as_str()).unwrap_or_default();
+                println!("Digest sent to {}: {}", to, digest.subject());
+            } else {
+                print!("{}", digest.to_text());
+            }
+        }
+
+        Commands::Tags => {
+            let counts = store.get_tag_counts();
+
+            if json {
+                print_json(&counts);
+            } else if counts.is_empty() {
+                println!("No tags found!");
+            } else {
+                for (tag, count) in counts {
+                    println!("{} ({})", tag, count);
+                }
+            }
+        }
+
+        Commands::Count { due: _, all, tags } => {
+            let due_count = store.get_due_reminders()
+                .into_iter()
+                .filter(|r| r.has_tags(&tags))
+                .count();
+            let count = if all {
+                store.reminders.values().filter(|r| r.has_tags(&tags)).count()
+            } else {
+                due_count
+            };
+
+            if json {
+                print_json(&serde_json::json!({ "count": count, "due": due_count }));
+            } else {
+                println!("{}", count);
+            }
+
+            if due_count > 0 {
+                process::exit(EXIT_DUE);
+            }
+        }
+
+        Commands::Review { ids, selectors, grade, again, hard, good, easy } => {
+            let rating = [(again, Rating::Again), (hard, Rating::Hard), (good, Rating::Good), (easy, Rating::Easy)]
+                .into_iter()
+                .find_map(|(chosen, rating)| chosen.then_some(rating));
+            let grade = rating.map(Rating::grade).or(grade);
+            let ids = select::resolve(&store, &ids, &selectors, true)?;
+            for &id in &ids {
+                store.review_reminder(id, grade, &config)?;
+            }
+            save_store(storage.as_mut(), &store, &original)?;
+
+            let reviewed: Vec<&Reminder> = ids.iter().map(|id| &store.reminders[id]).collect();
+            if json {
+                print_json_selection(&reviewed);
+            } else {
+                for reminder in reviewed {
+                    print_review_result(reminder);
+                }
+            }
+        }
+
+        Commands::Edit { id, matching, content, back, priority, deck, recur, notes } => {
+            let id = match (id, matching) {
+                (Some(id), _) => id.resolve(&store)?,
+                // clap requires either the ID or --match
+                (None, matching) => select::resolve_match(&store, &matching.unwrap_or_default(), false)?,
+            };
+            let recurrence = recur.map(|rule| match rule.trim() {
+                "" => Ok(None),
+                rule => Recurrence::parse(rule).map(Some),
+            }).transpose()?;
+            let notes = match notes {
+                // A bare --notes edits the current notes in $EDITOR
+                Some(None) => {
+                    let reminder = store.reminders.get(&id).ok_or(Error::NotFound(id))?;
+                    Some(editor::edit_text(reminder.notes.as_deref().unwrap_or_default())?)
+                }
+                notes => notes.flatten(),
+            };
+            let mut changes = ReminderChanges { content, answer: back, priority, deck, recurrence, notes };
+
+            // Without any flags, edit the content in $EDITOR
+            if changes.is_empty() {
+                let reminder = store.reminders.get(&id).ok_or(Error::NotFound(id))?;
+                changes.content = Some(editor::edit_text(&reminder.content)?);
+            }
+
+            store.edit_reminder(id, changes)?;
+            save_store(storage.as_mut(), &store, &original)?;
+            let reminder = &store.reminders[&id];
+            if json {
+                print_json(reminder);
+            } else {
+                let displayed_content = get_trimmed_content(&reminder.content, trim_opt);
+                println!("Reminder {} updated: \"{}\"", id, displayed_content);
+            }
+        }
+
+        Commands::Attach { id, path } => {
+            let id = id.resolve(&store)?;
+            if !store.reminders.contains_key(&id) {
+                return Err(Error::NotFound(id));
+            }
+            let name = attachments::copy_in(id, &path)?;
+            store.add_attachment(id, name.clone())?;
+            save_store(storage.as_mut(), &store, &original)?;
+            if json {
+                print_json(&store.reminders[&id]);
+            } else {
+                println!("Attached {} to reminder {} as {}", path.display(), id, name);
+            }
+        }
+
+        Commands::OpenAttachment { id, nth } => {
+            let id = id.resolve(&store)?;
+            let reminder = store.find_reminder(id).ok_or(Error::NotFound(id))?;
+            let names = match nth {
+                Some(nth) => nth.checked_sub(1).and_then(|index| reminder.attachments.get(index)).map(std::slice::from_ref).ok_or_else(|| {
+                    Error::Invalid(format!("Reminder {} has no attachment {}, only {}", id, nth, reminder.attachments.len()))
+                })?,
+                None if reminder.attachments.is_empty() => {
+                    return Err(Error::Invalid(format!("Reminder {} has no attachments", id)));
+                }
+                None => &reminder.attachments[..],
+            };
+            for name in names {
+                let path = attachments::path(name);
+                if !path.is_file() {
+                    return Err(Error::Invalid(format!("Attachment {} is missing from {}", name, attachments::dir().display())));
+                }
+                launcher::open(path.as_os_str())?;
+            }
+            if json {
+                print_json(names);
+            } else {
+                for name in names {
+                    println!("Opened {}", name);
+                }
+            }
+        }
+
+        Commands::Open { id, nth } => {
+            let id = id.resolve(&store)?;
+            let reminder = store.find_reminder(id).ok_or(Error::NotFound(id))?;
+            let urls = reminder.urls();
+            let url = match nth.checked_sub(1).and_then(|index| urls.get(index)) {
+                Some(url) => *url,
+                None if urls.is_empty() => {
+                    return Err(Error::Invalid(format!("Reminder {} has no web address in it", id)));
+                }
+                None => {
+                    return Err(Error::Invalid(format!("Reminder {} has no web address {}, only {}", id, nth, urls.len())));
+                }
+            };
+            launcher::open(url.as_ref())?;
+            if json {
+                print_json(&serde_json::json!({ "id": id, "url": url }));
+            } else {
+                println!("Opened {}", url);
+            }
+        }
+
+        Commands::History { id } => {
+            let id = id.resolve(&store)?;
+            let reminder = store.find_reminder(id).ok_or(Error::NotFound(id))?;
+
+            if json {
+                print_json(&reminder.history);
+            } else if reminder.history.is_empty() {
+                println!("Reminder {} has not been reviewed yet", id);
+            } else {
+                println!("Review history of reminder {}: \"{}\"", id, get_trimmed_content(&reminder.content, trim_opt));
+                println!("{}", "=".repeat(50));
+                for index in 0..reminder.history.len() {
+                    println!("{}", format_review_event(reminder, index));
+                }
+            }
+        }
+
+        Commands::Versions { id, restore: Some(number) } => {
+            let id = id.resolve(&store)?;
+            let changed = store.restore_version(id, number)?;
+            if changed {
+                save_store(storage.as_mut(), &store, &original)?;
+            }
+            let reminder = &store.reminders[&id];
+            if json {
+                print_json(reminder);
+            } else if changed {
+                println!("Restored version {} of reminder {} as version {}", number, id, reminder.version_count());
+            } else {
+                println!("Version {} is already the content of reminder {}", number, id);
+            }
+        }
+
+        Commands::Versions { id, restore: None } => {
+            let id = id.resolve(&store)?;
+            let reminder = store.find_reminder(id).ok_or(Error::NotFound(id))?;
+            let versions: Vec<(usize, DateTime<Utc>, &str)> = (1..=reminder.version_count())
+                .filter_map(|number| Some((number, reminder.version_written_at(number)?, reminder.version(number).ok()?)))
+                .collect();
+
+            if json {
+                let versions: Vec<_> = versions.iter()
+                    .map(|(number, written_at, content)| serde_json::json!({
+                        "version": number,
+                        "written_at": written_at,
+                        "content": content,
+                        "current": *number == reminder.version_count(),
+                    }))
+                    .collect();
+                print_json(&versions);
+            } else if reminder.versions.is_empty() {
+                println!("The content of reminder {} hasn't been edited", id);
+            } else {
+                println!("Versions of reminder {}:", id);
+                for (number, written_at, content) in versions {
+                    let current = if number == reminder.version_count() { " (current)" } else { "" };
+                    println!("  {:>3}  {}  {}{}", number, dates::local(written_at).format("%Y-%m-%d %H:%M"),
+                             get_trimmed_content(content.lines().next().unwrap_or_default(), trim_opt), current);
+                }
+                println!("'reminder diff {} <V1> [V2]' compares two of them", id);
+            }
+        }
+
+        Commands::Diff { id, from, to } => {
+            let id = id.resolve(&store)?;
+            let reminder = store.find_reminder(id).ok_or(Error::NotFound(id))?;
+            let to = to.unwrap_or(reminder.version_count());
+            let changes = diff::lines(reminder.version(from)?, reminder.version(to)?);
+
+            if json {
+                print_json(&serde_json::json!({ "id": id, "from": from, "to": to, "lines": changes }));
+            } else {
+                let heading = |number: usize| {
+                    let current = if number == reminder.version_count() { ", current" } else { "" };
+                    let written_at = reminder.version_written_at(number).map(dates::local).unwrap_or_default();
+                    format!("version {} ({}{})", number, written_at.format("%Y-%m-%d %H:%M"), current)
+                };
+                println!("{}", style::removed(&format!("--- {}", heading(from))));
+                println!("{}", style::added(&format!("+++ {}", heading(to))));
+                for change in changes {
+                    match change {
+                        diff::LineChange::Kept(line) => println!("  {}", line),
+                        diff::LineChange::Removed(line) => println!("{}", style::removed(&format!("- {}", line))),
+                        diff::LineChange::Added(line) => println!("{}", style::added(&format!("+ {}", line))),
+                    }
+                }
+            }
+        }
+
+        Commands::Schedule { id, when } => {
+            let id = id.resolve(&store)?;
+            let when = dates::parse_when(&when, clock::now())?;
+            store.schedule_reminder(id, when)?;
+            save_store(storage.as_mut(), &store, &original)?;
+            let reminder = &store.reminders[&id];
+            if json {
+                print_json(reminder);
+            } else {
+                println!("Reminder {} scheduled", id);
+                println!("Next review: {} ({})",
+                         dates::local(reminder.next_review).format("%Y-%m-%d %H:%M"),
+                         format_duration_until(reminder.next_review));
+            }
+        }
+
+        Commands::SetIntervals { id, intervals } => {
+            let id = id.resolve(&store)?;
+            let intervals = if intervals.trim().is_empty() {
+                None
+            } else {
+                Some(duration::parse_intervals(&intervals)?)
+            };
+            store.set_intervals(id, intervals)?;
+            save_store(storage.as_mut(), &store, &original)?;
+            let reminder = &store.reminders[&id];
+            if json {
+                print_json(reminder);
+            } else {
+                let ladder = duration::format_intervals(scheduler::ladder(reminder, &config));
+                match reminder.intervals {
+                    Some(_) => println!("Reminder {} now follows its own intervals {}", id, ladder),
+                    None => println!("Reminder {} follows the shared intervals {} again", id, ladder),
+                }
+                println!("They apply from its next review, due {}", format_duration_until(reminder.next_review));
+            }
+        }
+
+        Commands::Snooze { mut args, selectors } => {
+            // clap guarantees at least the duration is present
+            let duration = duration::parse_duration(&args.pop().unwrap_or_default())?;
+            let ids = select::resolve(&store, &args, &selectors, true)?;
+            for &id in &ids {
+                store.snooze_reminder(id, duration)?;
+            }
+            save_store(storage.as_mut(), &store, &original)?;
+
+            let snoozed: Vec<&Reminder> = ids.iter().map(|id| &store.reminders[id]).collect();
+            if json {
+                print_json_selection(&snoozed);
+            } else {
+                for reminder in snoozed {
+                    println!("Reminder {} snoozed", reminder.id);
+                    println!("Next review: {}", format_duration_until(reminder.next_review));
+                }
+            }
+        }
+
+        Commands::Remove { ids, selectors } => {
+            let ids = select::resolve(&store, &ids, &selectors, false)?;
+            for &id in &ids {
+                store.remove_reminder(id)?;
+            }
+            save_store(storage.as_mut(), &store, &original)?;
+
+            if json {
+                let removed: Vec<_> = ids.iter().map(|id| serde_json::json!({ "id": id, "removed": true })).collect();
+                print_json_selection(&removed);
+            } else {
+                for id in ids {
+                    println!("Reminder {} moved to the trash, use 'reminder trash restore {}' to bring it back", id, id);
+                }
+            }
+        }
+
+        Commands::Postpone { ids, selectors, by, proportional } => {
+            let ids = select::resolve(&store, &ids, &selectors, true)?;
+            let how = match (by, proportional) {
+                (_, Some(factor)) => Postponement::Proportional(factor),
+                (Some(over), None) => Postponement::Spread(over),
+                (None, None) => unreachable!("clap requires --by or --proportional"),
+            };
+            let order = store.postpone_reminders(&ids, how)?;
+            save_store(storage.as_mut(), &store, &original)?;
+
+            let postponed: Vec<&Reminder> = order.iter().map(|id| &store.reminders[id]).collect();
+            if json {
+                print_json_selection(&postponed);
+            } else {
+                for reminder in postponed {
+                    println!("Reminder {} postponed, next review {}", reminder.id, format_duration_until(reminder.next_review));
+                }
+            }
+        }
+
+        Commands::Pause { ids, selectors } => {
+            let ids = select::resolve(&store, &ids, &selectors, true)?;
+            for &id in &ids {
+                store.pause_reminder(id)?;
+            }
+            save_store(storage.as_mut(), &store, &original)?;
+
+            if json {
+                let paused: Vec<&Reminder> = ids.iter().map(|id| &store.reminders[id]).collect();
+                print_json_selection(&paused);
+            } else {
+                for id in ids {
+                    println!("Reminder {} paused, use 'reminder resume {}' to continue its schedule", id, id);
+                }
+            }
+        }
+
+        Commands::Resume { ids, selectors } => {
+            let ids = select::resolve(&store, &ids, &selectors, true)?;
+            let mut paused_for = Vec::with_capacity(ids.len());
+            for &id in &ids {
+                paused_for.push(store.resume_reminder(id)?);
+            }
+            save_store(storage.as_mut(), &store, &original)?;
+
+            if json {
+                let resumed: Vec<&Reminder> = ids.iter().map(|id| &store.reminders[id]).collect();
+                print_json_selection(&resumed);
+            } else {
+                for (id, paused_for) in ids.iter().zip(paused_for) {
+                    println!("Reminder {} resumed after {}, next review {}",
+                             id,
+                             format_paused_for(paused_for),
+                             format_duration_until(store.reminders[id].next_review));
+                }
+            }
+        }
+
+        Commands::Reopen { id, due } => {
+            let id = id.resolve(&store)?;
+            let now = clock::now();
+            let when = match due {
+                Some(due) => dates::parse_when(&due, now)?,
+                None => {
+                    let reminder = store.reminders.get(&id).ok_or(Error::NotFound(id))?;
+                    now.checked_add_signed(Duration::days(reminder.interval.max(1) as i64))
+                        .ok_or_else(|| Error::Invalid(format!("Reminder {} can't be reopened {} days from now", id, reminder.interval)))?
+                }
+            };
+            store.reopen_reminder(id, when)?;
+            save_store(storage.as_mut(), &store, &original)?;
+            let reminder = &store.reminders[&id];
+            if json {
+                print_json(reminder);
+            } else {
+                println!("Reminder {} reopened, next review {}", id, format_duration_until(reminder.next_review));
+            }
+        }
+
+        Commands::Reset { id, due, keep_history } => {
+            let id = id.resolve(&store)?;
+            let when = dates::parse_when(due.as_deref().unwrap_or("tomorrow"), clock::now())?;
+            store.reset_reminder(id, when, keep_history, &config)?;
+            save_store(storage.as_mut(), &store, &original)?;
+            let reminder = &store.reminders[&id];
+            if json {
+                print_json(reminder);
+            } else {
+                println!("Reminder {} starts over, next review {}", id, format_duration_until(reminder.next_review));
+            }
+        }
+
+        Commands::Leeches { suspend } => {
+            let mut ids: Vec<u32> = store.get_all_reminders().into_iter()
+                .filter(|r| r.is_leech() && !r.completed)
+                .map(|r| r.id)
+                .collect();
+            ids.sort_by_key(|id| (Reverse(store.reminders[id].lapses), *id));
+
+            let mut suspended = 0;
+            if suspend {
+                for &id in &ids {
+                    if store.reminders[&id].paused_at.is_none() {
+                        store.pause_reminder(id)?;
+                        suspended += 1;
+                    }
+                }
+                if suspended > 0 {
+                    save_store(storage.as_mut(), &store, &original)?;
+                }
+            }
+
+            let leeches: Vec<&Reminder> = ids.iter().map(|id| &store.reminders[id]).collect();
+            if json {
+                print_json(&leeches);
+            } else if leeches.is_empty() {
+                println!("No leeches, nothing keeps being forgotten");
+            } else {
+                println!("Leeches ({} lapses or more flag a reminder):", config.leech_threshold);
+                println!("{}", "=".repeat(50));
+                for reminder in &leeches {
+                    let paused = if reminder.paused_at.is_some() { " | ⏸ Paused" } else { "" };
+                    println!("ID: {} | Lapses: {}{} | {}",
+                             style::id(reminder.id),
+                             reminder.lapses,
+                             paused,
+                             get_trimmed_content(&reminder.content, trim_opt));
+                }
+                if suspend {
+                    println!("{} leech(es) paused, use 'reminder resume' to bring them back", suspended);
+                }
+            }
+        }
+
+        Commands::Vacation { list: true, .. } => {
+            if json {
+                print_json(&store.vacations);
+            } else if store.vacations.is_empty() {
+                println!("No vacations recorded");
+            } else {
+                for vacation in &store.vacations {
+                    println!("{} to {}: {} review(s) shifted (recorded {})",
+                             dates::local(vacation.from).format("%Y-%m-%d %H:%M"),
+                             dates::local(vacation.to).format("%Y-%m-%d %H:%M"),
+                             vacation.shifted,
+                             dates::local(vacation.recorded_at).format("%Y-%m-%d %H:%M"));
+                }
+            }
+        }
+
+        Commands::Vacation { from, to, .. } => {
+            let now = clock::now();
+            let from = from.map(|from| dates::parse_when(&from, now)).transpose()?.unwrap_or(now);
+            // clap requires --to unless listing
+            let to = dates::parse_when(&to.unwrap_or_default(), now)?;
+            let vacation = store.take_vacation(from, to)?.clone();
+            save_store(storage.as_mut(), &store, &original)?;
+
+            if json {
+                print_json(&vacation);
+            } else {
+                println!("Moved {} review(s) scheduled from {} to {} to after the vacation",
+                         vacation.shifted,
+                         dates::local(vacation.from).format("%Y-%m-%d %H:%M"),
+                         dates::local(vacation.to).format("%Y-%m-%d %H:%M"));
+            }
+        }
+
+        Commands::Archive { ids, selectors } => {
+            let ids = select::resolve(&store, &ids, &selectors, false)?;
+            for &id in &ids {
+                store.archive_reminder(id)?;
+            }
+            save_store(storage.as_mut(), &store, &original)?;
+
+            if json {
+                let archived: Vec<_> = ids.iter().map(|id| serde_json::json!({ "id": id, "archived": true })).collect();
+                print_json_selection(&archived);
+            } else {
+                for id in ids {
+                    println!("Reminder {} archived, use 'reminder restore {}' to bring it back", id, id);
+                }
+            }
+        }
+
+        Commands::Tag { tag, ids, selectors, remove } => {
+            let ids = select::resolve(&store, &ids, &selectors, false)?;
+            let mut changed = 0;
+            for &id in &ids {
+                let did_change = if remove { store.remove_tag(id, &tag)? } else { store.add_tag(id, &tag)? };
+                changed += did_change as usize;
+            }
+            save_store(storage.as_mut(), &store, &original)?;
+
+            if json {
+                print_json(&serde_json::json!({ "tag": tag, "selected": ids, "changed": changed }));
+            } else if remove {
+                println!("Removed tag '{}' from {} of {} reminder(s)", tag, changed, ids.len());
+            } else {
+                println!("Added tag '{}' to {} of {} reminder(s)", tag, changed, ids.len());
+            }
+        }
+
+        Commands::Link { id, other, remove } => {
+            let id = id.resolve(&store)?;
+            let other = other.resolve(&store)?;
+            let changed = if remove { store.unlink(id, other)? } else { store.link(id, other)? };
+            if changed {
+                save_store(storage.as_mut(), &store, &original)?;
+            }
+
+            if json {
+                print_json(&serde_json::json!({ "id": id, "other": other, "linked": !remove, "changed": changed }));
+            } else if remove && changed {
+                println!("Reminders {} and {} are no longer linked", id, other);
+            } else if remove {
+                println!("Reminders {} and {} weren't linked", id, other);
+            } else if changed {
+                println!("Linked reminders {} and {}", id, other);
+            } else {
+                println!("Reminders {} and {} are already linked", id, other);
+            }
+        }
+
+        Commands::Depend { id, on, reviews, remove } => {
+            let id = id.resolve(&store)?;
+            let on = on.resolve(&store)?;
+            let changed = if remove { store.undepend(id, on)? } else { store.depend(id, on, reviews)? };
+            if changed {
+                save_store(storage.as_mut(), &store, &original)?;
+            }
+
+            let until = match reviews {
+                Some(reviews) => format!("has been reviewed {} time(s) or completed", reviews),
+                None => "has been completed".to_string(),
+            };
+            if json {
+                print_json(&serde_json::json!({
+                    "id": id, "on": on, "reviews": reviews, "depends": !remove, "changed": changed,
+                    "blocked": store.is_blocked(id),
+                }));
+            } else if remove && changed {
+                println!("Reminder {} no longer waits for {}", id, on);
+            } else if remove {
+                println!("Reminder {} didn't wait for {}", id, on);
+            } else if changed {
+                println!("Reminder {} won't come due until {} {}", id, on, until);
+            } else {
+                println!("Reminder {} already waits until {} {}", id, on, until);
+            }
+        }
+
+        Commands::Related { id, depth } => {
+            let id = id.resolve(&store)?;
+            let reminder = store.find_reminder(id).ok_or(Error::NotFound(id))?;
+            let related: Vec<(&Reminder, usize)> = store.related(id, depth).into_iter()
+                .filter_map(|(other, distance)| store.find_reminder(other).map(|r| (r, distance)))
+                .collect();
+
+            if json {
+                let related: Vec<_> = related.iter()
+                    .map(|(reminder, distance)| serde_json::json!({ "distance": distance, "reminder": reminder }))
+                    .collect();
+                print_json(&related);
+            } else if related.is_empty() {
+                println!("Reminder {} isn't linked to any other; use 'reminder link {} <ID>' to link one", id, id);
+            } else {
+                println!("Related to {} {}:", style::id(id), graph::label(reminder, trim_opt));
+                for (other, distance) in related {
+                    let distance = if depth > 1 { format!(" ({} step(s) away)", distance) } else { String::new() };
+                    println!("  {} {}{}", style::id(other.id), graph::label(other, trim_opt), distance);
+                }
+            }
+        }
+
+        Commands::Restore { target, keep_config } if backup_path(&target).is_some() => {
+            let path = backup_path(&target).expect("Checked by the guard");
+            let backup = backup::read(&path)?;
+            let restored = backup.restore_files(keep_config)?;
+            save_store(storage.as_mut(), &backup.store, &original)?;
+
+            if json {
+                print_json(&serde_json::json!({ "manifest": backup.manifest, "restored": restored }));
+            } else {
+                println!("Restored {} reminder(s) from the backup of {}", backup.manifest.reminders,
+                         dates::local(backup.manifest.created_at).format("%Y-%m-%d %H:%M"));
+                let mut parts = vec![format!("{} attachment(s)", restored.attachments)];
+                if restored.config {
+                    parts.push("the config file".to_string());
+                }
+                if restored.audit_log {
+                    parts.push("the audit log".to_string());
+                }
+                println!("Also restored {}", parts.join(", "));
+                if !original.reminders.is_empty() || !original.archived.is_empty() {
+                    println!("'reminder undo' brings back the reminders it replaced");
+                }
+            }
+        }
+
+        Commands::Restore { target, .. } => {
+            let id = target.parse::<ReminderRef>().map_err(Error::Invalid)?.resolve(&store)?;
+            store.restore_reminder(id)?;
+            save_store(storage.as_mut(), &store, &original)?;
+            let reminder = &store.reminders[&id];
+            if json {
+                print_json(reminder);
+            } else {
+                println!("Reminder {} restored", id);
+                if !reminder.completed {
+                    println!("Next review: {}", format_duration_until(reminder.next_review));
+                }
+            }
+        }
+
+        Commands::Trash { command } => match command {
+            TrashCommand::List => {
+                let trashed = store.get_trashed_reminders();
+                if json {
+                    print_json(&trashed);
+                } else if trashed.is_empty() {
+                    println!("The trash is empty");
+                } else {
+                    for entry in trashed {
+                        let deleted = entry.removed_at.checked_add_signed(Duration::days(config.trash_days as i64))
+                            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                        println!(
+                            "ID: {} | removed {} | deleted for good {}",
+                            style::id(entry.reminder.id),
+                            dates::local(entry.removed_at).format("%Y-%m-%d %H:%M"),
+                            format_duration_until(deleted)
+                        );
+                        println!("Content: {}", format.apply(&entry.reminder.content));
+                        println!("{}", "-".repeat(50));
+                    }
+                }
+            }
+            TrashCommand::Restore { id } => {
+                let id = id.resolve_trashed(&store)?;
+                let restored = store.restore_from_trash(id)?;
+                save_store(storage.as_mut(), &store, &original)?;
+                let reminder = store.find_reminder(restored).ok_or(Error::NotFound(restored))?;
+                if json {
+                    print_json(reminder);
+                } else {
+                    if restored == id {
+                        println!("Reminder {} restored from the trash", id);
+                    } else {
+                        println!("Reminder {} restored from the trash as reminder {}, its ID is taken", id, restored);
+                    }
+                    if store.archived.contains_key(&restored) {
+                        println!("It was archived, use 'reminder restore {}' to bring it into rotation", restored);
+                    } else if !reminder.completed {
+                        println!("Next review: {}", format_duration_until(reminder.next_review));
+                    }
+                }
+            }
+            TrashCommand::Empty => {
+                let count = store.empty_trash();
+                save_store(storage.as_mut(), &store, &original)?;
+                if json {
+                    print_json(&serde_json::json!({ "deleted": count }));
+                } else {
+                    println!("Deleted {} reminder(s) from the trash for good", count);
+                }
+            }
+        },
+
+        Commands::Undo => {
+            let entry = undo::pop()?;
+            audit::record(&store, &entry.store, &undo::current_command())?;
+            storage.save(&entry.store)?;
+            if json {
+                print_json(&serde_json::json!({
+                    "undone": entry.command,
+                    "timestamp": entry.timestamp,
+                }));
+            } else {
+                println!("Undid: {} ({})", entry.command, dates::local(entry.timestamp).format("%Y-%m-%d %H:%M"));
+            }
+        }
+
+        Commands::Log { id, since } => {
+            let now = clock::now();
+            let since = since.map(|since| dates::parse_since(&since, now)).transpose()?;
+            let id = id.map(|id| id.resolve_any(&store)).transpose()?;
+            let entries: Vec<audit::AuditEntry> = audit::read(since)?
+                .into_iter()
+                .filter(|entry| id.is_none_or(|id| entry.touches(id)))
+                .collect();
+            let _pager = pager::Pager::start(paging);
+
+            if json {
+                print_json(&entries);
+            } else if entries.is_empty() {
+                println!("No changes logged");
+            } else {
+                for entry in &entries {
+                    let label = |id: u32| match entry.contents.get(&id) {
+                        Some(content) => format!("{} \"{}\"", id, get_trimmed_content(content, Some(40))),
+                        None => id.to_string(),
+                    };
+                    println!("{}  {}", dates::local(entry.timestamp).format("%Y-%m-%d %H:%M:%S"), entry.command);
+                    for line in describe_changes(&entry.changes, &label) {
+                        println!("  {}", line);
+                    }
+                }
+            }
+        }
+
+        Commands::Repair { from, list } => {
+            if list {
+                let backups = storage.backups();
+                if json {
+                    print_json(&backups);
+                } else if backups.is_empty() {
+                    println!("No backups found!");
+                } else {
+                    for (index, path) in backups.iter().enumerate() {
+                        let modified = fs::metadata(path)
+                            .and_then(|metadata| metadata.modified())
+                            .map(|time| dates::local(DateTime::<Utc>::from(time)).format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default();
+                        println!("{}: {} ({})", index + 1, path.display(), modified);
+                    }
+                }
+            } else if let Some((path, recovered)) = from.map_or_else(|| storage.recover(), |_| Ok(None))?
+                // With nothing recovered, the newest readable backup is better
+                .filter(|(_, recovered)| {
+                    !recovered.reminders.is_empty() || !recovered.archived.is_empty() || storage.backups().is_empty()
+                })
+            {
+                save_store(storage.as_mut(), &recovered, &original)?;
+                if json {
+                    print_json(&serde_json::json!({
+                        "recovered": path,
+                        "reminders": recovered.reminders.len(),
+                        "archived": recovered.archived.len(),
+                        "trashed": recovered.trash.len(),
+                    }));
+                } else {
+                    println!(
+                        "Recovered {} reminder(s), {} archived and {} in the trash, from {}",
+                        recovered.reminders.len(),
+                        recovered.archived.len(),
+                        recovered.trash.len(),
+                        path.display()
+                    );
+                    println!("The file is left there; 'reminder repair --list' shows the backups to restore instead");
+                }
+            } else {
+                let restored = storage.restore_backup(from)?;
+                if restored.is_some() {
+                    undo::record(&original, &undo::current_command())?;
+                    audit::record(&original, &storage.load()?, &undo::current_command())?;
+                }
+                if json {
+                    print_json(&serde_json::json!({ "restored": restored }));
+                } else {
+                    match restored {
+                        Some(number) => println!("Restored the reminder store from backup {}", number),
+                        None => println!("The reminder store is readable, nothing to repair \
+                                          (use --from N to restore a backup anyway)"),
+                    }
+                }
+            }
+        }
+
+        Commands::Session { deck, no_limit } => {
+            session::run(&mut store, storage.as_mut(), &config, deck.as_deref(), no_limit, format)?;
+        }
+
+        Commands::Tui => tui::run(&mut store, storage.as_mut(), &config)?,
+
+        Commands::Deck { command } => match command {
+            DeckCommand::List => {
+                let counts = store.get_deck_counts();
+
+                if json {
+                    print_json(&counts);
+                } else if counts.is_empty() {
+                    println!("No decks found!");
+                } else {
+                    for (deck, count) in counts {
+                        println!("{} ({})", deck, count);
+                    }
+                }
+            }
+            DeckCommand::Rename { old, new } => {
+                let count = store.rename_deck(&old, &new)?;
+                save_store(storage.as_mut(), &store, &original)?;
+                if json {
+                    print_json(&serde_json::json!({ "renamed": count }));
+                } else {
+                    println!("Renamed deck '{}' to '{}' ({} reminder(s))", old, new.trim(), count);
+                }
+            }
+            DeckCommand::Remove { name, with_reminders } => {
+                let count = store.remove_deck(&name, with_reminders)?;
+                save_store(storage.as_mut(), &store, &original)?;
+                if json {
+                    print_json(&serde_json::json!({ "removed": count }));
+                } else if with_reminders {
+                    println!("Removed deck '{}' and moved its {} reminder(s) to the trash", name, count);
+                } else {
+                    println!("Removed deck '{}', {} reminder(s) are no longer in a deck", name, count);
+                }
+            }
+        },
+
+        Commands::Daemon { interval } => {
+            let interval = polling_interval(interval, &config)?;
+            // The daemon runs forever, so it only takes the lock while loading
+            drop(lock);
+            daemon::run(storage.as_mut(), interval, &notify::notifiers(&config, true));
+        }
+
+        Commands::NotifyTest { no_desktop, priority } => {
+            let notifiers = notify::notifiers(&config, !no_desktop);
+            if notifiers.is_empty() {
+                return Err(Error::Invalid(
+                    "No notifiers are configured; see [[webhooks]], [ntfy], [pushover], [gotify] and [mqtt] in the README".to_string(),
+                ));
+            }
+            // Made up in a store of its own, so nothing is saved
+            let mut scratch = ReminderStore::default();
+            let content = "This is a test notification from reminder".to_string();
+            let id = scratch.add_reminder(NewReminder { content, priority, ..NewReminder::default() }, &config);
+            let reminder = &scratch.reminders[&id];
+
+            let results: Vec<(String, Result<()>)> = notifiers.iter()
+                .map(|notifier| (notifier.name(), notifier.notify_many(&[reminder])))
+                .collect();
+            let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+            if json {
+                let results: Vec<_> = results.iter()
+                    .map(|(name, result)| serde_json::json!({
+                        "notifier": name,
+                        "error": result.as_ref().err().map(|e| e.to_string()),
+                    }))
+                    .collect();
+                print_json(&results);
+            } else {
+                for (name, result) in &results {
+                    match result {
+                        Ok(()) => println!("{} {}", style::added("✓"), name),
+                        Err(e) => println!("{} {}: {}", style::removed("✗"), name, e),
+                    }
+                }
+            }
+            if failed > 0 {
+                return Err(Error::Notify(format!("{} of {} notifier(s) failed", failed, results.len())));
+            }
+        }
+
+        Commands::Watch { interval, tags, deck, no_beep } => {
+            if interval < Duration::seconds(1) {
+                return Err(Error::Invalid("Watch interval must be at least one second".to_string()));
+            }
+            // Like the daemon, only take the lock while loading
+            drop(lock);
+            let options = watch::WatchOptions { interval, tags: &tags, deck: deck.as_deref(), trim: trim_opt, beep: !no_beep };
+            watch::run(storage.as_mut(), &options);
+        }
+
+        Commands::Telegram { interval } => {
+            let interval = polling_interval(interval, &config)?;
+            drop(lock);
+            telegram::run(storage.as_mut(), &config, interval)?;
+        }
+
+        Commands::Serve { port, bind } => {
+            // Each request takes the lock on its own
+            drop(lock);
+            server::run(storage.as_mut(), &config, &format!("{}:{}", bind, port))?;
+        }
+
+        Commands::Encrypt => {
+            if storage_kind != StorageKind::Json {
+                return Err(Error::Invalid("Encryption is only supported by the JSON storage backend".to_string()));
+            }
+            if crypto::is_encrypting() {
+                return Err(Error::Invalid("The store is already encrypted".to_string()));
+            }
+            crypto::set_passphrase(crypto::new_passphrase()?);
+            set_encryption(storage.as_mut(), &store, true)?;
+            println!("Encrypted the store, its backups, the undo history and the audit log");
+        }
+
+        Commands::Decrypt => {
+            if !crypto::is_encrypting() {
+                return Err(Error::Invalid("The store is not encrypted".to_string()));
+            }
+            set_encryption(storage.as_mut(), &store, false)?;
+            println!("Decrypted the store, its backups, the undo history and the audit log");
+        }
+
+        Commands::Sync => {
+            let sync_config = config.sync.clone().ok_or_else(|| Error::Invalid(
+                "No sync remote configured; add a [sync] table with a remote to config.toml".to_string()
+            ))?;
+            if storage_kind != StorageKind::Json || storage::has_custom_data_file() {
+                return Err(Error::Invalid(
+                    "Sync only supports the JSON store in the data directory, without --data-file".to_string()
+                ));
+            }
+
+            let summary = sync::run(storage.as_mut(), &mut store, &storage::get_data_dir(), &sync_config)?;
+            if !matches!(summary.pulled, sync::Pulled::Nothing) {
+                undo::record(&original, &undo::current_command())?;
+                audit::record(&original, &store, &undo::current_command())?;
+            }
+            if json {
+                print_json(&summary);
+            } else {
+                if summary.committed {
+                    println!("Committed local changes");
+                }
+                match &summary.pulled {
+                    sync::Pulled::Nothing => println!("No new changes on {}", sync_config.remote),
+                    sync::Pulled::FastForward => println!("Pulled changes from {}", sync_config.remote),
+                    sync::Pulled::Merged(merge) => println!(
+                        "Merged changes from {}: {} reminder(s) added, {} updated",
+                        sync_config.remote, merge.added, merge.updated
+                    ),
+                }
+                if summary.pushed {
+                    println!("Pushed to {} ({})", sync_config.remote, sync_config.branch);
+                }
+            }
+        }
+
+        Commands::Merge { other_file } => {
+            let other = storage::read_json_store(&other_file)?;
+            let summary = merge::merge_stores(&mut store, &other);
+            if summary.changed() {
+                save_store(storage.as_mut(), &store, &original)?;
+            }
+            if json {
+                print_json(&summary);
+            } else {
+                println!(
+                    "Merged {}: {} reminder(s) added ({} under a new ID), {} updated, {} duplicate(s) combined, {} link(s) and {} prerequisite(s) added",
+                    other_file.display(), summary.added, summary.renumbered, summary.updated, summary.deduplicated,
+                    summary.linked, summary.dependencies
+                );
+            }
+        }
+
+        Commands::Import { source: ImportSource::Lines { file, tags } } => {
+            let summary = import::import_lines(&mut store, &file, &tags, &config)?;
+            save_store(storage.as_mut(), &store, &original)?;
+
+            if json {
+                print_json(&summary);
+            } else {
+                let skipped = match summary.skipped {
+                    0 => String::new(),
+                    skipped => format!(", skipped {} imported before", skipped),
+                };
+                let from = if file == Path::new("-") { "standard input".to_string() } else { file.display().to_string() };
+                println!("Imported {} reminder(s) from {}{}", summary.added.len(), from, skipped);
+                for id in &summary.added {
+                    println!("  {} {}", style::id(id), graph::label(&store.reminders[id], trim_opt));
+                }
+            }
+        }
+
+        Commands::Import { source } => {
+            let count = match source {
+                ImportSource::Anki { file, keep_schedule } => {
+                    import::import_anki(&mut store, &file, keep_schedule, &config)?
+                }
+                ImportSource::Csv { file } => import::import_csv(&mut store, &file, &config)?,
+                ImportSource::Markdown { dir, tag_from_folder, marker } => {
+                    import::import_markdown(&mut store, &dir, &marker, tag_from_folder, &config)?
+                }
+                ImportSource::Org { file } => import::import_org(&mut store, &file, &config)?,
+                ImportSource::Lines { .. } => unreachable!("Handled above"),
+            };
+
+            save_store(storage.as_mut(), &store, &original)?;
+            if json {
+                print_json(&serde_json::json!({ "imported": count }));
+            } else {
+                println!("Imported {} reminder(s)", count);
+            }
+        }
+
+        Commands::Backup { file } => {
+            let manifest = backup::create(&store, &file)?;
+            if json {
+                print_json(&serde_json::json!({ "path": file, "manifest": manifest }));
+            } else {
+                println!("Backed up {} reminder(s) and {} attachment(s) to {}", manifest.reminders, manifest.attachments(), file.display());
+            }
+        }
+
+        Commands::ExportAll { format, path } => {
+            let reminders = store.get_all_reminders();
+            let count = export::export_all(&reminders, format, &path)?;
+            if json {
+                print_json(&serde_json::json!({ "exported": count, "path": path }));
+            } else {
+                println!("Exported {} reminder(s) to {}", count, path.display());
+            }
+        }
+
+        Commands::ExportCalendar { path, alarm } => {
+            let reminders = store.get_all_reminders();
+            let count = export::export_calendar(&reminders, &path, alarm)?;
+            if json {
+                print_json(&serde_json::json!({ "exported": count, "path": path }));
+            } else {
+                println!("Exported {} upcoming review(s) to {}", count, path.display());
+            }
+        }
+
+        Commands::Completions { .. } | Commands::Config { .. } | Commands::Profile { .. } | Commands::InstallSchedule { .. } => unreachable!("Handled before the store is loaded"),
+
+        Commands::Export {id, path} => {
+            let id = id.resolve(&store)?;
+            store.export_to_file(id, &path)?;
+            if json {
+                print_json(&serde_json::json!({ "id": id, "path": path }));
+            } else {
+                println!("Reminder {} export successfully", id);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+// src/markdown.rs
+use reminder::get_trimmed_content;
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const ITALIC: &str = "\x1b[3m";
+const UNDERLINE: &str = "\x1b[4m";
+const CODE: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// How reminder text is shown on the terminal.
+#[derive(Clone, Copy, Default)]
+pub struct ContentFormat {
+    /// Trim text to this many characters.
+    pub trim: Option<usize>,
+    /// Render Markdown markup instead of printing it raw.
+    pub markdown: bool,
+    /// Use terminal escape codes when rendering Markdown; without them the
+    /// markup is still removed.
+    pub styled: bool,
+}
+
+impl ContentFormat {
+    pub fn apply(&self, text: &str) -> String {
+        let text = get_trimmed_content(text, self.trim);
+        if self.markdown {
+            render(&text, self.styled)
+        } else {
+            text
+        }
+    }
+}
+
+/// Render the commonly used parts of Markdown: headings, emphasis, inline
+/// code, fenced code blocks, lists, block quotes and links.
+pub fn render(text: &str, styled: bool) -> String {
+    let style = |codes: &str, text: &str| {
+        if styled { format!("{}{}{}", codes, text, RESET) } else { text.to_string() }
+    };
+
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(format!("    {}", style(CODE, line)));
+            continue;
+        }
+
+        if let Some((level, heading)) = parse_heading(trimmed) {
+            let codes = if level == 1 { format!("{}{}", BOLD, UNDERLINE) } else { BOLD.to_string() };
+            lines.push(style(&codes, &render_inline(heading, false)));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            lines.push(style(DIM, &format!("│ {}", render_inline(quote.trim_start(), false))));
+        } else if is_rule(trimmed) {
+            lines.push(style(DIM, &"─".repeat(30)));
+        } else if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|bullet| trimmed.strip_prefix(bullet)) {
+            lines.push(format!("{}  • {}", indent, render_inline(item, styled)));
+        } else if let Some((number, item)) = parse_ordered_item(trimmed) {
+            lines.push(format!("{}  {}. {}", indent, number, render_inline(item, styled)));
+        } else {
+            lines.push(format!("{}{}", indent, render_inline(trimmed, styled)));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// `## Title` gives `(2, "Title")`.
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let heading = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then(|| (level, heading.trim_end_matches('#').trim()))
+}
+
+/// `3. Third` gives `("3", "Third")`.
+fn parse_ordered_item(line: &str) -> Option<(&str, &str)> {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    let item = line[digits..].strip_prefix(". ").or_else(|| line[digits..].strip_prefix(") "))?;
+    Some((&line[..digits], item))
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ["-", "*", "_"].iter().any(|marker| compact.chars().all(|c| c.to_string() == *marker))
+}
+
+/// Render emphasis, inline code and links within a line. Markers without a
+/// closing counterpart are printed as they are.
+fn render_inline(text: &str, styled: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut previous: Option<char> = None;
+
+    while let Some(c) = rest.chars().next() {
+        let span = match c {
+            // Underscores inside words such as snake_case are not emphasis
+            '_' if previous.is_some_and(char::is_alphanumeric) => None,
+            '`' => delimited(rest, "`").map(|(inner, len)| (CODE, inner.to_string(), len)),
+            '*' | '_' => {
+                let marker = if rest.starts_with("**") || rest.starts_with("__") { &rest[..2] } else { &rest[..1] };
+                let code = if marker.len() == 2 { BOLD } else { ITALIC };
+                delimited(rest, marker).map(|(inner, len)| (code, render_inline(inner, styled), len))
+            }
+            '[' => parse_link(rest).map(|(label, url, len)| {
+                let label = render_inline(label, styled);
+                if styled {
+                    (UNDERLINE, label, len)
+                } else {
+                    ("", format!("{} ({})", label, url), len)
+                }
+            }),
+            _ => None,
+        };
+
+        match span {
+            Some((code, inner, len)) => {
+                if styled && !code.is_empty() {
+                    out.push_str(code);
+                    out.push_str(&inner);
+                    out.push_str(RESET);
+                } else {
+                    out.push_str(&inner);
+                }
+                rest = &rest[len..];
+                previous = None;
+            }
+            None => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+                previous = Some(c);
+            }
+        }
+    }
+
+    out
+}
+
+/// When `text` starts with `marker`, the non-empty text up to the next
+/// `marker` and the length of the whole span including both markers.
+fn delimited<'a>(text: &'a str, marker: &str) -> Option<(&'a str, usize)> {
+    let inner = &text[marker.len()..];
+    let end = inner.find(marker)?;
+    (end > 0 && !inner.starts_with(' ')).then(|| (&inner[..end], end + 2 * marker.len()))
+}
+
+/// `[label](url)` gives the label, the URL and the length of the span.
+fn parse_link(text: &str) -> Option<(&str, &str, usize)> {
+    let label_end = text.find("](")?;
+    let url_len = text[label_end + 2..].find(')')?;
+    let label = &text[1..label_end];
+    let url = &text[label_end + 2..label_end + 2 + url_len];
+    Some((label, url, label_end + 2 + url_len + 1))
+}
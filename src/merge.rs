@@ -0,0 +1,167 @@
+// src/merge.rs
+//! Combining two copies of a store that were changed independently, e.g. on
+//! two machines.
+
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use uuid::Uuid;
+
+use crate::{Reminder, ReminderStore};
+
+/// What a merge changed in the local store.
+#[derive(Serialize, Debug, Default)]
+pub struct MergeSummary {
+    /// Reminders only the other store had.
+    pub added: usize,
+    /// Reminders both stores had, where the other copy was further along.
+    pub updated: usize,
+    /// Reminders the other store had under a different ID but with the same
+    /// content and answer as a local one, merged into that one.
+    pub deduplicated: usize,
+    /// Added reminders that got a new ID because the local store already
+    /// used theirs for a different reminder.
+    pub renumbered: usize,
+    /// Links between reminders only the other store had.
+    pub linked: usize,
+    /// Prerequisites only the other store had.
+    pub dependencies: usize,
+}
+
+impl MergeSummary {
+    /// Whether the local store changed; recognising a duplicate alone
+    /// doesn't change anything.
+    pub fn changed(&self) -> bool {
+        self.added > 0 || self.updated > 0 || self.linked > 0 || self.dependencies > 0
+    }
+}
+
+/// Hash of what a reminder asks and answers, to recognise the same reminder
+/// added on both sides.
+fn content_hash(reminder: &Reminder) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    reminder.content.trim().hash(&mut hasher);
+    reminder.answer.as_deref().map(str::trim).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether `a` is in a more advanced review state than `b`: the later last
+/// review wins, then the higher review count.
+fn is_further_along(a: &Reminder, b: &Reminder) -> bool {
+    (a.last_review, a.review_count, a.history.len()) > (b.last_review, b.review_count, b.history.len())
+}
+
+/// Merge `other` into `local`. The union of both stores' reminders is kept;
+/// a reminder is the same in both when it has the same UUID, the same ID and
+/// creation time, or otherwise the same content and answer, and the copy in the more
+/// advanced review state wins (staying archived or active as that copy is,
+/// under the local ID and UUID). Different reminders under the same ID are
+/// both kept, the other store's one under a new ID but its own UUID.
+///
+/// Removing a reminder in only one of the stores is not a change a merge can
+/// see, so such reminders come back from the other store.
+pub fn merge_stores(local: &mut ReminderStore, other: &ReminderStore) -> MergeSummary {
+    let mut summary = MergeSummary::default();
+
+    let mut incoming: Vec<(&Reminder, bool)> = other.reminders.values().map(|r| (r, false))
+        .chain(other.archived.values().map(|r| (r, true)))
+        .collect();
+    incoming.sort_by_key(|(r, _)| r.id);
+
+    let mut by_content: HashMap<u64, u32> = HashMap::new();
+    let mut by_uuid: HashMap<Uuid, u32> = HashMap::new();
+    let mut local_ids: Vec<u32> = local.reminders.keys().chain(local.archived.keys()).copied().collect();
+    local_ids.sort_unstable();
+    for id in local_ids.into_iter().rev() {
+        let reminder = local.reminders.get(&id).or_else(|| local.archived.get(&id)).expect("ID was just listed");
+        by_content.insert(content_hash(reminder), id);
+        by_uuid.insert(reminder.uuid(), id);
+    }
+
+    // Once all IDs of the other store are known, renumbered reminders can't
+    // collide with one of them that is added later
+    local.next_id = local.next_id.max(other.next_id);
+
+    // Where each of the other store's reminders ended up, to carry its links
+    let mut local_id: HashMap<u32, u32> = HashMap::new();
+
+    for (reminder, archived) in incoming {
+        let find = |id: u32| local.reminders.get(&id).or_else(|| local.archived.get(&id));
+        let same_uuid = by_uuid.get(&reminder.uuid()).and_then(|id| find(*id));
+        let same_id = || find(reminder.id).filter(|existing| existing.created_at == reminder.created_at);
+        let same_content = || by_content.get(&content_hash(reminder)).and_then(|id| find(*id));
+
+        match same_uuid.or_else(same_id).or_else(same_content) {
+            Some(existing) => {
+                let id = existing.id;
+                local_id.insert(reminder.id, id);
+                if id != reminder.id {
+                    summary.deduplicated += 1;
+                }
+                if is_further_along(reminder, existing) {
+                    let uuid = Some(existing.uuid());
+                    local.reminders.remove(&id);
+                    local.archived.remove(&id);
+                    insert(local, Reminder { id, uuid, ..reminder.clone() }, archived);
+                    summary.updated += 1;
+                }
+            }
+            None => {
+                let id = if find(reminder.id).is_some() {
+                    summary.renumbered += 1;
+                    local.next_id
+                } else {
+                    reminder.id
+                };
+                // A derived UUID would change with the ID
+                let uuid = Some(reminder.uuid());
+                by_content.insert(content_hash(reminder), id);
+                by_uuid.insert(reminder.uuid(), id);
+                local_id.insert(reminder.id, id);
+                insert(local, Reminder { id, uuid, ..reminder.clone() }, archived);
+                summary.added += 1;
+            }
+        }
+    }
+
+    // A day's activity may have been split between the stores
+    for (date, record) in &other.days {
+        let day = local.days.entry(*date).or_default();
+        day.reviews = day.reviews.max(record.reviews);
+        day.cleared |= record.cleared;
+    }
+    for vacation in &other.vacations {
+        if !local.vacations.contains(vacation) {
+            local.vacations.push(vacation.clone());
+        }
+    }
+    local.vacations.sort_by_key(|vacation| vacation.recorded_at);
+    for (a, b) in &other.links {
+        if let (Some(&a), Some(&b)) = (local_id.get(a), local_id.get(b)) {
+            if a != b && local.links.insert((a.min(b), a.max(b))) {
+                summary.linked += 1;
+            }
+        }
+    }
+    // Local prerequisites of the same pair win, and cycles are left out
+    for dependency in &other.dependencies {
+        if let (Some(&id), Some(&on)) = (local_id.get(&dependency.id), local_id.get(&dependency.on)) {
+            let known = local.dependencies.iter().any(|d| (d.id, d.on) == (id, on));
+            if !known && local.depend(id, on, dependency.reviews).unwrap_or(false) {
+                summary.dependencies += 1;
+            }
+        }
+    }
+
+    summary
+}
+
+fn insert(store: &mut ReminderStore, reminder: Reminder, archived: bool) {
+    store.next_id = store.next_id.max(reminder.id + 1);
+    if archived {
+        store.archived.insert(reminder.id, reminder);
+    } else {
+        store.reminders.insert(reminder.id, reminder);
+    }
+}
@@ -0,0 +1,159 @@
+// src/notify/chat.rs
+//! Slack and Discord messages summing up the reminders that became due
+//! together, kept within what each accepts of a message.
+
+use serde_json::{json, Value};
+
+use reminder::{dates, get_trimmed_content, Priority, Reminder};
+
+/// Slack takes up to 50 blocks, two of which are the header and the count
+/// of reminders left out.
+const SLACK_REMINDERS: usize = 48;
+
+/// Longest text of a Slack section block.
+const SLACK_SECTION_LENGTH: usize = 3000;
+
+/// Discord takes up to 10 embeds, with 6000 characters across them and
+/// 4096 in a description.
+const DISCORD_EMBEDS: usize = 10;
+const DISCORD_EMBEDS_LENGTH: usize = 6000;
+const DISCORD_DESCRIPTION_LENGTH: usize = 4096;
+
+/// Room left in an embed for its title and fields, which are kept short.
+const DISCORD_EMBED_OVERHEAD: usize = 250;
+
+/// Longest deck or tag list shown with a reminder.
+const DETAIL_LENGTH: usize = 100;
+
+/// The "..." trimmed content ends with.
+const ELLIPSIS_LENGTH: usize = 3;
+
+/// A Slack message listing `reminders`: the first `max_reminders` with up
+/// to `content_length` characters of their content, then how many more
+/// there are.
+pub fn slack(reminders: &[&Reminder], max_reminders: usize, content_length: usize) -> Value {
+    let shown = max_reminders.min(SLACK_REMINDERS).min(reminders.len());
+    let summary = summary(reminders.len());
+
+    let mut blocks = vec![json!({ "type": "header", "text": { "type": "plain_text", "text": summary } })];
+    for reminder in &reminders[..shown] {
+        let mut details = vec![slack_date(reminder)];
+        if let Some(deck) = &reminder.deck {
+            details.push(format!("deck {}", slack_escape(&get_trimmed_content(deck, Some(DETAIL_LENGTH)))));
+        }
+        if !reminder.tags.is_empty() {
+            details.push(slack_escape(&get_trimmed_content(&hashtags(reminder), Some(DETAIL_LENGTH))));
+        }
+        if reminder.priority != Priority::Normal {
+            details.push(format!("{} priority", reminder.priority));
+        }
+        let details = format!("\n_{}_", details.join(" · "));
+
+        let room = SLACK_SECTION_LENGTH - details.chars().count() - 20;
+        let text = format!("*{}* {}{}", reminder.id, slack_content(&reminder.content, content_length, room), details);
+        blocks.push(json!({ "type": "section", "text": { "type": "mrkdwn", "text": text } }));
+    }
+    if shown < reminders.len() {
+        blocks.push(json!({
+            "type": "context",
+            "elements": [{ "type": "mrkdwn", "text": remainder(reminders.len() - shown) }],
+        }));
+    }
+
+    // `text` is what notifications and clients without blocks show
+    json!({ "text": summary, "blocks": blocks })
+}
+
+/// A Discord message with an embed for each of the first `max_reminders`
+/// of `reminders`, up to `content_length` characters of their content in
+/// each, and how many more there are.
+pub fn discord(reminders: &[&Reminder], max_reminders: usize, content_length: usize) -> Value {
+    let shown = max_reminders.min(DISCORD_EMBEDS).min(reminders.len());
+    // Trimmed content ends with "...", which has to fit too
+    let description_length = content_length
+        .min(DISCORD_DESCRIPTION_LENGTH - ELLIPSIS_LENGTH)
+        .min(DISCORD_EMBEDS_LENGTH / shown.max(1) - DISCORD_EMBED_OVERHEAD - ELLIPSIS_LENGTH);
+
+    let embeds: Vec<Value> = reminders[..shown].iter()
+        .map(|reminder| {
+            let mut fields = Vec::new();
+            if let Some(deck) = &reminder.deck {
+                fields.push(json!({ "name": "Deck", "value": get_trimmed_content(deck, Some(DETAIL_LENGTH)), "inline": true }));
+            }
+            if !reminder.tags.is_empty() {
+                let tags = get_trimmed_content(&hashtags(reminder), Some(DETAIL_LENGTH));
+                fields.push(json!({ "name": "Tags", "value": tags, "inline": true }));
+            }
+            if reminder.priority != Priority::Normal {
+                fields.push(json!({ "name": "Priority", "value": reminder.priority.to_string(), "inline": true }));
+            }
+            json!({
+                "title": format!("Reminder {}", reminder.id),
+                "description": get_trimmed_content(&reminder.content, Some(description_length)),
+                "color": discord_color(reminder.priority),
+                "timestamp": reminder.next_review.to_rfc3339(),
+                "fields": fields,
+            })
+        })
+        .collect();
+
+    let mut content = summary(reminders.len());
+    if shown < reminders.len() {
+        content = format!("{}, {}", content, remainder(reminders.len() - shown));
+    }
+    json!({
+        "content": content,
+        "embeds": embeds,
+        // Content such as "@everyone" is shown, not acted on
+        "allowed_mentions": { "parse": [] },
+    })
+}
+
+fn summary(count: usize) -> String {
+    match count {
+        1 => "1 reminder is due".to_string(),
+        count => format!("{} reminders are due", count),
+    }
+}
+
+fn remainder(hidden: usize) -> String {
+    format!("and {} more not shown", hidden)
+}
+
+fn hashtags(reminder: &Reminder) -> String {
+    reminder.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ")
+}
+
+/// The due time, shown by Slack in the reader's own time zone.
+fn slack_date(reminder: &Reminder) -> String {
+    let fallback = dates::local(reminder.next_review).format("%Y-%m-%d %H:%M");
+    format!("due <!date^{}^{{date_short_pretty}} {{time}}|{}>", reminder.next_review.timestamp(), fallback)
+}
+
+/// Up to `length` characters of `content`, escaped, and fewer when the
+/// escapes would take it past `room`.
+fn slack_content(content: &str, length: usize, room: usize) -> String {
+    let mut length = length;
+    loop {
+        let escaped = slack_escape(&get_trimmed_content(content, Some(length)));
+        let count = escaped.chars().count();
+        if count <= room {
+            return escaped;
+        }
+        length = length * room / count;
+    }
+}
+
+/// Escape the characters Slack's mrkdwn reads as links and mentions.
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn discord_color(priority: Priority) -> u32 {
+    match priority {
+        Priority::Low => 0x95a5a6,
+        Priority::Normal => 0x3498db,
+        Priority::High => 0xe67e22,
+        Priority::Urgent => 0xe74c3c,
+    }
+}
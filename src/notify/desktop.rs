@@ -0,0 +1,28 @@
+// src/notify/desktop.rs
+use notify_rust::Notification;
+
+use reminder::{get_trimmed_content, Error, Reminder, Result};
+
+use super::Notifier;
+
+/// Longest content shown in a notification body before it is trimmed.
+const NOTIFICATION_CONTENT_LENGTH: usize = 120;
+
+/// Native desktop notifications.
+pub struct Desktop;
+
+impl Notifier for Desktop {
+    fn name(&self) -> String {
+        "desktop".to_string()
+    }
+
+    fn notify(&self, reminder: &Reminder) -> Result<()> {
+        Notification::new()
+            .appname("reminder")
+            .summary(&format!("Reminder {} is due for review", reminder.id))
+            .body(&get_trimmed_content(&reminder.content, Some(NOTIFICATION_CONTENT_LENGTH)))
+            .show()
+            .map(|_| ())
+            .map_err(|e| Error::Notify(format!("Failed to show notification: {}", e)))
+    }
+}
@@ -0,0 +1,48 @@
+// src/notify/gotify.rs
+use reminder::config::GotifyConfig;
+use reminder::{Priority, Reminder, Result};
+
+use super::Notifier;
+
+/// Pushes each due reminder to a Gotify server, for the clients subscribed
+/// to its application.
+pub struct Gotify {
+    config: GotifyConfig,
+}
+
+impl Gotify {
+    pub fn new(config: GotifyConfig) -> Self {
+        Gotify { config }
+    }
+
+    fn url(&self) -> String {
+        format!("{}/message", self.config.url.trim_end_matches('/'))
+    }
+}
+
+/// Gotify's priorities run from 0 to 10; its Android app stays silent
+/// below 4 and is loudest from 8 up.
+fn gotify_priority(priority: Priority) -> u8 {
+    match priority {
+        Priority::Low => 2,
+        Priority::Normal => 5,
+        Priority::High => 8,
+        Priority::Urgent => 10,
+    }
+}
+
+impl Notifier for Gotify {
+    fn name(&self) -> String {
+        format!("Gotify {}", self.config.url)
+    }
+
+    fn notify(&self, reminder: &Reminder) -> Result<()> {
+        let body = serde_json::json!({
+            "title": format!("Reminder {} is due", reminder.id),
+            "message": reminder.content,
+            "priority": gotify_priority(reminder.priority),
+        });
+        let headers = [("X-Gotify-Key", self.config.token.as_str())];
+        super::post(&self.url(), "application/json", &headers, &body.to_string(), self.config.retries)
+    }
+}
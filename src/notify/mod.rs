@@ -0,0 +1,144 @@
+// src/notify/mod.rs
+mod chat;
+mod desktop;
+mod gotify;
+mod mqtt;
+mod ntfy;
+mod pushover;
+mod webhook;
+
+use std::thread;
+use std::time::Duration;
+
+use reminder::config::Config;
+use reminder::{Error, Reminder, Result};
+
+pub use desktop::Desktop;
+pub use gotify::Gotify;
+pub use mqtt::Mqtt;
+pub use ntfy::Ntfy;
+pub use pushover::Pushover;
+pub use webhook::Webhook;
+
+/// Longest a single HTTP request may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wait before the first retry of a failed delivery; doubled on each retry.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Somewhere to announce that a reminder has become due.
+pub trait Notifier {
+    /// Short description used in log and error messages.
+    fn name(&self) -> String;
+    fn notify(&self, reminder: &Reminder) -> Result<()>;
+
+    /// Whether `reminder` is announced here; notifiers routed to some decks
+    /// or tags leave the others out.
+    fn accepts(&self, _reminder: &Reminder) -> bool {
+        true
+    }
+
+    /// Announce `reminders`, which became due together. Each is notified on
+    /// its own unless the notifier sums them up in one message; the first
+    /// failure is returned once all have been tried.
+    fn notify_many(&self, reminders: &[&Reminder]) -> Result<()> {
+        notify_each(self, reminders)
+    }
+}
+
+/// Notify `reminders` one at a time through `notifier`, returning the first
+/// failure once all have been tried.
+fn notify_each<N: Notifier + ?Sized>(notifier: &N, reminders: &[&Reminder]) -> Result<()> {
+    let mut first_error = None;
+    for reminder in reminders {
+        if let Err(e) = notifier.notify(reminder) {
+            first_error.get_or_insert(e);
+        }
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// The notifiers configured for due reminders, optionally including desktop
+/// notifications.
+pub fn notifiers(config: &Config, desktop: bool) -> Vec<Box<dyn Notifier>> {
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+    if desktop {
+        notifiers.push(Box::new(Desktop));
+    }
+    for webhook in &config.webhooks {
+        notifiers.push(Box::new(Webhook::new(webhook.clone())));
+    }
+    if let Some(ntfy) = &config.ntfy {
+        notifiers.push(Box::new(Ntfy::new(ntfy.clone())));
+    }
+    if let Some(pushover) = &config.pushover {
+        notifiers.push(Box::new(Pushover::new(pushover.clone())));
+    }
+    if let Some(gotify) = &config.gotify {
+        notifiers.push(Box::new(Gotify::new(gotify.clone())));
+    }
+    if let Some(mqtt) = &config.mqtt {
+        notifiers.push(Box::new(Mqtt::new(mqtt.clone())));
+    }
+    notifiers
+}
+
+/// Announce `reminders` through every notifier, each getting the ones it
+/// accepts. A failing notifier is reported without keeping the others from
+/// running.
+pub fn notify_all(notifiers: &[Box<dyn Notifier>], reminders: &[&Reminder]) {
+    for notifier in notifiers {
+        let accepted: Vec<&Reminder> = reminders.iter().copied().filter(|reminder| notifier.accepts(reminder)).collect();
+        if accepted.is_empty() {
+            continue;
+        }
+        let ids: Vec<u32> = accepted.iter().map(|reminder| reminder.id).collect();
+        match notifier.notify_many(&accepted) {
+            Ok(()) => tracing::debug!(notifier = %notifier.name(), ids = ?ids, "notified"),
+            Err(e) => {
+                tracing::warn!(notifier = %notifier.name(), ids = ?ids, error = %e, "notification failed");
+                eprintln!("Error ({}): {}", notifier.name(), e);
+            }
+        }
+    }
+}
+
+/// POST `body` to `url`, retrying transient failures (network errors, rate
+/// limiting and server errors) up to `retries` times.
+fn post(url: &str, content_type: &str, headers: &[(&str, &str)], body: &str, retries: u32) -> Result<()> {
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(REQUEST_TIMEOUT))
+        .build()
+        .into();
+
+    with_retries(retries, || {
+        let mut request = agent.post(url).header("Content-Type", content_type);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.send(body).map(|_| ()).map_err(|e| {
+            let permanent = matches!(e, ureq::Error::StatusCode(code) if (400..500).contains(&code) && code != 429);
+            (Error::Notify(format!("Failed to POST to {}: {}", url, e)), permanent)
+        })
+    })
+}
+
+/// Run `attempt` until it succeeds, at most `retries` more times, waiting
+/// longer before each retry. Errors `attempt` marks as permanent (the
+/// `bool` in `Err`) are not retried.
+fn with_retries(retries: u32, mut attempt: impl FnMut() -> std::result::Result<(), (Error, bool)>) -> Result<()> {
+    let mut delay = RETRY_DELAY;
+    let mut tries_left = retries;
+    loop {
+        match attempt() {
+            Ok(()) => return Ok(()),
+            Err((e, permanent)) if permanent || tries_left == 0 => return Err(e),
+            Err((e, _)) => {
+                tracing::info!(error = %e, retry_in = ?delay, tries_left, "delivery failed, retrying");
+                thread::sleep(delay);
+                delay *= 2;
+                tries_left -= 1;
+            }
+        }
+    }
+}
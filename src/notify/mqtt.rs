@@ -0,0 +1,132 @@
+// src/notify/mqtt.rs
+use rumqttc::{Client, ConnectionError, Event, MqttOptions, Outgoing, Packet, QoS, RecvTimeoutError, Transport};
+
+use reminder::config::MqttConfig;
+use reminder::{get_trimmed_content, Error, Reminder, Result};
+
+use super::{Notifier, REQUEST_TIMEOUT};
+
+/// Longest content sent, keeping a message within the packet size.
+const MESSAGE_LENGTH: usize = 4000;
+
+/// Largest packet sent or taken from the broker.
+const MAX_PACKET_SIZE: usize = 64 * 1024;
+
+/// Publishes an event for each due reminder to an MQTT broker, for home
+/// automation to act on: to `<topic>/due`, and to a topic of its deck and
+/// one of each of its tags.
+pub struct Mqtt {
+    config: MqttConfig,
+}
+
+impl Mqtt {
+    pub fn new(config: MqttConfig) -> Self {
+        Mqtt { config }
+    }
+
+    fn options(&self) -> MqttOptions {
+        let config = &self.config;
+        let port = config.port.unwrap_or(if config.tls { 8883 } else { 1883 });
+        let client_id = config.client_id.clone().unwrap_or_else(|| format!("reminder-{}", std::process::id()));
+        let mut options = MqttOptions::new(client_id, &config.host, port);
+        options.set_max_packet_size(MAX_PACKET_SIZE, MAX_PACKET_SIZE);
+        if config.tls {
+            options.set_transport(Transport::tls_with_default_config());
+        }
+        if let Some(username) = &config.username {
+            options.set_credentials(username, config.password.as_deref().unwrap_or_default());
+        }
+        options
+    }
+
+    fn qos(&self) -> QoS {
+        match self.config.qos {
+            0 => QoS::AtMostOnce,
+            2 => QoS::ExactlyOnce,
+            _ => QoS::AtLeastOnce,
+        }
+    }
+
+    /// The topics and payload of the event for `reminder`.
+    fn messages(&self, reminder: &Reminder) -> Vec<(String, String)> {
+        let payload = serde_json::json!({
+            "id": reminder.id,
+            "content": get_trimmed_content(&reminder.content, Some(MESSAGE_LENGTH)),
+            "due": reminder.next_review.to_rfc3339(),
+            "tags": reminder.tags,
+            "deck": reminder.deck,
+            "priority": reminder.priority.to_string(),
+        }).to_string();
+
+        let mut topics = vec![format!("{}/due", self.config.topic)];
+        if let Some(deck) = &reminder.deck {
+            topics.push(format!("{}/deck/{}", self.config.topic, topic_level(deck)));
+        }
+        for tag in &reminder.tags {
+            topics.push(format!("{}/tag/{}", self.config.topic, topic_level(tag)));
+        }
+        topics.into_iter().map(|topic| (topic, payload.clone())).collect()
+    }
+
+    /// Connect, publish `messages` and disconnect once the broker has taken
+    /// them all as far as the QoS asks. Errors are marked permanent when the
+    /// broker refused the connection, as retrying won't change its mind.
+    fn publish(&self, messages: &[(String, String)]) -> std::result::Result<(), (Error, bool)> {
+        let failed = |e: String, permanent: bool| {
+            (Error::Notify(format!("Failed to publish to {}: {}", self.config.host, e)), permanent)
+        };
+        let (client, mut connection) = Client::new(self.options(), messages.len() + 1);
+        for (topic, payload) in messages {
+            client.publish(topic, self.qos(), self.config.retain, payload.as_bytes())
+                .map_err(|e| failed(e.to_string(), false))?;
+        }
+
+        let mut done = 0;
+        loop {
+            let event = match connection.recv_timeout(REQUEST_TIMEOUT) {
+                Ok(Ok(event)) => event,
+                Ok(Err(ConnectionError::ConnectionRefused(code))) => {
+                    return Err(failed(format!("the broker refused the connection ({:?})", code), true));
+                }
+                Ok(Err(e)) => return Err(failed(e.to_string(), false)),
+                Err(RecvTimeoutError::Timeout) => return Err(failed("the broker didn't answer in time".to_string(), false)),
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            };
+            let published = match (self.qos(), event) {
+                (QoS::AtMostOnce, Event::Outgoing(Outgoing::Publish(_))) => true,
+                (QoS::AtLeastOnce, Event::Incoming(Packet::PubAck(_))) => true,
+                (QoS::ExactlyOnce, Event::Incoming(Packet::PubComp(_))) => true,
+                (_, Event::Outgoing(Outgoing::Disconnect)) => return Ok(()),
+                _ => false,
+            };
+            if published {
+                done += 1;
+                if done == messages.len() {
+                    client.disconnect().map_err(|e| failed(e.to_string(), false))?;
+                }
+            }
+        }
+    }
+}
+
+/// `name` as a single topic level: MQTT reads `/` as a separator and `+`
+/// and `#` as wildcards.
+fn topic_level(name: &str) -> String {
+    name.replace(['/', '+', '#'], "_")
+}
+
+impl Notifier for Mqtt {
+    fn name(&self) -> String {
+        format!("MQTT {}", self.config.host)
+    }
+
+    fn notify(&self, reminder: &Reminder) -> Result<()> {
+        self.notify_many(&[reminder])
+    }
+
+    fn notify_many(&self, reminders: &[&Reminder]) -> Result<()> {
+        // The events of all of them over one connection
+        let messages: Vec<(String, String)> = reminders.iter().flat_map(|reminder| self.messages(reminder)).collect();
+        super::with_retries(self.config.retries, || self.publish(&messages))
+    }
+}
@@ -0,0 +1,64 @@
+// src/notify/ntfy.rs
+use base64::Engine;
+
+use reminder::config::NtfyConfig;
+use reminder::{get_trimmed_content, Priority, Reminder, Result};
+
+use super::Notifier;
+
+/// Longest content sent; ntfy turns longer messages into attachments.
+const MESSAGE_LENGTH: usize = 4000;
+
+/// Publishes each due reminder to an ntfy topic, for push notifications on
+/// phones subscribed to it.
+pub struct Ntfy {
+    config: NtfyConfig,
+}
+
+impl Ntfy {
+    pub fn new(config: NtfyConfig) -> Self {
+        Ntfy { config }
+    }
+
+    fn url(&self) -> String {
+        format!("{}/{}", self.config.server.trim_end_matches('/'), self.config.topic)
+    }
+
+    fn authorization(&self) -> Option<String> {
+        match (&self.config.token, &self.config.username) {
+            (Some(token), _) => Some(format!("Bearer {}", token)),
+            (None, Some(username)) => {
+                let credentials = format!("{}:{}", username, self.config.password.as_deref().unwrap_or_default());
+                Some(format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(credentials)))
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+/// ntfy's priorities run from 1 (min) to 5 (max), 3 being the default.
+fn ntfy_priority(priority: Priority) -> &'static str {
+    match priority {
+        Priority::Low => "2",
+        Priority::Normal => "3",
+        Priority::High => "4",
+        Priority::Urgent => "5",
+    }
+}
+
+impl Notifier for Ntfy {
+    fn name(&self) -> String {
+        format!("ntfy {}", self.url())
+    }
+
+    fn notify(&self, reminder: &Reminder) -> Result<()> {
+        let title = format!("Reminder {} is due", reminder.id);
+        let authorization = self.authorization();
+        let mut headers = vec![("Title", title.as_str()), ("Priority", ntfy_priority(reminder.priority))];
+        if let Some(authorization) = &authorization {
+            headers.push(("Authorization", authorization));
+        }
+        let message = get_trimmed_content(&reminder.content, Some(MESSAGE_LENGTH));
+        super::post(&self.url(), "text/plain; charset=utf-8", &headers, &message, self.config.retries)
+    }
+}
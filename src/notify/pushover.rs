@@ -0,0 +1,74 @@
+// src/notify/pushover.rs
+use reminder::config::PushoverConfig;
+use reminder::{get_trimmed_content, Priority, Reminder, Result};
+
+use super::Notifier;
+
+const MESSAGES_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// Longest message Pushover accepts, in characters.
+const MESSAGE_LENGTH: usize = 1024;
+
+/// The "..." trimmed content ends with.
+const ELLIPSIS_LENGTH: usize = 3;
+
+/// How often, in seconds, Pushover repeats an urgent reminder until it is
+/// acknowledged, and for how long at most.
+const EMERGENCY_RETRY: u32 = 300;
+const EMERGENCY_EXPIRE: u32 = 3600;
+
+/// Pushes each due reminder to a Pushover user's devices.
+pub struct Pushover {
+    config: PushoverConfig,
+}
+
+impl Pushover {
+    pub fn new(config: PushoverConfig) -> Self {
+        Pushover { config }
+    }
+
+    fn body(&self, reminder: &Reminder) -> String {
+        let mut body = serde_json::json!({
+            "token": self.config.token,
+            "user": self.config.user,
+            "title": format!("Reminder {} is due", reminder.id),
+            "message": get_trimmed_content(&reminder.content, Some(MESSAGE_LENGTH - ELLIPSIS_LENGTH)),
+            "priority": pushover_priority(reminder.priority),
+        });
+        if reminder.priority == Priority::Urgent {
+            body["retry"] = EMERGENCY_RETRY.into();
+            body["expire"] = EMERGENCY_EXPIRE.into();
+        }
+        if let Some(device) = &self.config.device {
+            body["device"] = device.as_str().into();
+        }
+        if let Some(sound) = &self.config.sound {
+            body["sound"] = sound.as_str().into();
+        }
+        body.to_string()
+    }
+}
+
+/// Pushover's priorities run from -2 (no notification) to 2 (repeated
+/// until acknowledged), 0 being the default.
+fn pushover_priority(priority: Priority) -> i8 {
+    match priority {
+        Priority::Low => -1,
+        Priority::Normal => 0,
+        Priority::High => 1,
+        Priority::Urgent => 2,
+    }
+}
+
+impl Notifier for Pushover {
+    fn name(&self) -> String {
+        match &self.config.device {
+            Some(device) => format!("Pushover device {}", device),
+            None => "Pushover".to_string(),
+        }
+    }
+
+    fn notify(&self, reminder: &Reminder) -> Result<()> {
+        super::post(MESSAGES_URL, "application/json", &[], &self.body(reminder), self.config.retries)
+    }
+}
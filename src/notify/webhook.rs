@@ -0,0 +1,121 @@
+// src/notify/webhook.rs
+use reminder::config::{WebhookConfig, WebhookFormat};
+use reminder::{Reminder, Result};
+
+use super::{chat, Notifier};
+
+/// POSTs due reminders to a URL: each as a JSON description of the
+/// reminder or as a body rendered from a template, or those due together
+/// summed up in one Slack or Discord message. The default payload's `text`
+/// field is what Slack shows and `content` is what Discord shows.
+pub struct Webhook {
+    config: WebhookConfig,
+}
+
+impl Webhook {
+    pub fn new(config: WebhookConfig) -> Self {
+        Webhook { config }
+    }
+
+    /// The format the body is in, a template winning over the configured one.
+    fn format(&self) -> WebhookFormat {
+        match self.config.template {
+            Some(_) => WebhookFormat::Json,
+            None => self.config.format,
+        }
+    }
+
+    fn body(&self, reminder: &Reminder) -> String {
+        match &self.config.template {
+            Some(template) => render_template(template, reminder, self.config.content_type.contains("json")),
+            None => serde_json::json!({
+                "text": format!("Reminder {} is due: {}", reminder.id, reminder.content),
+                "id": reminder.id,
+                "content": reminder.content,
+                "answer": reminder.answer,
+                "due": reminder.next_review.to_rfc3339(),
+                "tags": reminder.tags,
+                "deck": reminder.deck,
+                "priority": reminder.priority.to_string(),
+            }).to_string(),
+        }
+    }
+
+    fn post(&self, content_type: &str, body: &str) -> Result<()> {
+        super::post(&self.config.url, content_type, &[], body, self.config.retries)
+    }
+}
+
+impl Notifier for Webhook {
+    fn name(&self) -> String {
+        format!("webhook {}", self.config.url)
+    }
+
+    fn notify(&self, reminder: &Reminder) -> Result<()> {
+        match self.format() {
+            WebhookFormat::Json => self.post(&self.config.content_type, &self.body(reminder)),
+            _ => self.notify_many(&[reminder]),
+        }
+    }
+
+    fn accepts(&self, reminder: &Reminder) -> bool {
+        let WebhookConfig { tags, decks, .. } = &self.config;
+        (tags.is_empty() && decks.is_empty())
+            || reminder.tags.iter().any(|tag| tags.contains(tag))
+            || reminder.deck.as_ref().is_some_and(|deck| decks.contains(deck))
+    }
+
+    fn notify_many(&self, reminders: &[&Reminder]) -> Result<()> {
+        let WebhookConfig { max_reminders, content_length, .. } = self.config;
+        let message = match self.format() {
+            WebhookFormat::Json => return super::notify_each(self, reminders),
+            WebhookFormat::Slack => chat::slack(reminders, max_reminders, content_length),
+            WebhookFormat::Discord => chat::discord(reminders, max_reminders, content_length),
+        };
+        self.post("application/json", &message.to_string())
+    }
+}
+
+/// Fill in `{id}`, `{content}`, `{answer}`, `{due}`, `{tags}`, `{deck}` and
+/// `{priority}`. With `json_escape` the values are escaped to be placed
+/// inside JSON strings.
+fn render_template(template: &str, reminder: &Reminder, json_escape: bool) -> String {
+    let escape = |value: &str| {
+        if json_escape {
+            let quoted = serde_json::to_string(value).unwrap_or_default();
+            quoted[1..quoted.len() - 1].to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    let values = [
+        ("{id}", reminder.id.to_string()),
+        ("{content}", reminder.content.clone()),
+        ("{answer}", reminder.answer.clone().unwrap_or_default()),
+        ("{due}", reminder.next_review.format("%Y-%m-%d %H:%M").to_string()),
+        ("{tags}", reminder.tags.join(", ")),
+        ("{deck}", reminder.deck.clone().unwrap_or_default()),
+        ("{priority}", reminder.priority.to_string()),
+    ];
+
+    // Replaced in one pass so placeholders inside the content stay as they are
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match values.iter().find(|(placeholder, _)| rest.starts_with(placeholder)) {
+            Some((placeholder, value)) => {
+                out.push_str(&escape(value));
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
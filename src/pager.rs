@@ -0,0 +1,165 @@
+// src/pager.rs
+//! Paging of long output. While a [`Pager`] is alive everything printed goes
+//! into a buffer; when it is dropped the output is shown in `$PAGER` if it
+//! is taller than the terminal, and printed as usual otherwise.
+
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Pager used when `$PAGER` isn't set.
+const DEFAULT_PAGER: &str = "less";
+
+/// Width of the terminal while a pager collects the output meant for it; 0
+/// otherwise.
+static PAGED_WIDTH: AtomicUsize = AtomicUsize::new(0);
+
+/// Width of the terminal the output is collected for, while a pager is
+/// collecting it, as stdout isn't the terminal meanwhile.
+pub fn paged_width() -> Option<usize> {
+    match PAGED_WIDTH.load(Ordering::Relaxed) {
+        0 => None,
+        width => Some(width),
+    }
+}
+
+pub struct Pager {
+    height: usize,
+    width: usize,
+    #[cfg(unix)]
+    capture: unix::Capture,
+}
+
+impl Pager {
+    /// Start collecting the output, unless stdout isn't a terminal or the
+    /// pager is turned off, in which case the output isn't touched.
+    pub fn start(enabled: bool) -> Option<Pager> {
+        if !enabled || !io::stdout().is_terminal() || env::var("PAGER").is_ok_and(|pager| pager.trim().is_empty()) {
+            return None;
+        }
+        let (width, height) = terminal_size::terminal_size()?;
+        let _ = io::stdout().flush();
+        let pager = Pager {
+            height: height.0 as usize,
+            width: width.0 as usize,
+            #[cfg(unix)]
+            capture: unix::Capture::start()?,
+        };
+        PAGED_WIDTH.store(pager.width, Ordering::Relaxed);
+        Some(pager)
+    }
+
+    /// Lines the output takes up on the terminal, with long ones wrapped.
+    fn rows(&self, output: &str) -> usize {
+        output.lines()
+            .map(|line| strip_escapes(line).chars().count().div_ceil(self.width.max(1)).max(1))
+            .sum()
+    }
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        PAGED_WIDTH.store(0, Ordering::Relaxed);
+        #[cfg(unix)]
+        {
+            let output = String::from_utf8_lossy(&self.capture.finish()).into_owned();
+            if self.rows(&output) < self.height || page(&output).is_err() {
+                let _ = io::stdout().write_all(output.as_bytes());
+                let _ = io::stdout().flush();
+            }
+        }
+    }
+}
+
+/// Show `output` in the pager, waiting until it is closed.
+fn page(output: &str) -> io::Result<()> {
+    let pager = env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    // Pagers like "less -S" come with their own arguments
+    let mut parts = pager.split_whitespace();
+    let program = parts.next().unwrap_or(DEFAULT_PAGER);
+    let mut command = Command::new(program);
+    command.args(parts).stdin(Stdio::piped());
+    // Keep colors, and leave the text on the screen after quitting, as git does
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    let mut child = command.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may be quit before reading everything
+        let _ = stdin.write_all(output.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// `line` without the escape sequences that color it.
+fn strip_escapes(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip up to the letter ending the sequence, e.g. "\x1b[1;31m"
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io::{self, Read, Write};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::thread::{self, JoinHandle};
+
+    /// Stdout pointed at a pipe, with a thread reading what comes out of it.
+    pub struct Capture {
+        stdout: Option<OwnedFd>,
+        reader: Option<JoinHandle<Vec<u8>>>,
+    }
+
+    impl Capture {
+        pub fn start() -> Option<Capture> {
+            let (mut reader, writer) = io::pipe().ok()?;
+            // SAFETY: dup and dup2 only duplicate descriptors this process
+            // owns; the duplicate of stdout is owned by the OwnedFd
+            let stdout = unsafe {
+                let saved = libc::dup(libc::STDOUT_FILENO);
+                if saved < 0 {
+                    return None;
+                }
+                let saved = OwnedFd::from_raw_fd(saved);
+                if libc::dup2(writer.as_raw_fd(), libc::STDOUT_FILENO) < 0 {
+                    return None;
+                }
+                saved
+            };
+            drop(writer);
+            let reader = thread::spawn(move || {
+                let mut output = Vec::new();
+                let _ = reader.read_to_end(&mut output);
+                output
+            });
+            Some(Capture { stdout: Some(stdout), reader: Some(reader) })
+        }
+
+        /// Point stdout back at the terminal and return what was printed.
+        pub fn finish(&mut self) -> Vec<u8> {
+            let _ = io::stdout().flush();
+            if let Some(stdout) = self.stdout.take() {
+                // SAFETY: as above; this also closes the pipe's last writer,
+                // ending the reader
+                unsafe {
+                    libc::dup2(stdout.as_raw_fd(), libc::STDOUT_FILENO);
+                }
+            }
+            self.reader.take().and_then(|reader| reader.join().ok()).unwrap_or_default()
+        }
+    }
+}
@@ -0,0 +1,106 @@
+// src/profile.rs
+//! Profiles keep separate stores, e.g. for work and personal reminders. Each
+//! profile has a data directory of its own; the default profile uses the
+//! data directory itself, the others `profiles/<name>` inside it.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::error::{Error, Result};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Files that hold a profile's reminders, as opposed to locks and caches.
+const STORE_FILES: [&str; 3] = ["reminders.json", "reminders.db", "webdav-cache.json"];
+
+static CURRENT: OnceLock<String> = OnceLock::new();
+
+/// The directory all profiles live under.
+fn base_dir() -> PathBuf {
+    let mut path = dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from(".")); // Fallback to current directory if data_dir is not available
+    path.push("reminder");
+    path
+}
+
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(Error::Invalid(format!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Use profile `name` for the rest of this invocation. Only the first call
+/// has an effect, so pick the profile before anything touches the store.
+pub fn select(name: &str) -> Result<()> {
+    validate_name(name)?;
+    let _ = CURRENT.set(name.to_string());
+    Ok(())
+}
+
+/// The profile in use, the default one unless another was selected.
+pub fn current() -> &'static str {
+    CURRENT.get().map(String::as_str).unwrap_or(DEFAULT_PROFILE)
+}
+
+pub fn data_dir(name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        base_dir()
+    } else {
+        base_dir().join("profiles").join(name)
+    }
+}
+
+pub fn exists(name: &str) -> bool {
+    name == DEFAULT_PROFILE || data_dir(name).is_dir()
+}
+
+/// Whether the profile holds a store, rather than only being created.
+pub fn has_store(name: &str) -> bool {
+    STORE_FILES.iter().any(|file| data_dir(name).join(file).exists())
+}
+
+/// The default profile followed by the others in alphabetical order.
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(base_dir().join("profiles"))
+        .map(|entries| {
+            entries.filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| validate_name(name).is_ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+pub fn create(name: &str) -> Result<()> {
+    validate_name(name)?;
+    if exists(name) {
+        return Err(Error::Invalid(format!("Profile '{}' already exists", name)));
+    }
+    let dir = data_dir(name);
+    fs::create_dir_all(&dir)
+        .map_err(|e| Error::io(format!("Failed to create profile directory {}", dir.display()), e))
+}
+
+/// Delete a profile's data directory with everything in it.
+pub fn delete(name: &str) -> Result<()> {
+    if name == DEFAULT_PROFILE {
+        return Err(Error::Invalid("The default profile cannot be deleted".to_string()));
+    }
+    validate_name(name)?;
+    if !exists(name) {
+        return Err(Error::Invalid(format!("Profile '{}' does not exist", name)));
+    }
+    let dir = data_dir(name);
+    fs::remove_dir_all(&dir)
+        .map_err(|e| Error::io(format!("Failed to delete profile directory {}", dir.display()), e))
+}
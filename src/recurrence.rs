@@ -0,0 +1,254 @@
+// src/recurrence.rs
+//! Fixed repeat schedules for habit-style reminders, which come back on the
+//! same schedule forever instead of following the spaced repetition ladder.
+//! A rule is either `every <duration>`, such as `every 7 days` or
+//! `every 2w`, or a five-field cron expression such as `0 9 * * mon-fri`.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{clock, dates};
+use crate::duration;
+use crate::error::{Error, Result};
+
+/// How far ahead a cron rule is searched for its next occurrence; rules that
+/// never match, like `0 0 31 2 *`, are rejected when parsed.
+const CRON_SEARCH_DAYS: i64 = 366 * 5;
+
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+const MONTH_NAMES: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+
+/// A repeat rule, stored in the reminder as the text it was written in.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Recurrence {
+    rule: String,
+    schedule: Schedule,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Schedule {
+    Every(Duration),
+    Cron(Cron),
+}
+
+/// The values each field of a cron expression allows, one bit per value.
+#[derive(Clone, Debug, PartialEq)]
+struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day of month or the day of week was restricted, which
+    /// decides how the two combine
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl Recurrence {
+    pub fn parse(input: &str) -> Result<Recurrence> {
+        let rule = input.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        let schedule = match rule.strip_prefix("every ") {
+            Some(period) => Schedule::Every(parse_period(period)?),
+            None => Schedule::Cron(Cron::parse(&rule)?),
+        };
+        let recurrence = Recurrence { rule, schedule };
+
+        if recurrence.next_after(clock::now()).is_none() {
+            return Err(Error::Parse(format!("The repeat rule '{}' never matches a date", recurrence.rule)));
+        }
+        Ok(recurrence)
+    }
+
+    /// The first occurrence after `after`, `None` for a cron rule that
+    /// never matches or a period that goes past the last representable time.
+    fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match &self.schedule {
+            Schedule::Every(period) => after.checked_add_signed(*period),
+            Schedule::Cron(cron) => cron.next_after(after),
+        }
+    }
+
+    /// When a reminder due at `due` is next due once reviewed at `now`. The
+    /// schedule stays anchored to the due time; occurrences that were missed
+    /// while the reminder waited are skipped.
+    pub fn next_review(&self, due: DateTime<Utc>, now: DateTime<Utc>) -> DateTime<Utc> {
+        let next = match &self.schedule {
+            Schedule::Every(period) => {
+                let missed = if now >= due {
+                    (now - due).num_seconds() / period.num_seconds().max(1) + 1
+                } else {
+                    1
+                };
+                // Past the last representable time, it is never due again
+                i32::try_from(missed).ok()
+                    .and_then(|missed| period.checked_mul(missed))
+                    .and_then(|skipped| due.checked_add_signed(skipped))
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC)
+            }
+            Schedule::Cron(cron) => cron.next_after(due.max(now)).unwrap_or(now + Duration::days(1)),
+        };
+        next.max(now)
+    }
+
+    /// The first occurrence of a newly added reminder.
+    pub fn first_review(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.next_after(now).unwrap_or(now + Duration::days(1))
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.rule)
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = Error;
+
+    fn try_from(rule: String) -> Result<Recurrence> {
+        Recurrence::parse(&rule)
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(recurrence: Recurrence) -> String {
+        recurrence.rule
+    }
+}
+
+/// The period of an `every` rule; a bare unit means one of it, as in
+/// `every day`.
+fn parse_period(period: &str) -> Result<Duration> {
+    let period = period.trim();
+    let starts_with_digit = period.starts_with(|c: char| c.is_ascii_digit());
+    let duration = duration::parse_duration(&if starts_with_digit { period.to_string() } else { format!("1{}", period) })
+        .map_err(|_| Error::Parse(format!(
+            "Invalid repeat period '{}': use a duration like \"every 7 days\" or \"every 2w\", \
+             or a cron expression like \"0 9 * * mon\" for weekdays",
+            period
+        )))?;
+    if duration < Duration::minutes(1) {
+        return Err(Error::Parse(format!("The repeat period '{}' is shorter than a minute", period)));
+    }
+    if clock::now().checked_add_signed(duration).is_none() {
+        return Err(Error::Parse(format!("The repeat period '{}' is too long", period)));
+    }
+    Ok(duration)
+}
+
+impl Cron {
+    fn parse(expression: &str) -> Result<Cron> {
+        let fields: Vec<&str> = expression.split(' ').collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err(Error::Parse(format!(
+                "Invalid repeat rule '{}': use \"every 7 days\" or a cron expression like \"0 9 * * mon\"",
+                expression
+            )));
+        };
+
+        let mut weekday_bits = parse_field(weekdays, 0, 7, &WEEKDAY_NAMES, 0)?;
+        // Both 0 and 7 mean Sunday
+        if weekday_bits & (1 << 7) != 0 {
+            weekday_bits = (weekday_bits | 1) & !(1 << 7);
+        }
+
+        Ok(Cron {
+            minutes: parse_field(minutes, 0, 59, &[], 0)?,
+            hours: parse_field(hours, 0, 23, &[], 0)?,
+            days: parse_field(days, 1, 31, &[], 0)?,
+            months: parse_field(months, 1, 12, &MONTH_NAMES, 1)?,
+            weekdays: weekday_bits,
+            days_restricted: !days.starts_with('*'),
+            weekdays_restricted: !weekdays.starts_with('*'),
+        })
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        // As in cron, a restricted day of month and day of week match either
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+
+    fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = dates::local(after).naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        for offset in 0..CRON_SEARCH_DAYS {
+            let date = start.date() + Duration::days(offset);
+            if !self.matches_date(date) {
+                continue;
+            }
+            for hour in (0..24).filter(|hour| has(self.hours, *hour)) {
+                for minute in (0..60).filter(|minute| has(self.minutes, *minute)) {
+                    let time = NaiveDateTime::new(date, NaiveTime::from_hms_opt(hour, minute, 0)?);
+                    if time < start {
+                        continue;
+                    }
+                    // Times skipped by a daylight saving change don't exist
+                    if let Some(time) = dates::from_local(time) {
+                        return Some(time);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+/// Parse one cron field: `*`, a value, a range `a-b`, any of them with a
+/// step `/n`, or a comma-separated list of those. `names` are accepted for
+/// the values from `first_name` on.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], first_name: u32) -> Result<u64> {
+    let invalid = || Error::Parse(format!("Invalid cron field '{}': expected values from {} to {}", field, min, max));
+    let value = |text: &str| -> Result<u32> {
+        let value = match names.iter().position(|name| *name == text) {
+            Some(index) => index as u32 + first_name,
+            None => text.parse().map_err(|_| invalid())?,
+        };
+        if !(min..=max).contains(&value) {
+            return Err(invalid());
+        }
+        Ok(value)
+    };
+
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|step| *step > 0).ok_or_else(invalid)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // A single value with a step runs to the end, as in `5/15`
+                None if part.contains('/') => (value(range)?, max),
+                None => {
+                    let value = value(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
@@ -0,0 +1,292 @@
+// src/schedule.rs
+use chrono::Duration;
+use std::env;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use reminder::{Error, Result};
+
+/// The comment put above the crontab entry, so it can be found again.
+const CRON_MARKER: &str = "# Added by reminder install-schedule";
+
+/// What runs `reminder check --notify` on a schedule.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Scheduler {
+    /// A user-level systemd timer
+    Systemd,
+    /// An entry in the user's crontab
+    Cron,
+}
+
+impl Scheduler {
+    /// systemd where it manages the system, cron everywhere else.
+    pub fn detect() -> Result<Scheduler> {
+        if cfg!(windows) {
+            return Err(Error::Invalid(
+                "install-schedule needs systemd or cron; on Windows add 'reminder check --notify' to the Task Scheduler".to_string(),
+            ));
+        }
+        Ok(if Path::new("/run/systemd/system").is_dir() { Scheduler::Systemd } else { Scheduler::Cron })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scheduler::Systemd => "systemd",
+            Scheduler::Cron => "cron",
+        }
+    }
+}
+
+/// A periodic `reminder check --notify` for one profile.
+pub struct Schedule {
+    /// Name of the systemd units, or of the crontab entry
+    pub name: String,
+    /// The arguments given before `check`, such as `--profile work`
+    pub args: Vec<String>,
+    pub every: Duration,
+}
+
+impl Schedule {
+    /// The command the scheduler runs, starting with this executable.
+    fn command(&self, extra: &[&str]) -> Result<Vec<String>> {
+        let exe = env::current_exe()
+            .map_err(|e| Error::io("Failed to find the reminder executable", e))?;
+        let mut command = vec![exe.to_string_lossy().into_owned()];
+        command.extend(self.args.iter().cloned());
+        command.push("check".to_string());
+        command.extend(extra.iter().map(|arg| arg.to_string()));
+        Ok(command)
+    }
+
+    /// Write and enable the schedule, replacing an earlier one of the same
+    /// name.
+    pub fn install(&self, scheduler: Scheduler) -> Result<()> {
+        if self.every < Duration::minutes(1) {
+            return Err(Error::Invalid("Reminders can be checked at most once a minute".to_string()));
+        }
+        match scheduler {
+            Scheduler::Systemd => self.install_systemd(),
+            Scheduler::Cron => self.install_cron(),
+        }
+    }
+
+    /// Stop and delete the schedule. Returns false when none was installed.
+    pub fn remove(&self, scheduler: Scheduler) -> Result<bool> {
+        match scheduler {
+            Scheduler::Systemd => self.remove_systemd(),
+            Scheduler::Cron => self.remove_cron(),
+        }
+    }
+
+    fn unit_path(&self, extension: &str) -> Result<PathBuf> {
+        let dir = dirs::config_dir()
+            .ok_or_else(|| Error::Invalid("Could not find the config directory for the systemd units".to_string()))?;
+        Ok(dir.join("systemd").join("user").join(format!("{}.{}", self.name, extension)))
+    }
+
+    fn install_systemd(&self) -> Result<()> {
+        // Without --quiet a due reminder doesn't fail the unit, and the
+        // output goes to the journal
+        let command = self.command(&["--notify"])?;
+        let exec: Vec<String> = command.iter().map(|arg| systemd_quote(arg)).collect();
+        let service = format!(
+            "[Unit]\nDescription=Notify about due reminders\n\n[Service]\nType=oneshot\nExecStart={}\n",
+            exec.join(" ")
+        );
+        let timer = format!(
+            "[Unit]\nDescription=Check for due reminders every {every}\n\n[Timer]\nOnStartupSec=1min\nOnUnitActiveSec={secs}s\n\n[Install]\nWantedBy=timers.target\n",
+            every = describe(self.every),
+            secs = self.every.num_seconds()
+        );
+
+        let service_path = self.unit_path("service")?;
+        if let Some(dir) = service_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| Error::io(format!("Failed to create {}", dir.display()), e))?;
+        }
+        for (path, text) in [(service_path, service), (self.unit_path("timer")?, timer)] {
+            fs::write(&path, text).map_err(|e| Error::io(format!("Failed to write {}", path.display()), e))?;
+        }
+
+        systemctl(&["daemon-reload"])?;
+        systemctl(&["enable", "--now", &format!("{}.timer", self.name)])?;
+        // A changed interval only applies once the timer is restarted
+        systemctl(&["restart", &format!("{}.timer", self.name)])
+    }
+
+    fn remove_systemd(&self) -> Result<bool> {
+        let paths = [self.unit_path("timer")?, self.unit_path("service")?];
+        if !paths.iter().any(|path| path.exists()) {
+            return Ok(false);
+        }
+        systemctl(&["disable", "--now", &format!("{}.timer", self.name)])?;
+        for path in &paths {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => {
+                    return Err(Error::io(format!("Failed to delete {}", path.display()), e))
+                }
+                _ => {}
+            }
+        }
+        systemctl(&["daemon-reload"])?;
+        Ok(true)
+    }
+
+    fn marker(&self) -> String {
+        format!("{} ({})", CRON_MARKER, self.name)
+    }
+
+    fn install_cron(&self) -> Result<()> {
+        // cron mails whatever a job prints, so only failures are printed
+        let command = self.command(&["--quiet", "--notify"])?;
+        let mut line = cron_schedule(self.every)?;
+        // Desktop notifications need the session bus, which cron jobs don't see
+        if let Ok(bus) = env::var("DBUS_SESSION_BUS_ADDRESS") {
+            line.push_str(&format!(" DBUS_SESSION_BUS_ADDRESS={}", cron_quote(&bus)));
+        }
+        for arg in &command {
+            line.push(' ');
+            line.push_str(&cron_quote(arg));
+        }
+
+        let mut lines = self.crontab_without_entry()?.0;
+        lines.push(self.marker());
+        lines.push(line);
+        write_crontab(&lines)
+    }
+
+    fn remove_cron(&self) -> Result<bool> {
+        let (lines, found) = self.crontab_without_entry()?;
+        if found {
+            write_crontab(&lines)?;
+        }
+        Ok(found)
+    }
+
+    /// The lines of the user's crontab without this schedule's entry, and
+    /// whether it had one.
+    fn crontab_without_entry(&self) -> Result<(Vec<String>, bool)> {
+        let marker = self.marker();
+        let mut lines = Vec::new();
+        let mut found = false;
+        let mut skip_next = false;
+        for line in read_crontab()?.lines() {
+            if skip_next {
+                skip_next = false;
+            } else if line == marker {
+                found = true;
+                skip_next = true;
+            } else {
+                lines.push(line.to_string());
+            }
+        }
+        Ok((lines, found))
+    }
+}
+
+fn systemctl(args: &[&str]) -> Result<()> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .stdin(Stdio::null())
+        .status()
+        .map_err(|e| Error::io("Failed to launch 'systemctl'", e))?;
+    if !status.success() {
+        return Err(Error::Invalid(format!("'systemctl --user {}' failed ({})", args.join(" "), status)));
+    }
+    Ok(())
+}
+
+fn read_crontab() -> Result<String> {
+    let output = Command::new("crontab")
+        .arg("-l")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| Error::io("Failed to launch 'crontab'", e))?;
+    // crontab -l fails when the user has no crontab yet
+    if !output.status.success() {
+        return Ok(String::new());
+    }
+    String::from_utf8(output.stdout).map_err(|_| Error::Invalid("The crontab isn't valid UTF-8".to_string()))
+}
+
+fn write_crontab(lines: &[String]) -> Result<()> {
+    let mut child = Command::new("crontab")
+        .arg("-")
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::io("Failed to launch 'crontab'", e))?;
+    let mut text = lines.join("\n");
+    text.push('\n');
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).map_err(|e| Error::io("Failed to write the crontab", e))?;
+    }
+    let status = child.wait().map_err(|e| Error::io("Failed to write the crontab", e))?;
+    if !status.success() {
+        return Err(Error::Invalid(format!("'crontab' refused the new crontab ({})", status)));
+    }
+    Ok(())
+}
+
+/// The crontab time fields for running every `every`, which has to divide an
+/// hour or a day evenly.
+fn cron_schedule(every: Duration) -> Result<String> {
+    let minutes = every.num_minutes();
+    if every.num_seconds() % 60 == 0 {
+        if minutes == 1 {
+            return Ok("* * * * *".to_string());
+        }
+        if minutes < 60 && 60 % minutes == 0 {
+            return Ok(format!("*/{} * * * *", minutes));
+        }
+        let hours = minutes / 60;
+        if minutes % 60 == 0 && hours < 24 && 24 % hours == 0 {
+            return Ok(if hours == 1 { "0 * * * *".to_string() } else { format!("0 */{} * * *", hours) });
+        }
+        if minutes == 24 * 60 {
+            return Ok("0 0 * * *".to_string());
+        }
+    }
+    Err(Error::Invalid(format!(
+        "cron can't run every {}; use a number of minutes or hours that divides an hour or a day, e.g. 15m, 30m or 2h",
+        describe(every)
+    )))
+}
+
+/// Quote an argument for a systemd `ExecStart=` line.
+fn systemd_quote(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    if arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == '\\') {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg
+    }
+}
+
+/// Quote an argument for the shell that runs a crontab entry; `%` means a
+/// newline to cron unless escaped.
+fn cron_quote(arg: &str) -> String {
+    let quoted = if arg.chars().all(|c| c.is_ascii_alphanumeric() || "/-_.=:,@".contains(c)) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    };
+    quoted.replace('%', "\\%")
+}
+
+/// An interval in the largest whole unit, e.g. "30 minutes" or "2 hours".
+pub fn describe(every: Duration) -> String {
+    let seconds = every.num_seconds();
+    let (amount, unit) = if seconds % 86400 == 0 {
+        (seconds / 86400, "day")
+    } else if seconds % 3600 == 0 {
+        (seconds / 3600, "hour")
+    } else if seconds % 60 == 0 {
+        (seconds / 60, "minute")
+    } else {
+        (seconds, "second")
+    };
+    if amount == 1 { unit.to_string() } else { format!("{} {}s", amount, unit) }
+}
@@ -0,0 +1,316 @@
+// src/scheduler.rs
+use chrono::{DateTime, Duration, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::config::{Completion, Config};
+use crate::error::{Error, Result};
+use crate::{clock, dates};
+use crate::{Reminder, ReviewEvent};
+
+/// Starting ease factor for SM-2, as recommended by SuperMemo.
+pub const DEFAULT_EASE_FACTOR: f64 = 2.5;
+pub const MIN_EASE_FACTOR: f64 = 1.3;
+
+/// Grade assumed for a graded review when none is given on the command line.
+pub const DEFAULT_GRADE: u8 = 4;
+
+/// Lowest grade that counts as recalled; anything below is a lapse.
+pub const PASSING_GRADE: u8 = 3;
+
+/// How much a "hard" or "easy" rating stretches the interval of the simple
+/// ladder.
+const HARD_MULTIPLIER: f64 = 1.2;
+const EASY_MULTIPLIER: f64 = 1.5;
+
+/// How much each review past the end of the simple ladder stretches the
+/// interval, for reminders that aren't completed there.
+const EXTEND_MULTIPLIER: f64 = 2.0;
+
+/// Longest interval scheduled, a hundred years, unless `max_interval` sets a
+/// shorter one; intervals that keep growing would otherwise run past the
+/// last date that can be represented.
+pub const MAX_INTERVAL: u32 = 36_500;
+
+/// Four-button rating of a review, as in Anki. Each maps onto the 0-5 grade
+/// scale shared by the algorithms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rating {
+    /// Forgotten: the simple ladder starts over
+    Again,
+    /// Recalled with difficulty: the simple ladder stays on its stage
+    Hard,
+    /// Recalled: the simple ladder moves up a stage
+    Good,
+    /// Recalled effortlessly: up a stage with a longer interval
+    Easy,
+}
+
+impl Rating {
+    pub fn from_grade(grade: u8) -> Rating {
+        match grade {
+            0..PASSING_GRADE => Rating::Again,
+            PASSING_GRADE => Rating::Hard,
+            4 => Rating::Good,
+            _ => Rating::Easy,
+        }
+    }
+
+    pub fn grade(self) -> u8 {
+        match self {
+            Rating::Again => 1,
+            Rating::Hard => PASSING_GRADE,
+            Rating::Good => 4,
+            Rating::Easy => 5,
+        }
+    }
+}
+
+/// Default FSRS-4.5 model weights.
+const FSRS_WEIGHTS: [f64; 17] = [
+    0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367, 1.0461,
+    2.1072, 0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+];
+const FSRS_DECAY: f64 = -0.5;
+const FSRS_FACTOR: f64 = 19.0 / 81.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Algorithm {
+    /// Fixed interval ladder from the config file
+    #[default]
+    Simple,
+    /// SuperMemo-2 with graded reviews
+    Sm2,
+    /// Free Spaced Repetition Scheduler with graded reviews
+    Fsrs,
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Algorithm::Simple => "simple",
+            Algorithm::Sm2 => "sm2",
+            Algorithm::Fsrs => "fsrs",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Apply a review to the reminder and schedule its next review according to
+/// the reminder's algorithm, or its repeat rule if it has one, recording it
+/// in the reminder's history. The simple algorithm only takes the grade into
+/// account when one is given, as a [`Rating`]; recurring reminders ignore it.
+/// A recalled reminder is completed when its [`Completion`] policy says so.
+/// Fails, with the reminder partly updated, only when the next review would
+/// be due after the last representable time.
+pub fn review(reminder: &mut Reminder, grade: Option<u8>, config: &Config) -> Result<()> {
+    let graded = reminder.is_graded() || (grade.is_some() && reminder.recurrence.is_none());
+    let grade = grade.unwrap_or(DEFAULT_GRADE);
+    let completion = config.completion_for(reminder.deck.as_deref(), &reminder.tags);
+    reminder.review_count += 1;
+
+    if graded && grade < PASSING_GRADE {
+        reminder.lapses += 1;
+    }
+    match (&reminder.recurrence, reminder.algorithm) {
+        (Some(_), _) => review_recurring(reminder),
+        (None, Algorithm::Simple) => review_simple(reminder, Rating::from_grade(grade), completion, config)?,
+        (None, Algorithm::Sm2) => review_sm2(reminder, grade, config)?,
+        (None, Algorithm::Fsrs) => review_fsrs(reminder, grade, config)?,
+    }
+    let recalled = !graded || grade >= PASSING_GRADE;
+    if reminder.recurrence.is_none() && recalled && is_complete(reminder, completion) {
+        reminder.completed = true;
+    }
+
+    let now = clock::now();
+    reminder.last_review = Some(now);
+    reminder.history.push(ReviewEvent {
+        timestamp: now,
+        grade: graded.then_some(grade),
+        interval: reminder.interval,
+    });
+    Ok(())
+}
+
+/// An interval the algorithm picked, in days, after the configured
+/// `interval_modifier` and `max_interval`. Never shorter than a day nor
+/// longer than [`MAX_INTERVAL`].
+pub fn adjust_interval(days: u32, config: &Config) -> u32 {
+    let days = ((days as f64 * config.interval_modifier).round() as u32).max(1);
+    days.min(longest_interval(config))
+}
+
+/// The longest interval allowed, in days.
+fn longest_interval(config: &Config) -> u32 {
+    config.max_interval.map_or(MAX_INTERVAL, |max| max.min(MAX_INTERVAL))
+}
+
+/// When a review `days` from now is due, moved by up to `fuzz` of the
+/// interval either way so reminders added or reviewed together spread out.
+/// The offset comes from the reminder's ID and review count instead of a
+/// random number generator, so the same review always lands at the same time.
+/// Fails when that is after the last representable time.
+pub fn fuzzed_due(id: u32, review_count: u32, days: u32, config: &Config) -> Result<DateTime<Utc>> {
+    let mut x = ((id as u64) << 32 | review_count as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    let unit = (x >> 11) as f64 / (1u64 << 53) as f64;
+
+    let interval = Duration::days(days as i64);
+    let mut offset = (unit * 2.0 - 1.0) * config.fuzz * interval.num_seconds() as f64;
+    // Fuzz never stretches an interval past the longest allowed
+    if days >= longest_interval(config) {
+        offset = -offset.abs();
+    }
+    // Reviews scheduled by day move by whole days only
+    if dates::day_rollover().is_some() {
+        offset = (offset / 86_400.0).round() * 86_400.0;
+    }
+    clock::now().checked_add_signed(interval + Duration::seconds(offset.round() as i64))
+        .ok_or_else(|| Error::Invalid(format!("Reminder {} can't be scheduled {} days from now", id, days)))
+}
+
+/// Recurring reminders come back on their fixed schedule and never complete.
+fn review_recurring(reminder: &mut Reminder) {
+    let Some(recurrence) = &reminder.recurrence else {
+        return;
+    };
+    let now = clock::now();
+    reminder.next_review = recurrence.next_review(reminder.next_review, now);
+    reminder.interval = (reminder.next_review - now).num_days().max(0) as u32;
+}
+
+/// The interval ladder the simple algorithm follows for a reminder: its own,
+/// or else its deck's, its tags' or the global one.
+pub fn ladder<'a>(reminder: &'a Reminder, config: &'a Config) -> &'a [u32] {
+    match reminder.intervals.as_deref() {
+        Some(intervals) if !intervals.is_empty() => intervals,
+        _ => config.intervals_for(reminder.deck.as_deref(), &reminder.tags),
+    }
+}
+
+/// Whether `completion` counts the reminder as done after a review it
+/// recalled. The ladder policy is applied by the simple algorithm itself.
+fn is_complete(reminder: &Reminder, completion: Completion) -> bool {
+    match completion {
+        Completion::Ladder | Completion::Never => false,
+        Completion::Reviews(reviews) => reminder.review_count >= reviews,
+        Completion::Interval(days) => reminder.interval >= days,
+    }
+}
+
+fn review_simple(reminder: &mut Reminder, rating: Rating, completion: Completion, config: &Config) -> Result<()> {
+    // "Again" starts the ladder over and "hard" keeps it on the same stage,
+    // by moving where the ladder counts from
+    match rating {
+        Rating::Again => reminder.ladder_start = reminder.review_count,
+        Rating::Hard => reminder.ladder_start += 1,
+        Rating::Good | Rating::Easy => {}
+    }
+    let stage = reminder.review_count - reminder.ladder_start.min(reminder.review_count);
+
+    // Schedule next review based on the reminder's interval ladder
+    let ladder = ladder(reminder, config);
+    let (first, next) = (ladder[0], ladder.get(stage as usize).copied());
+    let days = match (rating, next) {
+        (Rating::Again, _) => first,
+        (Rating::Hard, _) => ((reminder.interval as f64 * HARD_MULTIPLIER).round() as u32).max(1),
+        (Rating::Good, Some(days)) => days,
+        (Rating::Easy, Some(days)) => (days as f64 * EASY_MULTIPLIER).round() as u32,
+        (Rating::Good | Rating::Easy, None) if completion == Completion::Ladder => {
+            reminder.completed = true;
+            return Ok(());
+        }
+        (Rating::Good, None) => (reminder.interval.max(1) as f64 * EXTEND_MULTIPLIER).round() as u32,
+        (Rating::Easy, None) => (reminder.interval.max(1) as f64 * EXTEND_MULTIPLIER * EASY_MULTIPLIER).round() as u32,
+    };
+    let days = adjust_interval(days, config);
+    reminder.interval = days;
+    reminder.next_review = fuzzed_due(reminder.id, reminder.review_count, days, config)?;
+    Ok(())
+}
+
+fn review_sm2(reminder: &mut Reminder, grade: u8, config: &Config) -> Result<()> {
+    let q = grade.min(5) as f64;
+
+    if grade >= PASSING_GRADE {
+        reminder.interval = match reminder.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (reminder.interval as f64 * reminder.ease_factor).round() as u32,
+        };
+        reminder.repetitions += 1;
+    } else {
+        // Failed recall starts the repetition sequence over
+        reminder.repetitions = 0;
+        reminder.interval = 1;
+    }
+    reminder.interval = adjust_interval(reminder.interval, config);
+
+    let change = 0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02);
+    let change = change * if change >= 0.0 { config.ease_bonus } else { config.ease_penalty };
+    reminder.ease_factor = (reminder.ease_factor + change).max(MIN_EASE_FACTOR);
+    reminder.next_review = fuzzed_due(reminder.id, reminder.review_count, reminder.interval, config)?;
+    Ok(())
+}
+
+/// FSRS ratings: 1 = again, 2 = hard, 3 = good, 4 = easy. The 0-5 grade scale
+/// shared with SM-2 is folded onto them, so the default grade of 4 is "good".
+fn fsrs_rating(grade: u8) -> f64 {
+    match grade {
+        0..=2 => 1.0,
+        3 => 2.0,
+        4 => 3.0,
+        _ => 4.0,
+    }
+}
+
+fn fsrs_initial_difficulty(rating: f64) -> f64 {
+    let w = &FSRS_WEIGHTS;
+    (w[4] - (rating - 3.0) * w[5]).clamp(1.0, 10.0)
+}
+
+fn review_fsrs(reminder: &mut Reminder, grade: u8, config: &Config) -> Result<()> {
+    let w = &FSRS_WEIGHTS;
+    let rating = fsrs_rating(grade);
+    let now = clock::now();
+
+    if reminder.stability <= 0.0 {
+        // First review: initialise memory state from the rating alone
+        reminder.stability = w[rating as usize - 1];
+        reminder.difficulty = fsrs_initial_difficulty(rating);
+    } else {
+        let last = reminder.last_review.unwrap_or(reminder.created_at);
+        let elapsed_days = (now - last).num_seconds().max(0) as f64 / 86_400.0;
+        let s = reminder.stability;
+        let d = reminder.difficulty;
+        let r = (1.0 + FSRS_FACTOR * elapsed_days / s).powf(FSRS_DECAY);
+
+        reminder.stability = if rating == 1.0 {
+            w[11] * d.powf(-w[12]) * ((s + 1.0).powf(w[13]) - 1.0) * (w[14] * (1.0 - r)).exp()
+        } else {
+            let hard_penalty = if rating == 2.0 { w[15] } else { 1.0 };
+            let easy_bonus = if rating == 4.0 { w[16] } else { 1.0 };
+            s * (w[8].exp() * (11.0 - d) * s.powf(-w[9]) * ((w[10] * (1.0 - r)).exp() - 1.0)
+                * hard_penalty
+                * easy_bonus
+                + 1.0)
+        };
+
+        // Move difficulty by the rating, with mean reversion towards the
+        // initial difficulty of a "good" rating
+        let next_d = d - w[6] * (rating - 3.0);
+        reminder.difficulty =
+            (w[7] * fsrs_initial_difficulty(3.0) + (1.0 - w[7]) * next_d).clamp(1.0, 10.0);
+    }
+
+    let retention = config.desired_retention;
+    let interval = reminder.stability / FSRS_FACTOR * (retention.powf(1.0 / FSRS_DECAY) - 1.0);
+    reminder.interval = adjust_interval(interval.round() as u32, config);
+    reminder.next_review = fuzzed_due(reminder.id, reminder.review_count, reminder.interval, config)?;
+    Ok(())
+}
@@ -0,0 +1,149 @@
+// src/search.rs
+use regex::{Regex, RegexBuilder};
+use std::collections::HashSet;
+
+use crate::error::{Error, Result};
+use crate::{find_urls, Reminder};
+
+/// A search query matched case-insensitively against a reminder's content
+/// and tags.
+pub enum Query {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl Query {
+    pub fn new(pattern: &str, use_regex: bool) -> Result<Self> {
+        if use_regex {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map(Query::Regex)
+                .map_err(|e| Error::Parse(format!("Invalid regex: {}", e)))
+        } else {
+            Ok(Query::Substring(pattern.to_lowercase()))
+        }
+    }
+
+    pub fn matches(&self, reminder: &Reminder) -> bool {
+        std::iter::once(&reminder.content)
+            .chain(&reminder.tags)
+            .any(|text| self.matches_text(text))
+    }
+
+    fn matches_text(&self, text: &str) -> bool {
+        match self {
+            Query::Substring(needle) => text.to_lowercase().contains(needle),
+            Query::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// How well `pattern` fuzzily matches `text`, higher being better, or `None`
+/// when a word of the pattern can't be found at all. Each word scores most
+/// when it is a whole word of the text, less inside a word, and least when
+/// only its letters appear in order, as in "ownrshp"; the whole pattern
+/// appearing as a phrase scores extra.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<u32> {
+    let pattern = pattern.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let text = text.to_lowercase();
+    let words: Vec<&str> = text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()).collect();
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let mut score = 0;
+    for needle in pattern.split(' ') {
+        score += if words.contains(&needle) {
+            3
+        } else if text.contains(needle) {
+            2
+        } else if words.iter().any(|word| is_subsequence(needle, word)) {
+            1
+        } else {
+            return None;
+        };
+    }
+    if pattern.contains(' ') && text.contains(&pattern) {
+        score += 2;
+    }
+    Some(score)
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|other| other == c))
+}
+
+/// The reminders whose content fuzzily matches `pattern`, best first.
+pub fn fuzzy_matches<'a>(reminders: impl IntoIterator<Item = &'a Reminder>, pattern: &str) -> Vec<(&'a Reminder, u32)> {
+    let mut matches: Vec<(&Reminder, u32)> = reminders.into_iter()
+        .filter_map(|reminder| fuzzy_score(pattern, &reminder.content).map(|score| (reminder, score)))
+        .collect();
+    matches.sort_by_key(|(reminder, score)| (std::cmp::Reverse(*score), reminder.id));
+    matches
+}
+
+/// Share of words two texts need in common to count as near duplicates.
+pub const DUPLICATE_SIMILARITY: f64 = 0.8;
+
+/// How an existing reminder duplicates content about to be added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Duplicate {
+    /// The same text, ignoring case, punctuation and spacing
+    Exact,
+    /// A web address in both
+    SameLink,
+    /// Mostly the same words
+    Similar,
+}
+
+impl Duplicate {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Duplicate::Exact => "has the same content",
+            Duplicate::SameLink => "links to the same address",
+            Duplicate::Similar => "has nearly the same content",
+        }
+    }
+}
+
+/// Lowercase words of `text`, without punctuation.
+fn normalized_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// A web address without the parts that don't change what it points to.
+fn normalized_url(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.strip_prefix("www.").unwrap_or(url);
+    url.split('#').next().unwrap_or(url).trim_end_matches('/').to_lowercase()
+}
+
+/// The reminders that `content` would duplicate, most alike first.
+pub fn find_duplicates<'a>(reminders: impl IntoIterator<Item = &'a Reminder>, content: &str) -> Vec<(&'a Reminder, Duplicate)> {
+    let words = normalized_words(content);
+    let word_set: HashSet<&String> = words.iter().collect();
+    let urls: HashSet<String> = find_urls(content).into_iter().map(normalized_url).collect();
+
+    let mut duplicates: Vec<(&Reminder, Duplicate)> = reminders.into_iter()
+        .filter_map(|reminder| {
+            let other = normalized_words(&reminder.content);
+            if !words.is_empty() && other == words {
+                return Some((reminder, Duplicate::Exact));
+            }
+            if find_urls(&reminder.content).into_iter().any(|url| urls.contains(&normalized_url(url))) {
+                return Some((reminder, Duplicate::SameLink));
+            }
+            let other: HashSet<&String> = other.iter().collect();
+            let union = word_set.union(&other).count();
+            let similarity = word_set.intersection(&other).count() as f64 / union.max(1) as f64;
+            (similarity >= DUPLICATE_SIMILARITY).then_some((reminder, Duplicate::Similar))
+        })
+        .collect();
+    duplicates.sort_by_key(|(reminder, duplicate)| (*duplicate as u8, reminder.id));
+    duplicates
+}
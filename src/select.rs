@@ -0,0 +1,179 @@
+// src/select.rs
+use clap::Args;
+use clap_complete::ArgValueCandidates;
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::str::FromStr;
+
+use reminder::search::fuzzy_matches;
+use reminder::{get_trimmed_content, Error, ReminderStore, Result};
+
+use crate::completions;
+
+/// Most candidates listed when a `--match` is ambiguous.
+const MAX_CANDIDATES: usize = 9;
+
+/// Ways of picking reminders besides listing their IDs, shared by the bulk
+/// commands.
+#[derive(Args)]
+pub struct Selectors {
+    /// Also select every reminder that is due now
+    #[arg(long)]
+    pub all_due: bool,
+    /// Also select reminders with this tag (repeatable, all must match)
+    #[arg(long = "tag", value_name = "TAG", add = ArgValueCandidates::new(completions::tags))]
+    pub tags: Vec<String>,
+    /// Also select the reminder whose content best matches this text, e.g. "ownership rules"
+    #[arg(long = "match", value_name = "TEXT")]
+    pub matching: Option<String>,
+}
+
+/// A reminder given on the command line by its ID, or by a prefix of its
+/// code when it isn't a number.
+#[derive(Clone, Debug)]
+pub enum ReminderRef {
+    Id(u32),
+    Code(String),
+}
+
+impl FromStr for ReminderRef {
+    type Err = String;
+
+    fn from_str(arg: &str) -> std::result::Result<Self, Self::Err> {
+        let arg = arg.trim();
+        if arg.bytes().all(|b| b.is_ascii_digit()) {
+            return arg.parse().map(ReminderRef::Id).map_err(|_| format!("'{}' is not a valid ID", arg));
+        }
+        if is_code(arg) {
+            return Ok(ReminderRef::Code(arg.to_lowercase()));
+        }
+        Err(format!("'{}' is neither an ID nor a reminder code", arg))
+    }
+}
+
+impl ReminderRef {
+    /// The ID of the reminder meant.
+    pub fn resolve(&self, store: &ReminderStore) -> Result<u32> {
+        match self {
+            ReminderRef::Id(id) => Ok(*id),
+            ReminderRef::Code(code) => store.find_by_code(code),
+        }
+    }
+
+    /// The ID of the reminder meant among those in the trash.
+    pub fn resolve_trashed(&self, store: &ReminderStore) -> Result<u32> {
+        match self {
+            ReminderRef::Id(id) => Ok(*id),
+            ReminderRef::Code(code) => store.find_trashed_by_code(code),
+        }
+    }
+
+    /// The ID of the reminder meant, active, archived or in the trash.
+    pub fn resolve_any(&self, store: &ReminderStore) -> Result<u32> {
+        match self {
+            ReminderRef::Id(id) => Ok(*id),
+            ReminderRef::Code(code) => store.find_any_by_code(code),
+        }
+    }
+}
+
+/// Whether an argument is written like a reminder code rather than an ID.
+fn is_code(arg: &str) -> bool {
+    !arg.is_empty() && arg.bytes().all(|b| b.is_ascii_alphanumeric())
+        && !arg.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Parse IDs and inclusive ranges such as `3`, `7-10`.
+pub fn parse_ids(args: &[String]) -> Result<Vec<u32>> {
+    let mut ids = Vec::new();
+    for arg in args {
+        let parse = |id: &str| {
+            id.trim().parse::<u32>()
+                .map_err(|_| Error::Parse(format!("Invalid ID '{}': expected a number, a range like 7-10 or a reminder code", arg)))
+        };
+        match arg.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(Error::Parse(format!("Invalid range '{}': start is after end", arg)));
+                }
+                ids.extend(start..=end);
+            }
+            None => ids.push(parse(arg)?),
+        }
+    }
+    Ok(ids)
+}
+
+/// The IDs and codes given on the command line plus every reminder matching
+/// the selectors, in ascending order. With `active_only`, completed reminders
+/// are left out of what the selectors pick.
+pub fn resolve(store: &ReminderStore, ids: &[String], selectors: &Selectors, active_only: bool) -> Result<Vec<u32>> {
+    let (codes, ids): (Vec<String>, Vec<String>) = ids.iter().cloned().partition(|arg| is_code(arg.trim()));
+    let mut selected: BTreeSet<u32> = parse_ids(&ids)?.into_iter().collect();
+    for code in &codes {
+        selected.insert(store.find_by_code(code)?);
+    }
+
+    if selectors.all_due || !selectors.tags.is_empty() {
+        let candidates = if selectors.all_due { store.get_due_reminders() } else { store.get_all_reminders() };
+        selected.extend(
+            candidates.into_iter()
+                .filter(|r| r.has_tags(&selectors.tags) && !(active_only && r.completed))
+                .map(|r| r.id),
+        );
+    }
+
+    if let Some(pattern) = &selectors.matching {
+        selected.insert(resolve_match(store, pattern, active_only)?);
+    }
+
+    if selected.is_empty() {
+        return Err(Error::Invalid("No reminders selected (give IDs, codes, --all-due, --tag or --match)".to_string()));
+    }
+    Ok(selected.into_iter().collect())
+}
+
+/// The one reminder whose content fuzzily matches `pattern` best. When
+/// several match equally well, ask which one is meant if running in a
+/// terminal, and fail otherwise.
+pub fn resolve_match(store: &ReminderStore, pattern: &str, active_only: bool) -> Result<u32> {
+    let candidates = store.get_all_reminders().into_iter().filter(|r| !(active_only && r.completed));
+    let matches = fuzzy_matches(candidates, pattern);
+
+    let best = match matches.as_slice() {
+        [] => return Err(Error::Invalid(format!("No reminder matches '{}'", pattern))),
+        [(only, _)] => return Ok(only.id),
+        [(first, score), (_, second), ..] if score > second => return Ok(first.id),
+        [(_, score), ..] => *score,
+    };
+    let tied: Vec<_> = matches.iter()
+        .take_while(|(_, score)| *score == best)
+        .take(MAX_CANDIDATES)
+        .map(|(reminder, _)| (reminder.id, get_trimmed_content(&reminder.content, Some(60))))
+        .collect();
+
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        let listed: Vec<String> = tied.iter().map(|(id, content)| format!("{} \"{}\"", id, content)).collect();
+        return Err(Error::Invalid(format!(
+            "'{}' matches several reminders: {}; use more of the content or the ID",
+            pattern,
+            listed.join(", ")
+        )));
+    }
+
+    eprintln!("'{}' matches several reminders:", pattern);
+    for (index, (id, content)) in tied.iter().enumerate() {
+        eprintln!("  [{}] ID {}: {}", index + 1, id, content);
+    }
+    eprint!("Which one? [1-{}]: ", tied.len());
+    io::stderr().flush().map_err(|e| Error::io("Failed to write the prompt", e))?;
+
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line).map_err(|e| Error::io("Failed to read the choice", e))?;
+    line.trim().parse::<usize>().ok()
+        .and_then(|choice| choice.checked_sub(1))
+        .and_then(|index| tied.get(index))
+        .map(|(id, _)| *id)
+        .ok_or_else(|| Error::Invalid("No reminder chosen".to_string()))
+}
@@ -0,0 +1,189 @@
+// src/server.rs
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::Read;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use reminder::config::Config;
+use reminder::recurrence::Recurrence;
+use reminder::scheduler::Algorithm;
+use reminder::storage::Storage;
+use reminder::{audit, clock, dates, duration, lock, undo, Error, NewReminder, Priority, ReminderStore, Result};
+
+/// Largest request body accepted.
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// The web UI, served from `/` and talking to the API below it.
+const ASSETS: [(&str, &str, &[u8]); 3] = [
+    ("/", "text/html; charset=utf-8", include_bytes!("web/index.html")),
+    ("/app.js", "text/javascript; charset=utf-8", include_bytes!("web/app.js")),
+    ("/style.css", "text/css; charset=utf-8", include_bytes!("web/style.css")),
+];
+
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json(status: u16, body: Value) -> Self {
+        Reply { status, content_type: "application/json", body: body.to_string().into_bytes() }
+    }
+}
+
+/// Body of `POST /reminders`.
+#[derive(Deserialize)]
+struct AddRequest {
+    content: String,
+    answer: Option<String>,
+    algorithm: Option<Algorithm>,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// When the first review is due, in any form `add --due` accepts.
+    due: Option<String>,
+    #[serde(default)]
+    priority: Priority,
+    deck: Option<String>,
+    /// Repeat rule, as for `add --recur`.
+    recur: Option<String>,
+    notes: Option<String>,
+}
+
+/// Body of `POST /reminders/{id}/review`.
+#[derive(Deserialize, Default)]
+struct ReviewRequest {
+    grade: Option<u8>,
+}
+
+/// Body of `POST /reminders/{id}/snooze`.
+#[derive(Deserialize)]
+struct SnoozeRequest {
+    /// How long to snooze for, e.g. "2h" or "3d".
+    duration: String,
+}
+
+/// Serve the REST API and the web UI on `address` until the process is
+/// stopped. Each API request locks, loads and (when it changes something)
+/// saves the store, so the server can run alongside other `reminder` commands.
+pub fn run(storage: &mut dyn Storage, config: &Config, address: &str) -> Result<()> {
+    let server = Server::http(address)
+        .map_err(|e| Error::Invalid(format!("Failed to listen on {}: {}", address, e)))?;
+    println!("Serving the reminder web UI and API on http://{} (Ctrl+C to stop)", address);
+
+    for mut request in server.incoming_requests() {
+        let started = std::time::Instant::now();
+        let asset = ASSETS.iter().find(|(path, _, _)| *request.method() == Method::Get && request.url() == *path);
+        let reply = match asset {
+            Some((_, content_type, body)) => Reply { status: 200, content_type, body: body.to_vec() },
+            None => handle(storage, config, &mut request)
+                .unwrap_or_else(|e| Reply::json(error_status(&e), json!({ "error": e.to_string() }))),
+        };
+
+        tracing::info!(method = %request.method(), url = request.url(), status = reply.status, elapsed = ?started.elapsed(), "request");
+        let response = Response::from_data(reply.body)
+            .with_status_code(reply.status)
+            .with_header(Header::from_bytes("Content-Type", reply.content_type).expect("Header is valid"));
+        if let Err(e) = request.respond(response) {
+            tracing::warn!(error = %e, "failed to send the response");
+            eprintln!("Error: Failed to send response: {}", e);
+        }
+    }
+    Ok(())
+}
+
+fn error_status(error: &Error) -> u16 {
+    match error {
+        Error::NotFound(_) | Error::ArchivedNotFound(_) | Error::DeckNotFound(_) => 404,
+        Error::Invalid(_) | Error::Parse(_) => 400,
+        Error::Locked(_) => 503,
+        _ => 500,
+    }
+}
+
+fn read_body<T: for<'de> Deserialize<'de>>(request: &mut Request) -> Result<T> {
+    let mut body = String::new();
+    request.as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|e| Error::io("Failed to read request body", e))?;
+    let body = if body.trim().is_empty() { "{}" } else { &body };
+    serde_json::from_str(body).map_err(|e| Error::Parse(format!("Invalid request body: {}", e)))
+}
+
+fn handle(storage: &mut dyn Storage, config: &Config, request: &mut Request) -> Result<Reply> {
+    let method = request.method().clone();
+    let path = request.url().split('?').next().unwrap_or_default().trim_end_matches('/').to_string();
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    let parse_id = |id: &str| id.parse::<u32>().map_err(|_| Error::Parse(format!("Invalid ID '{}'", id)));
+
+    let _lock = lock::acquire()?;
+    let mut store = storage.load()?;
+    let before = store.clone();
+
+    let (status, body) = match (&method, segments.as_slice()) {
+        (Method::Get, ["reminders"]) => (200, json!(store.get_all_reminders())),
+        (Method::Get, ["reminders", id]) => {
+            let id = parse_id(id)?;
+            (200, json!(store.reminders.get(&id).ok_or(Error::NotFound(id))?))
+        }
+        (Method::Get, ["due"]) => {
+            let (due, _) = store.apply_daily_limit(store.get_due_reminders(), config);
+            (200, json!(due))
+        }
+        (Method::Post, ["reminders"]) => {
+            let add: AddRequest = read_body(request)?;
+            if add.content.trim().is_empty() {
+                return Err(Error::Invalid("Content cannot be empty".to_string()));
+            }
+            let due = add.due.map(|due| dates::parse_when(&due, clock::now())).transpose()?;
+            let recurrence = add.recur.map(|rule| Recurrence::parse(&rule)).transpose()?;
+            let id = store.add_reminder(NewReminder {
+                content: add.content,
+                answer: add.answer,
+                algorithm: add.algorithm,
+                tags: add.tags,
+                due,
+                priority: add.priority,
+                deck: add.deck,
+                recurrence,
+                notes: add.notes,
+            }, config);
+            save(storage, &store, &before, &method, &path)?;
+            (201, json!(store.reminders[&id]))
+        }
+        (Method::Post, ["reminders", id, "review"]) => {
+            let id = parse_id(id)?;
+            let review: ReviewRequest = read_body(request)?;
+            store.review_reminder(id, review.grade, config)?;
+            save(storage, &store, &before, &method, &path)?;
+            (200, json!(store.reminders[&id]))
+        }
+        (Method::Post, ["reminders", id, "snooze"]) => {
+            let id = parse_id(id)?;
+            let snooze: SnoozeRequest = read_body(request)?;
+            store.snooze_reminder(id, duration::parse_duration(&snooze.duration)?)?;
+            save(storage, &store, &before, &method, &path)?;
+            (200, json!(store.reminders[&id]))
+        }
+        (Method::Delete, ["reminders", id]) => {
+            let id = parse_id(id)?;
+            store.remove_reminder(id)?;
+            save(storage, &store, &before, &method, &path)?;
+            (200, json!({ "id": id, "removed": true }))
+        }
+        (_, ["reminders"] | ["reminders", _] | ["reminders", _, "review" | "snooze"] | ["due"]) => {
+            (405, json!({ "error": format!("Method {} not allowed on {}", method, path) }))
+        }
+        _ => (404, json!({ "error": format!("No such endpoint {}", path) })),
+    };
+
+    Ok(Reply::json(status, body))
+}
+
+fn save(storage: &mut dyn Storage, store: &ReminderStore, before: &ReminderStore, method: &Method, path: &str) -> Result<()> {
+    let command = format!("reminder serve {} {}", method, path);
+    undo::record(before, &command)?;
+    audit::record(before, store, &command)?;
+    storage.save(store)
+}
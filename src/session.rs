@@ -0,0 +1,168 @@
+// src/session.rs
+use std::io::{self, BufRead, Write};
+
+use reminder::config::Config;
+use reminder::scheduler::{self, Rating};
+use reminder::stats::Streak;
+use reminder::storage::Storage;
+use reminder::{dates, duration, Reminder, ReminderStore, Result};
+
+use crate::graph;
+use crate::markdown::ContentFormat;
+use crate::style;
+use crate::{print_review_result, save_store};
+
+/// Snooze duration used when the prompt is left empty.
+const DEFAULT_SNOOZE: &str = "1d";
+
+/// Walk through every due reminder that fits in today's review limit,
+/// prompting for what to do with each one. The store is saved after every
+/// change so quitting midway loses nothing.
+pub fn run(
+    store: &mut ReminderStore,
+    storage: &mut dyn Storage,
+    config: &Config,
+    deck: Option<&str>,
+    no_limit: bool,
+    format: ContentFormat,
+) -> Result<()> {
+    let due: Vec<&Reminder> = store.get_due_reminders()
+        .into_iter()
+        .filter(|r| r.in_deck(deck))
+        .collect();
+    let (due, deferred) = if no_limit { (due, 0) } else { store.apply_daily_limit(due, config) };
+    let due_ids: Vec<u32> = due.iter().map(|r| r.id).collect();
+
+    if deferred > 0 {
+        println!("{} due reminder(s) deferred to later days by the daily review limit", deferred);
+    }
+
+    if due_ids.is_empty() {
+        println!("No reminders due for review!");
+        return Ok(());
+    }
+
+    let total = due_ids.len();
+    let mut reviewed = 0;
+
+    for (index, id) in due_ids.into_iter().enumerate() {
+        let reminder = &store.reminders[&id];
+        println!("{}", "=".repeat(50));
+        println!("[{}/{}] ID: {} | Reviews: {}", index + 1, total, style::id(id), reminder.review_count);
+        println!("{}", format.apply(&reminder.content));
+        if let Some(related) = graph::related_line(store, id) {
+            println!("Related: {}", related);
+        }
+
+        if let Some(answer) = &reminder.answer {
+            if prompt("Press Enter to reveal the answer...").is_none() {
+                return finish(store, reviewed, total);
+            }
+            println!("{}", format.apply(answer));
+        }
+        println!("{}", "-".repeat(50));
+
+        loop {
+            let before = store.clone();
+            let Some(action) = prompt("[r]eviewed, [1] again, [2] hard, [3] good, [4] easy, [s]nooze, s[k]ip, [x] remove, [q]uit: ") else {
+                return finish(store, reviewed, total);
+            };
+
+            let result = match action.as_str() {
+                "r" | "reviewed" => {
+                    let grade = if !store.reminders[&id].is_graded() {
+                        None
+                    } else {
+                        match prompt_grade() {
+                            Some(grade) => grade,
+                            None => continue,
+                        }
+                    };
+                    store.review_reminder(id, grade, config).map(|()| {
+                        print_review_result(&store.reminders[&id]);
+                        reviewed += 1;
+                    })
+                }
+                key @ ("1" | "2" | "3" | "4") => {
+                    let rating = match key {
+                        "1" => Rating::Again,
+                        "2" => Rating::Hard,
+                        "3" => Rating::Good,
+                        _ => Rating::Easy,
+                    };
+                    store.review_reminder(id, Some(rating.grade()), config).map(|()| {
+                        print_review_result(&store.reminders[&id]);
+                        reviewed += 1;
+                    })
+                }
+                "s" | "snooze" => {
+                    let input = prompt(&format!("Snooze for [{}]: ", DEFAULT_SNOOZE)).unwrap_or_default();
+                    let input = if input.is_empty() { DEFAULT_SNOOZE.to_string() } else { input };
+                    duration::parse_duration(&input)
+                        .and_then(|duration| store.snooze_reminder(id, duration))
+                        .map(|()| println!("Reminder {} snoozed", id))
+                }
+                "k" | "skip" => Ok(()),
+                "x" | "remove" => store.remove_reminder(id)
+                    .map(|()| println!("Reminder {} moved to the trash", id)),
+                "q" | "quit" => return finish(store, reviewed, total),
+                _ => {
+                    println!("Unknown action '{}'", action);
+                    continue;
+                }
+            };
+
+            match result {
+                Ok(()) => {
+                    if action != "k" && action != "skip" {
+                        save_store(storage, store, &before)?;
+                    }
+                    break;
+                }
+                Err(e) => eprintln!("Error: {}", e),
+            }
+        }
+    }
+
+    finish(store, reviewed, total)
+}
+
+fn finish(store: &ReminderStore, reviewed: usize, total: usize) -> Result<()> {
+    println!("{}", "=".repeat(50));
+    println!("Session finished: reviewed {} of {} due reminder(s)", reviewed, total);
+
+    let streak = Streak::compute(&store.days, dates::today());
+    if streak.current > 0 {
+        println!("Streak: {} day(s) in a row with every due review done (longest {})", streak.current, streak.longest);
+    }
+    Ok(())
+}
+
+/// Ask for a 0-5 grade. Returns `Some(None)` for the default grade and `None`
+/// when the input could not be understood.
+fn prompt_grade() -> Option<Option<u8>> {
+    let input = prompt(&format!("Grade 0-5 [{}]: ", scheduler::DEFAULT_GRADE)).unwrap_or_default();
+    if input.is_empty() {
+        return Some(None);
+    }
+
+    match input.parse::<u8>() {
+        Ok(grade) if grade <= 5 => Some(Some(grade)),
+        _ => {
+            println!("Grade must be a number from 0 to 5");
+            None
+        }
+    }
+}
+
+/// Print a prompt and read one trimmed line, or `None` at end of input.
+fn prompt(message: &str) -> Option<String> {
+    print!("{}", message);
+    io::stdout().flush().ok()?;
+
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim().to_lowercase()),
+    }
+}
@@ -0,0 +1,204 @@
+// src/settings.rs
+//! Single settings of the config file, for `reminder config`. Settings are
+//! named by dotted keys such as `email.smtp_host` or `decks.work.intervals`;
+//! changing one leaves the rest of the file, comments included, as it was.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::ErrorKind;
+use toml_edit::{DocumentMut, Item, Table, TableLike};
+
+use crate::config::{self, Config};
+use crate::error::{Error, Result};
+use crate::storage;
+
+/// Settings whose values are masked when shown.
+const SECRET_KEYS: [&str; 2] = ["password", "token"];
+
+/// A setting and the value in effect.
+#[derive(Serialize, Debug)]
+pub struct Setting {
+    pub key: String,
+    /// Null when the setting is unset and has no default
+    pub value: Value,
+    /// Whether the config file sets it, as opposed to it being the default
+    pub set: bool,
+}
+
+/// The text of the config file, empty when there is none yet.
+pub fn read_file() -> Result<String> {
+    let path = config::get_config_file_path();
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(Error::io(format!("Failed to read {}", path.display()), e)),
+    }
+}
+
+/// Check `text` as a config file and write it in place of the current one.
+/// A file that can't be parsed is refused; the warnings about the settings
+/// in it are returned.
+pub fn write_file(text: &str) -> Result<Vec<String>> {
+    let (_, warnings) = Config::parse(text)
+        .map_err(|e| Error::Parse(format!("Could not parse the config file: {}", e)))?;
+    let path = config::get_config_file_path();
+    storage::create_parent_dir(&path)?;
+    storage::write_atomic(&path, text.as_bytes())?;
+    Ok(warnings)
+}
+
+/// Every setting in effect, sorted by key, with secrets masked. Tables of
+/// settings are flattened into their keys, lists such as `webhooks` are
+/// single values.
+pub fn list() -> Result<Vec<Setting>> {
+    let text = read_file()?;
+    let config = Config::parse(&text).map(|(config, _)| config).unwrap_or_default();
+    let document: DocumentMut = parse_document(&text)?;
+    let value = serde_json::to_value(&config)
+        .map_err(|e| Error::Parse(format!("Failed to serialize the config: {}", e)))?;
+
+    let mut settings = Vec::new();
+    flatten("", value, &mut settings);
+    for setting in &mut settings {
+        setting.set = lookup(document.as_table(), &setting.key).is_some();
+        let name = setting.key.rsplit('.').next().unwrap_or_default();
+        if SECRET_KEYS.contains(&name) && !setting.value.is_null() {
+            setting.value = Value::String("********".to_string());
+        }
+    }
+    Ok(settings)
+}
+
+/// The setting `key`, or all settings in the table `key`.
+pub fn get(key: &str) -> Result<Vec<Setting>> {
+    let prefix = format!("{}.", key);
+    let settings: Vec<Setting> = list()?
+        .into_iter()
+        .filter(|setting| setting.key == key || setting.key.starts_with(&prefix))
+        .collect();
+    if settings.is_empty() {
+        return Err(unknown(key));
+    }
+    Ok(settings)
+}
+
+/// Set `key` to `raw`, which is read as a TOML value, e.g. `0.85`, `true` or
+/// `[1, 3, 7]`, and otherwise taken as text. Values the config would reject
+/// or replace by the default are refused, as are unknown keys.
+pub fn set(key: &str, raw: &str) -> Result<()> {
+    let text = read_file()?;
+    let mut document = parse_document(&text)?;
+    let mut value = raw.parse::<toml_edit::Value>()
+        .unwrap_or_else(|_| toml_edit::Value::from(raw));
+
+    let (path, name) = split_key(key)?;
+    let mut table: &mut dyn TableLike = document.as_table_mut();
+    for part in path {
+        let item = table.entry(part).or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        });
+        table = item.as_table_like_mut()
+            .ok_or_else(|| Error::Invalid(format!("'{}' isn't a table of settings", part)))?;
+    }
+    match table.get_mut(name) {
+        // Replaced in place, keeping the comments around it
+        Some(Item::Value(existing)) => {
+            *value.decor_mut() = existing.decor().clone();
+            *existing = value;
+        }
+        _ => {
+            table.insert(name, toml_edit::value(value));
+        }
+    }
+    save(key, &text, &document.to_string())
+}
+
+/// Remove `key` from the config file, so its default applies again. Returns
+/// false when the file didn't set it.
+pub fn unset(key: &str) -> Result<bool> {
+    let text = read_file()?;
+    let mut document = parse_document(&text)?;
+    let (path, name) = split_key(key)?;
+    let mut table: &mut dyn TableLike = document.as_table_mut();
+    for part in path {
+        match table.get_mut(part).and_then(Item::as_table_like_mut) {
+            Some(inner) => table = inner,
+            None => return Ok(false),
+        }
+    }
+    if table.remove(name).is_none() {
+        return Ok(false);
+    }
+    save(key, &text, &document.to_string())?;
+    Ok(true)
+}
+
+/// Write the changed file unless changing `key` brought up a problem the
+/// file didn't have before.
+fn save(key: &str, before: &str, after: &str) -> Result<()> {
+    let known = Config::parse(before).map(|(_, warnings)| warnings).unwrap_or_default();
+    let (_, warnings) = Config::parse(after).map_err(|e| {
+        let mut message = format!("Invalid value for {}: {}", key, e.message());
+        // Tables such as `email` can't be filled in a setting at a time
+        if let (true, Some((table, _))) = (e.message().starts_with("missing field"), key.rsplit_once('.')) {
+            message.push_str(&format!("; add the whole [{}] table with 'reminder config edit'", table));
+        }
+        Error::Invalid(message)
+    })?;
+    if let Some(warning) = warnings.iter().find(|warning| !known.contains(warning)) {
+        if warning.starts_with("Unknown setting") {
+            return Err(unknown(key));
+        }
+        // Without what loading would do instead, e.g. ", using the default"
+        let problem = warning.split(", ").next().unwrap_or(warning);
+        return Err(Error::Invalid(problem.to_string()));
+    }
+    write_file(after)?;
+    Ok(())
+}
+
+fn parse_document(text: &str) -> Result<DocumentMut> {
+    text.parse()
+        .map_err(|e| Error::Parse(format!("Could not parse the config file: {}", e)))
+}
+
+/// The tables leading to `key` and its last part.
+fn split_key(key: &str) -> Result<(Vec<&str>, &str)> {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err(unknown(key));
+    }
+    let name = parts.pop().unwrap_or_default();
+    Ok((parts, name))
+}
+
+fn unknown(key: &str) -> Error {
+    Error::Invalid(format!("Unknown setting '{}'; 'reminder config list' shows them all", key))
+}
+
+fn lookup<'a>(table: &'a dyn TableLike, key: &str) -> Option<&'a Item> {
+    let mut item = None;
+    let mut table = Some(table);
+    for part in key.split('.') {
+        item = table?.get(part);
+        table = item.and_then(Item::as_table_like);
+    }
+    item
+}
+
+fn flatten(prefix: &str, value: Value, settings: &mut Vec<Setting>) {
+    match value {
+        Value::Object(fields) if !fields.is_empty() => {
+            for (name, value) in fields {
+                let key = if prefix.is_empty() { name } else { format!("{}.{}", prefix, name) };
+                flatten(&key, value, settings);
+            }
+        }
+        // Empty tables such as `decks` have nothing to show
+        Value::Object(_) => {}
+        value => settings.push(Setting { key: prefix.to_string(), value, set: false }),
+    }
+}
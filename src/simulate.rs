@@ -0,0 +1,121 @@
+// src/simulate.rs
+//! Projection of the review workload, for `reminder simulate`: a copy of the
+//! store is run forward day by day, each reminder scheduled by its own
+//! algorithm, with every review that comes due done that day and rated as
+//! the `[simulate]` shares of the config say. Nothing is saved.
+
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::clock::{self, ManualClock};
+use crate::config::{Config, GradeShares};
+use crate::scheduler::{self, Rating};
+use crate::{dates, NewReminder, ReminderStore};
+
+/// Seed of the ratings, so the same store always projects the same way.
+const SEED: u64 = 0x5EED_2024;
+
+/// One simulated day.
+#[derive(Serialize)]
+pub struct SimulatedDay {
+    pub date: NaiveDate,
+    /// Reviews done, within `max_daily_reviews`
+    pub reviews: usize,
+    /// Of those, reviews rated "again"
+    pub forgotten: usize,
+    /// Reminders added
+    pub added: usize,
+}
+
+#[derive(Serialize)]
+pub struct Simulation {
+    pub days: Vec<SimulatedDay>,
+    /// Active reminders at the end, completed ones not counted
+    pub active: usize,
+    /// Reminders completed along the way
+    pub completed: usize,
+}
+
+impl Simulation {
+    pub fn total_reviews(&self) -> usize {
+        self.days.iter().map(|day| day.reviews).sum()
+    }
+
+    /// The day with the most reviews, the earliest of equal ones.
+    pub fn busiest_day(&self) -> Option<&SimulatedDay> {
+        self.days.iter().rev().max_by_key(|day| day.reviews)
+    }
+}
+
+/// Run `store` forward for `days` days from now, adding `new_per_day`
+/// reminders at the start of each.
+pub fn run(store: &ReminderStore, config: &Config, days: i64, new_per_day: usize) -> Simulation {
+    let mut store = store.clone();
+    let start = clock::now();
+    let simulated = ManualClock::new(start);
+    let mut ratings = Ratings::new(config.simulate);
+    let completed_before = store.reminders.values().filter(|reminder| reminder.completed).count();
+
+    let days = clock::with(Arc::new(simulated.clone()), || {
+        (0..days).map(|offset| {
+            simulated.set(start + Duration::days(offset));
+            for number in 0..new_per_day {
+                let content = format!("Simulated reminder {} of day {}", number + 1, offset + 1);
+                store.add_reminder(NewReminder { content, ..NewReminder::default() }, config);
+            }
+
+            let (due, _) = store.apply_daily_limit(store.get_due_reminders(), config);
+            let due: Vec<u32> = due.iter().map(|reminder| reminder.id).collect();
+            let mut forgotten = 0;
+            for id in &due {
+                let rating = ratings.next();
+                forgotten += usize::from(rating == Rating::Again);
+                // Intervals are capped, so only a start at the very end of
+                // representable time could keep a review from being scheduled
+                if let Some(reminder) = store.reminders.get_mut(id) {
+                    scheduler::review(reminder, Some(rating.grade()), config).ok();
+                }
+            }
+            SimulatedDay { date: dates::today(), reviews: due.len(), forgotten, added: new_per_day }
+        }).collect()
+    });
+
+    let completed = store.reminders.values().filter(|reminder| reminder.completed).count();
+    Simulation {
+        days,
+        active: store.reminders.len() - completed,
+        completed: completed - completed_before,
+    }
+}
+
+/// Ratings picked at random in the configured shares, from a fixed seed.
+struct Ratings {
+    shares: GradeShares,
+    state: u64,
+}
+
+impl Ratings {
+    fn new(shares: GradeShares) -> Self {
+        Ratings { shares, state: SEED }
+    }
+
+    fn next(&mut self) -> Rating {
+        // SplitMix64
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut x = self.state;
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^= x >> 31;
+        let GradeShares { again, hard, good, easy } = self.shares;
+        let mut pick = (x >> 11) as f64 / (1u64 << 53) as f64 * (again + hard + good + easy);
+
+        for (rating, share) in [(Rating::Again, again), (Rating::Hard, hard), (Rating::Good, good)] {
+            if pick < share {
+                return rating;
+            }
+            pick -= share;
+        }
+        Rating::Easy
+    }
+}
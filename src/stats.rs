@@ -0,0 +1,148 @@
+// src/stats.rs
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::dates;
+use crate::{DayRecord, ReminderStore};
+
+/// How many past days the review activity covers.
+pub const HISTORY_DAYS: i64 = 30;
+/// How many days ahead the upcoming load covers.
+pub const FORECAST_DAYS: i64 = 14;
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub total: usize,
+    pub active: usize,
+    pub completed: usize,
+    pub archived: usize,
+    pub due: usize,
+    /// Mean current interval of active reminders, in days
+    pub average_interval: f64,
+    /// Reviews done on each of the last `HISTORY_DAYS` days, oldest first
+    pub reviews_per_day: Vec<DayCount>,
+    /// Reviews coming due on each of the next `FORECAST_DAYS` days, with
+    /// overdue reminders counted today
+    pub upcoming_per_day: Vec<DayCount>,
+    pub tags: BTreeMap<String, TagStats>,
+    pub streak: Streak,
+}
+
+/// Runs of consecutive days on which every due review was done.
+#[derive(Serialize, Clone, Copy, Debug, Default)]
+pub struct Streak {
+    pub current: usize,
+    pub longest: usize,
+}
+
+impl Streak {
+    /// The current streak still counts if today isn't cleared yet, as long
+    /// as yesterday was.
+    pub fn compute(days: &BTreeMap<NaiveDate, DayRecord>, today: NaiveDate) -> Self {
+        let cleared = |date: NaiveDate| days.get(&date).is_some_and(|day| day.cleared);
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for (&date, _) in days.iter().filter(|(_, day)| day.cleared) {
+            run = if previous.is_some_and(|previous| previous + Duration::days(1) == date) { run + 1 } else { 1 };
+            longest = longest.max(run);
+            previous = Some(date);
+        }
+
+        let mut day = if cleared(today) { today } else { today - Duration::days(1) };
+        let mut current = 0;
+        while cleared(day) {
+            current += 1;
+            day -= Duration::days(1);
+        }
+
+        Streak { current, longest }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DayCount {
+    pub date: NaiveDate,
+    pub count: usize,
+}
+
+#[derive(Serialize, Default)]
+pub struct TagStats {
+    pub total: usize,
+    pub active: usize,
+    pub completed: usize,
+    pub reviews: usize,
+}
+
+impl Stats {
+    pub fn compute(store: &ReminderStore, now: DateTime<Utc>) -> Self {
+        let today = dates::day_of(now);
+        let reminders: Vec<_> = store.reminders.values().collect();
+        let active: Vec<_> = reminders.iter().filter(|r| !r.completed).collect();
+
+        let average_interval = if active.is_empty() {
+            0.0
+        } else {
+            active.iter().map(|r| r.interval as f64).sum::<f64>() / active.len() as f64
+        };
+
+        let first_day = today - Duration::days(HISTORY_DAYS - 1);
+        let mut reviews: BTreeMap<NaiveDate, usize> = (0..HISTORY_DAYS)
+            .map(|offset| (first_day + Duration::days(offset), 0))
+            .collect();
+        for event in store.reminders.values().chain(store.archived.values()).flat_map(|r| &r.history) {
+            if let Some(count) = reviews.get_mut(&dates::day_of(event.timestamp)) {
+                *count += 1;
+            }
+        }
+
+        let mut tags: BTreeMap<String, TagStats> = BTreeMap::new();
+        for reminder in &reminders {
+            for tag in &reminder.tags {
+                let stats = tags.entry(tag.clone()).or_default();
+                stats.total += 1;
+                if reminder.completed {
+                    stats.completed += 1;
+                } else {
+                    stats.active += 1;
+                }
+                stats.reviews += reminder.history.len();
+            }
+        }
+
+        Stats {
+            total: reminders.len(),
+            active: active.len(),
+            completed: reminders.len() - active.len(),
+            archived: store.archived.len(),
+            due: active.iter().filter(|r| r.is_due(now) && !store.is_blocked(r.id)).count(),
+            average_interval,
+            reviews_per_day: to_day_counts(reviews),
+            upcoming_per_day: forecast(store, now, FORECAST_DAYS),
+            tags,
+            streak: Streak::compute(&store.days, today),
+        }
+    }
+}
+
+/// How many active reminders come due on each of the next `days` days,
+/// starting today. Overdue reminders are counted today.
+pub fn forecast(store: &ReminderStore, now: DateTime<Utc>, days: i64) -> Vec<DayCount> {
+    let today = dates::day_of(now);
+    let mut upcoming: BTreeMap<NaiveDate, usize> = (0..days)
+        .map(|offset| (today + Duration::days(offset), 0))
+        .collect();
+    for reminder in store.reminders.values().filter(|r| r.is_scheduled()) {
+        let day = dates::day_of(reminder.next_review).max(today);
+        if let Some(count) = upcoming.get_mut(&day) {
+            *count += 1;
+        }
+    }
+    to_day_counts(upcoming)
+}
+
+fn to_day_counts(counts: BTreeMap<NaiveDate, usize>) -> Vec<DayCount> {
+    counts.into_iter().map(|(date, count)| DayCount { date, count }).collect()
+}
@@ -0,0 +1,61 @@
+// src/storage/migrations.rs
+//! Upgrades of stores saved in an older format. Each store records the
+//! format it was saved in as `version`; every step below takes the JSON of a
+//! store from one version to the next before it is read, so a field that
+//! changes shape is converted instead of making the whole store unreadable.
+//! Stores from a newer version are refused: reading them would drop the
+//! fields this version doesn't know the next time they are saved.
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::ReminderStore;
+
+/// Format version of the stores this version writes.
+pub const STORE_VERSION: u32 = 1;
+
+/// The step at index N upgrades a store of version N to version N + 1.
+const MIGRATIONS: [fn(&mut Value); STORE_VERSION as usize] = [
+    // Version 1 started recording the version; older stores differ in
+    // nothing else, as fields added since have defaults
+    |_| {},
+];
+
+/// Upgrade the JSON of a store to [`STORE_VERSION`], returning the version
+/// it was saved in.
+pub fn upgrade(store: &mut Value) -> Result<u32> {
+    let version = match store.get("version") {
+        None => 0,
+        Some(version) => version.as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| Error::Parse(format!("Invalid store version {}", version)))?,
+    };
+    if version > STORE_VERSION {
+        return Err(Error::Invalid(format!(
+            "The store was saved by a newer version of reminder (format {}, this one reads up to {}); \
+             upgrade reminder to use it",
+            version, STORE_VERSION
+        )));
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(store);
+    }
+    if let Some(fields) = store.as_object_mut() {
+        fields.insert("version".to_string(), STORE_VERSION.into());
+    }
+    if version < STORE_VERSION {
+        tracing::info!(from = version, to = STORE_VERSION, "upgraded store format");
+    }
+    Ok(version)
+}
+
+/// Deserialize a store kept inside another file, such as the snapshots of
+/// the undo journal, upgrading it like a store read on its own; for
+/// `#[serde(deserialize_with)]`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<ReminderStore, D::Error> {
+    let mut value = Value::deserialize(deserializer)?;
+    upgrade(&mut value).map_err(serde::de::Error::custom)?;
+    serde_json::from_value(value).map_err(serde::de::Error::custom)
+}
@@ -0,0 +1,556 @@
+// src/storage/mod.rs
+pub mod migrations;
+mod recovery;
+mod webdav;
+
+use chrono::Utc;
+use clap::ValueEnum;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::config::Config;
+use crate::crypto;
+use crate::error::{Error, Result};
+use crate::{profile, ReminderStore};
+
+pub use webdav::WebdavStorage;
+
+/// Store file given with `--data-file` or `REMINDER_DATA_FILE`.
+static DATA_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Schema version of the SQLite database, tracked in `PRAGMA user_version`.
+const SQLITE_SCHEMA_VERSION: i32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    /// Single JSON file, rewritten on every change
+    #[default]
+    Json,
+    /// SQLite database, only changed reminders are written
+    Sqlite,
+    /// JSON file on a WebDAV server such as Nextcloud, cached locally
+    Webdav,
+}
+
+pub trait Storage {
+    fn load(&mut self) -> Result<ReminderStore>;
+    fn save(&mut self, store: &ReminderStore) -> Result<()>;
+
+    /// Backup files kept by this backend, newest first.
+    fn backups(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Replace the store with backup number `backup` (1 is the newest), or
+    /// with the newest readable backup if the store itself can't be read.
+    /// Returns the backup used, or `None` when there was nothing to repair.
+    fn restore_backup(&mut self, _backup: Option<usize>) -> Result<Option<usize>> {
+        Err(Error::Invalid("This storage backend does not keep backups".to_string()))
+    }
+
+    /// The unreadable store file `load` moved aside, with what can be
+    /// recovered of it, or `None` when there is none to repair.
+    fn recover(&self) -> Result<Option<(PathBuf, ReminderStore)>> {
+        Ok(None)
+    }
+}
+
+/// Open the storage backend of the given kind, set up from `config` (such as
+/// how many rotating backups to keep, where the backend supports them).
+pub fn open(kind: StorageKind, config: &Config) -> Box<dyn Storage> {
+    let file_path = get_data_file_path(kind);
+    tracing::debug!(backend = ?kind, path = %file_path.display(), "opening the store");
+    match kind {
+        StorageKind::Json => Box::new(JsonStorage::new(file_path, config.backups)),
+        StorageKind::Sqlite => Box::new(SqliteStorage::new(file_path)),
+        StorageKind::Webdav => Box::new(WebdavStorage::new(config.webdav.clone(), file_path)),
+    }
+}
+
+/// Data directory of the current profile, holding its store, lock, backups
+/// and undo journal.
+pub fn get_data_dir() -> PathBuf {
+    profile::data_dir(profile::current())
+}
+
+/// Use `path` as the store file for the rest of this invocation instead of
+/// the one in the data directory, as with `--data-file`. Only the first call
+/// has an effect.
+pub fn set_data_file(path: &Path) -> Result<()> {
+    let path = std::path::absolute(path)
+        .map_err(|e| Error::io(format!("Invalid data file {}", path.display()), e))?;
+    let _ = DATA_FILE.set(path);
+    Ok(())
+}
+
+pub fn has_custom_data_file() -> bool {
+    DATA_FILE.get().is_some()
+}
+
+/// The file holding the store of the given backend (the local cache for
+/// WebDAV).
+pub fn get_data_file_path(kind: StorageKind) -> PathBuf {
+    if let Some(path) = DATA_FILE.get() {
+        return path.clone();
+    }
+    let file_name = match kind {
+        StorageKind::Json => "reminders.json",
+        StorageKind::Sqlite => "reminders.db",
+        StorageKind::Webdav => "webdav-cache.json",
+    };
+    get_data_dir().join(file_name)
+}
+
+/// Where a file kept alongside the store goes, such as the lock: named after
+/// a custom data file and next to it (`extension` replacing its own), or
+/// `file_name` in the data directory.
+pub fn companion_path(file_name: &str, extension: &str) -> PathBuf {
+    match DATA_FILE.get() {
+        Some(path) => path.with_extension(extension),
+        None => get_data_dir().join(file_name),
+    }
+}
+
+pub fn create_parent_dir(file_path: &Path) -> Result<()> {
+    // Create directory if it doesn't exist
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| Error::io(format!("Failed to create data directory {}", parent.display()), e))?;
+    }
+    Ok(())
+}
+
+/// Replace `file_path` with `content` without ever leaving a half-written
+/// file behind: the content goes to a temporary file next to it first, which
+/// is then renamed over the original.
+pub fn write_atomic(file_path: &Path, content: &[u8]) -> Result<()> {
+    create_parent_dir(file_path)?;
+
+    let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = file_path.with_file_name(format!(".{}.tmp", file_name));
+    let write_error = |e| Error::io(format!("Failed to write {}", file_path.display()), e);
+
+    let mut file = fs::File::create(&temp_path).map_err(write_error)?;
+    file.write_all(content).map_err(write_error)?;
+    file.sync_all().map_err(write_error)?;
+    drop(file);
+
+    fs::rename(&temp_path, file_path).map_err(write_error)
+}
+
+/// Read a JSON store, failing instead of falling back to an empty store.
+/// An encrypted store is decrypted with the passphrase of this invocation.
+pub fn read_json_store(file_path: &Path) -> Result<ReminderStore> {
+    let content = fs::read(file_path)
+        .map_err(|e| Error::io(format!("Failed to read {}", file_path.display()), e))?;
+
+    parse_store(&crypto::open(content)?, &file_path.display().to_string())
+}
+
+/// Read the JSON of a store from `source`, upgrading it from an older format
+/// first; see [`migrations`].
+pub fn parse_store(json: &[u8], source: &str) -> Result<ReminderStore> {
+    let mut value: serde_json::Value = serde_json::from_slice(json)
+        .map_err(|e| Error::Parse(format!("Could not parse {}: {}", source, e)))?;
+    migrations::upgrade(&mut value)?;
+    serde_json::from_value(value)
+        .map_err(|e| Error::Parse(format!("Could not parse {}: {}", source, e)))
+}
+
+/// Write a JSON store, encrypted if encryption is on.
+pub fn write_json_store(file_path: &Path, store: &ReminderStore) -> Result<()> {
+    let content = serde_json::to_string_pretty(store)
+        .map_err(|e| Error::Parse(format!("Failed to serialize reminders: {}", e)))?;
+    write_atomic(file_path, &crypto::seal(content.as_bytes())?)
+}
+
+pub struct JsonStorage {
+    file_path: PathBuf,
+    backups: usize,
+}
+
+impl JsonStorage {
+    pub fn new(file_path: PathBuf, backups: usize) -> Self {
+        JsonStorage { file_path, backups }
+    }
+
+    fn backup_path(&self, number: usize) -> PathBuf {
+        let file_name = self.file_path.file_name().unwrap_or_default().to_string_lossy();
+        self.file_path.with_file_name(format!("{}.bak.{}", file_name, number))
+    }
+
+    /// Prefix of the name of the store file once it is moved aside.
+    fn quarantine_prefix(&self) -> String {
+        format!("{}.corrupt-", self.file_path.file_name().unwrap_or_default().to_string_lossy())
+    }
+
+    /// The store file moved aside last, if any.
+    fn quarantined(&self) -> Option<PathBuf> {
+        let prefix = self.quarantine_prefix();
+        let entries = fs::read_dir(self.file_path.parent()?).ok()?;
+        // The timestamps in the names sort by time
+        entries.filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| entry.path())
+            .max()
+    }
+
+    /// Move the unreadable store file out of the way, e.g. to
+    /// `reminders.json.corrupt-20240301-093000`, so that no save replaces it.
+    fn quarantine(&self) -> Result<PathBuf> {
+        let name = format!("{}{}", self.quarantine_prefix(), Utc::now().format("%Y%m%d-%H%M%S"));
+        let mut path = self.file_path.with_file_name(&name);
+        // Never over one moved aside earlier; ".1" still sorts after it
+        for number in 1.. {
+            if !path.exists() {
+                break;
+            }
+            path = self.file_path.with_file_name(format!("{}.{}", name, number));
+        }
+        fs::rename(&self.file_path, &path)
+            .map_err(|e| Error::io(format!("Failed to move {} aside", self.file_path.display()), e))?;
+        Ok(path)
+    }
+
+    /// Shift `.bak.1..N` up by one, dropping the oldest, and copy the current
+    /// file to `.bak.1`.
+    fn rotate_backups(&self) -> Result<()> {
+        if self.backups == 0 || !self.file_path.exists() {
+            return Ok(());
+        }
+
+        let backup_error = |e| Error::io("Failed to rotate backups", e);
+        for number in (1..self.backups).rev() {
+            let from = self.backup_path(number);
+            if from.exists() {
+                fs::rename(&from, self.backup_path(number + 1)).map_err(backup_error)?;
+            }
+        }
+        fs::copy(&self.file_path, self.backup_path(1)).map_err(backup_error)?;
+        Ok(())
+    }
+}
+
+impl Storage for JsonStorage {
+    #[tracing::instrument(name = "load_json", skip_all, fields(path = %self.file_path.display()))]
+    fn load(&mut self) -> Result<ReminderStore> {
+        if !self.file_path.exists() {
+            // Until it is repaired, a store moved aside isn't replaced by
+            // an empty one
+            if let Some(path) = self.quarantined() {
+                return Err(Error::Corrupt(path));
+            }
+            tracing::debug!("no store file yet, starting empty");
+            return Ok(ReminderStore::default());
+        }
+        if crypto::is_encrypted_file(&self.file_path) {
+            // Saved changes stay encrypted
+            crypto::set_encrypting(true);
+        }
+
+        match read_json_store(&self.file_path) {
+            Err(Error::Parse(e)) => {
+                let path = self.quarantine()?;
+                tracing::error!(error = %e, quarantined = %path.display(), "unreadable store file");
+                eprintln!("Warning: {}", e);
+                Err(Error::Corrupt(path))
+            }
+            Ok(store) => {
+                tracing::debug!(reminders = store.reminders.len(), archived = store.archived.len(), "loaded");
+                Ok(store)
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "failed to load");
+                Err(e)
+            }
+        }
+    }
+
+    #[tracing::instrument(name = "save_json", skip_all, fields(path = %self.file_path.display()))]
+    fn save(&mut self, store: &ReminderStore) -> Result<()> {
+        self.rotate_backups()?;
+        write_json_store(&self.file_path, store)
+            .inspect(|()| tracing::debug!(reminders = store.reminders.len(), backups = self.backups, "saved"))
+            .inspect_err(|e| tracing::error!(error = %e, "failed to save"))
+    }
+
+    fn backups(&self) -> Vec<PathBuf> {
+        (1..=self.backups)
+            .map(|number| self.backup_path(number))
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    fn restore_backup(&mut self, backup: Option<usize>) -> Result<Option<usize>> {
+        let number = match backup {
+            Some(number) => {
+                let path = self.backup_path(number);
+                if !path.exists() {
+                    return Err(Error::Invalid(format!("Backup {} does not exist", path.display())));
+                }
+                read_json_store(&path)?;
+                number
+            }
+            None => {
+                let unreadable = match self.file_path.exists() {
+                    true => read_json_store(&self.file_path).is_err(),
+                    false => self.quarantined().is_some(),
+                };
+                if !unreadable {
+                    return Ok(None);
+                }
+                (1..=self.backups)
+                    .find(|number| read_json_store(&self.backup_path(*number)).is_ok())
+                    .ok_or_else(|| Error::Parse("No readable backup found".to_string()))?
+            }
+        };
+
+        // Copied straight into place so the backups themselves stay as they are
+        let content = fs::read(self.backup_path(number))
+            .map_err(|e| Error::io("Failed to read backup", e))?;
+        write_atomic(&self.file_path, &content)?;
+        Ok(Some(number))
+    }
+
+    fn recover(&self) -> Result<Option<(PathBuf, ReminderStore)>> {
+        let path = match self.quarantined() {
+            Some(path) if !self.file_path.exists() => path,
+            _ => return Ok(None),
+        };
+        if crypto::is_encrypted_file(&path) {
+            crypto::set_encrypting(true);
+        }
+        let content = fs::read(&path)
+            .map_err(|e| Error::io(format!("Failed to read {}", path.display()), e))?;
+        let store = recovery::recover(&crypto::open(content)?);
+        Ok(Some((path, store)))
+    }
+}
+
+/// Keeps the store in memory, for tests and programs embedding the library.
+/// It is held as JSON, so a save and the next load go through the same
+/// serialization and upgrades as a store file, without touching the disk.
+#[derive(Default)]
+pub struct MemoryStorage {
+    json: Option<Vec<u8>>,
+    saves: usize,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        MemoryStorage::default()
+    }
+
+    /// Start out with `store`, as if it had been saved.
+    pub fn with_store(store: &ReminderStore) -> Result<Self> {
+        let mut storage = MemoryStorage::new();
+        storage.save(store)?;
+        storage.saves = 0;
+        Ok(storage)
+    }
+
+    /// The store as last saved, as JSON; `None` before the first save.
+    pub fn json(&self) -> Option<&str> {
+        self.json.as_deref().map(|json| std::str::from_utf8(json).unwrap_or_default())
+    }
+
+    /// How many times the store was saved.
+    pub fn saves(&self) -> usize {
+        self.saves
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load(&mut self) -> Result<ReminderStore> {
+        match &self.json {
+            Some(json) => parse_store(json, "the store in memory"),
+            None => Ok(ReminderStore::default()),
+        }
+    }
+
+    fn save(&mut self, store: &ReminderStore) -> Result<()> {
+        let json = serde_json::to_vec_pretty(store)
+            .map_err(|e| Error::Parse(format!("Failed to serialize reminders: {}", e)))?;
+        self.json = Some(json);
+        self.saves += 1;
+        Ok(())
+    }
+}
+
+/// Stores each reminder as a JSON row keyed by ID, plus the remaining store
+/// fields (such as `next_id`) as a single metadata row. Rows are compared with
+/// what was loaded so a save only touches reminders that actually changed.
+pub struct SqliteStorage {
+    file_path: PathBuf,
+    conn: Option<Connection>,
+    loaded: HashMap<u32, String>,
+}
+
+impl SqliteStorage {
+    pub fn new(file_path: PathBuf) -> Self {
+        SqliteStorage {
+            file_path,
+            conn: None,
+            loaded: HashMap::new(),
+        }
+    }
+
+    fn connection(&mut self) -> Result<&mut Connection> {
+        if self.conn.is_none() {
+            create_parent_dir(&self.file_path)?;
+            let mut conn = Connection::open(&self.file_path)?;
+            migrate(&mut conn)?;
+            self.conn = Some(conn);
+        }
+        Ok(self.conn.as_mut().unwrap())
+    }
+
+    /// Pull in an existing `reminders.json` the first time the database is
+    /// created, renaming the JSON file so it isn't mistaken for live data.
+    fn import_json_if_new(&mut self) -> Result<()> {
+        if self.file_path.exists() {
+            return Ok(());
+        }
+
+        let json_path = self.file_path.with_extension("json");
+        if !json_path.exists() {
+            return Ok(());
+        }
+
+        let store = JsonStorage::new(json_path.clone(), 0).load()?;
+        self.save(&store)?;
+
+        let migrated_path = json_path.with_extension("json.migrated");
+        fs::rename(&json_path, &migrated_path)
+            .map_err(|e| Error::io(format!("Failed to rename {}", json_path.display()), e))?;
+        eprintln!(
+            "Migrated {} reminders from {} to {}",
+            store.reminders.len(),
+            json_path.display(),
+            self.file_path.display()
+        );
+        Ok(())
+    }
+}
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    if version < 1 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS reminders (
+                 id INTEGER PRIMARY KEY,
+                 data TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS meta (
+                 key TEXT PRIMARY KEY,
+                 value TEXT NOT NULL
+             );",
+        )?;
+    }
+
+    if version < SQLITE_SCHEMA_VERSION {
+        conn.pragma_update(None, "user_version", SQLITE_SCHEMA_VERSION)?;
+    }
+    Ok(())
+}
+
+impl Storage for SqliteStorage {
+    #[tracing::instrument(name = "load_sqlite", skip_all, fields(path = %self.file_path.display()))]
+    fn load(&mut self) -> Result<ReminderStore> {
+        self.import_json_if_new()?;
+
+        let conn = self.connection()?;
+
+        let meta: Option<String> = conn
+            .query_row("SELECT value FROM meta WHERE key = 'store'", [], |row| row.get(0))
+            .ok();
+
+        let mut stmt = conn.prepare("SELECT id, data FROM reminders")?;
+        let rows: Vec<(u32, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        drop(stmt);
+
+        let mut value: serde_json::Value = match meta {
+            Some(meta) => serde_json::from_str(&meta)
+                .map_err(|e| Error::Parse(format!("Failed to parse store metadata: {}", e)))?,
+            None => serde_json::to_value(ReminderStore::default())
+                .map_err(|e| Error::Parse(format!("Failed to serialize reminders: {}", e)))?,
+        };
+
+        // Unparseable rows are left out of `loaded` so saving never deletes them
+        let mut reminders = serde_json::Map::new();
+        self.loaded.clear();
+        for (id, data) in rows {
+            match serde_json::from_str(&data) {
+                Ok(reminder) => {
+                    reminders.insert(id.to_string(), reminder);
+                    self.loaded.insert(id, data);
+                }
+                Err(e) => {
+                    tracing::warn!(id, error = %e, "unreadable reminder row");
+                    eprintln!("Warning: Could not parse reminder {}, skipping", id);
+                }
+            }
+        }
+        value["reminders"] = serde_json::Value::Object(reminders);
+        migrations::upgrade(&mut value)?;
+        tracing::debug!(rows = self.loaded.len(), "loaded");
+
+        // Saving an empty store in its place would delete every row
+        serde_json::from_value(value).map_err(|e| {
+            Error::Parse(format!("Could not parse the reminder database {}: {}", self.file_path.display(), e))
+        })
+    }
+
+    #[tracing::instrument(name = "save_sqlite", skip_all, fields(path = %self.file_path.display()))]
+    fn save(&mut self, store: &ReminderStore) -> Result<()> {
+        let serialize_error = |e: serde_json::Error| Error::Parse(format!("Failed to serialize reminders: {}", e));
+        let mut value = serde_json::to_value(store).map_err(serialize_error)?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("reminders");
+        }
+
+        let rows: HashMap<u32, String> = store.reminders.iter()
+            .map(|(id, reminder)| Ok((*id, serde_json::to_string(reminder).map_err(serialize_error)?)))
+            .collect::<Result<_>>()?;
+
+        let loaded = std::mem::take(&mut self.loaded);
+        let conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('store', ?1)",
+            params![value.to_string()],
+        )?;
+
+        let mut changed = 0;
+        for (id, data) in &rows {
+            if loaded.get(id) != Some(data) {
+                changed += 1;
+                tx.execute(
+                    "INSERT OR REPLACE INTO reminders (id, data) VALUES (?1, ?2)",
+                    params![id, data],
+                )?;
+            }
+        }
+
+        let mut deleted = 0;
+        for id in loaded.keys().filter(|id| !rows.contains_key(id)) {
+            deleted += 1;
+            tx.execute("DELETE FROM reminders WHERE id = ?1", params![id])?;
+        }
+
+        tx.commit()?;
+        tracing::debug!(rows = rows.len(), changed, deleted, "saved");
+        self.loaded = rows;
+        Ok(())
+    }
+}
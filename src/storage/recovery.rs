@@ -0,0 +1,112 @@
+// src/storage/recovery.rs
+//! Partial recovery of a store file that can't be read as a whole, for
+//! `reminder repair`. A file that is still valid JSON loses only the entries
+//! that don't fit; one that isn't, e.g. because it was cut off, is searched
+//! for the reminders it still holds.
+
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::sync::LazyLock;
+
+use super::migrations;
+use crate::{Reminder, ReminderStore, TrashedReminder};
+
+/// Start of a field of the store, as saved by [`super::write_json_store`]:
+/// top-level fields are indented by two spaces, and strings never hold a
+/// raw line break.
+static FIELD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\n  "(\w+)": "#).unwrap());
+
+/// What can be read of the store saved as `json`.
+pub fn recover(json: &[u8]) -> ReminderStore {
+    let text = String::from_utf8_lossy(json);
+    let mut value = serde_json::from_str(&text).unwrap_or_else(|_| Value::Object(scan(&text)));
+    // Newer formats are read for what this version knows of them
+    let _ = migrations::upgrade(&mut value);
+
+    let mut store = ReminderStore {
+        next_id: field(&value, "next_id"),
+        days: field(&value, "days"),
+        vacations: field(&value, "vacations"),
+        links: field(&value, "links"),
+        dependencies: field(&value, "dependencies"),
+        ..ReminderStore::default()
+    };
+    for reminder in entries::<Reminder>(&value, "reminders") {
+        store.reminders.insert(reminder.id, reminder);
+    }
+    for reminder in entries::<Reminder>(&value, "archived") {
+        store.archived.insert(reminder.id, reminder);
+    }
+    for trashed in entries::<TrashedReminder>(&value, "trash") {
+        store.trash.insert(trashed.reminder.id, trashed);
+    }
+
+    // IDs are never handed out twice, even if `next_id` was lost
+    let ids = store.reminders.keys().chain(store.archived.keys()).chain(store.trash.keys());
+    store.next_id = ids.map(|id| id + 1).fold(store.next_id, u32::max);
+    store
+}
+
+/// The field `name` of the store, or its default if it can't be read.
+fn field<T: DeserializeOwned + Default>(store: &Value, name: &str) -> T {
+    store.get(name)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// The entries of the map `name` of the store that can be read.
+fn entries<T: DeserializeOwned>(store: &Value, name: &str) -> Vec<T> {
+    store.get(name)
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|entries| entries.values())
+        .filter_map(|entry| serde_json::from_value(entry.clone()).ok())
+        .collect()
+}
+
+/// The fields of a store that isn't valid JSON any more: each field that is
+/// still whole, and of the maps of reminders that aren't, the entries that
+/// are.
+fn scan(text: &str) -> Map<String, Value> {
+    let starts: Vec<(usize, &str)> = FIELD.captures_iter(text)
+        .filter_map(|captures| Some((captures.get(0)?.end(), captures.get(1)?.as_str())))
+        .collect();
+
+    let mut fields = Map::new();
+    for (index, (start, name)) in starts.iter().enumerate() {
+        let end = starts.get(index + 1).map_or(text.len(), |(next, _)| *next);
+        let section = &text[*start..end];
+        if let Some(Ok(value)) = serde_json::Deserializer::from_str(section).into_iter::<Value>().next() {
+            fields.insert(name.to_string(), value);
+            continue;
+        }
+        let entries = match *name {
+            "reminders" | "archived" => objects::<Reminder>(section),
+            "trash" => objects::<TrashedReminder>(section),
+            _ => continue,
+        };
+        let entries = entries.into_iter().enumerate().map(|(index, entry)| (index.to_string(), entry));
+        fields.insert(name.to_string(), Value::Object(entries.collect()));
+    }
+    fields
+}
+
+/// Every object in `text` that reads as a `T`, skipping over the rest.
+fn objects<T: DeserializeOwned>(text: &str) -> Vec<Value> {
+    let mut objects = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = text[offset..].find('{') {
+        let start = offset + found;
+        let mut stream = serde_json::Deserializer::from_str(&text[start..]).into_iter::<Value>();
+        match stream.next() {
+            Some(Ok(value)) if serde_json::from_value::<T>(value.clone()).is_ok() => {
+                objects.push(value);
+                offset = start + stream.byte_offset();
+            }
+            // Not one, or cut off: one of the objects nested in it may be
+            _ => offset = start + 1,
+        }
+    }
+    objects
+}
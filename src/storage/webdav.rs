@@ -0,0 +1,268 @@
+// src/storage/webdav.rs
+use base64::Engine;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::{JsonStorage, Storage};
+use crate::config::WebdavConfig;
+use crate::error::{Error, Result};
+use crate::{merge, ReminderStore};
+
+/// Longest a single request to the server may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often a save is retried after another device saved in between.
+const MAX_SAVE_ATTEMPTS: usize = 3;
+
+/// What the server had for the store file.
+enum Fetched {
+    Store(Box<ReminderStore>, Option<String>),
+    Missing,
+    Unreachable(String),
+}
+
+/// How uploading the store went.
+enum Put {
+    Saved(Option<String>),
+    /// The file changed on the server since it was loaded.
+    Conflict,
+    Unreachable(String),
+}
+
+/// Keeps the store as a JSON file on a WebDAV server such as Nextcloud. A
+/// copy is cached locally so commands keep working while the
+/// server can't be reached (or with `offline = true`); changes made in the
+/// meantime are merged with the server's store by [`merge::merge_stores`]
+/// once it is back, as are changes another device saved since the store was
+/// loaded.
+pub struct WebdavStorage {
+    config: Option<WebdavConfig>,
+    cache: JsonStorage,
+    cache_path: PathBuf,
+    /// Exists while the cache has changes the server hasn't seen yet.
+    pending_path: PathBuf,
+    agent: ureq::Agent,
+    /// Whether the file exists on the server, and its ETag when the server
+    /// sent one, as last loaded or saved.
+    exists: bool,
+    etag: Option<String>,
+    /// Whether the server is left alone, because of the config or because
+    /// it couldn't be reached.
+    offline: bool,
+}
+
+impl WebdavStorage {
+    pub fn new(config: Option<WebdavConfig>, cache_path: PathBuf) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(REQUEST_TIMEOUT))
+            .http_status_as_error(false)
+            .build()
+            .into();
+        let mut pending_path = cache_path.clone().into_os_string();
+        pending_path.push(".pending");
+        WebdavStorage {
+            offline: config.as_ref().is_some_and(|config| config.offline),
+            config,
+            cache: JsonStorage::new(cache_path.clone(), 0),
+            pending_path: PathBuf::from(pending_path),
+            cache_path,
+            agent,
+            exists: false,
+            etag: None,
+        }
+    }
+
+    fn config(&self) -> Result<WebdavConfig> {
+        self.config.clone().ok_or_else(|| {
+            Error::Invalid("The WebDAV storage needs a [webdav] table with the url of the store file".to_string())
+        })
+    }
+
+    fn authorization(config: &WebdavConfig) -> Option<String> {
+        let username = config.username.as_deref()?;
+        let credentials = format!("{}:{}", username, config.password.as_deref().unwrap_or_default());
+        Some(format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(credentials)))
+    }
+
+    fn fetch(&self, config: &WebdavConfig) -> Result<Fetched> {
+        let mut request = self.agent.get(&config.url);
+        if let Some(authorization) = Self::authorization(config) {
+            request = request.header("Authorization", authorization);
+        }
+        let mut response = match request.call() {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!(url = %config.url, error = %e, "WebDAV server unreachable");
+                return Ok(Fetched::Unreachable(e.to_string()));
+            }
+        };
+
+        let status = response.status().as_u16();
+        tracing::debug!(url = %config.url, status, "fetched the store");
+        match status {
+            200 => {
+                let etag = etag(&response);
+                let content = response.body_mut()
+                    .read_to_string()
+                    .map_err(|e| Error::Remote(format!("Failed to download {}: {}", config.url, e)))?;
+                let store = super::parse_store(content.as_bytes(), &config.url)?;
+                Ok(Fetched::Store(Box::new(store), etag))
+            }
+            401 | 403 => Err(Error::Remote(format!(
+                "The WebDAV server refused access to {} (status {}); check the username and password",
+                config.url, status
+            ))),
+            404 => Ok(Fetched::Missing),
+            500.. => Ok(Fetched::Unreachable(format!("status {}", status))),
+            _ => Err(Error::Remote(format!("The WebDAV server refused to send {} (status {})", config.url, status))),
+        }
+    }
+
+    fn put(&self, config: &WebdavConfig, store: &ReminderStore) -> Result<Put> {
+        let content = serde_json::to_string_pretty(store)
+            .map_err(|e| Error::Parse(format!("Failed to serialize reminders: {}", e)))?;
+
+        let mut request = self.agent.put(&config.url).header("Content-Type", "application/json");
+        if let Some(authorization) = Self::authorization(config) {
+            request = request.header("Authorization", authorization);
+        }
+        // Only overwrite the version that was loaded
+        request = match (&self.etag, self.exists) {
+            (Some(etag), _) => request.header("If-Match", etag),
+            (None, false) => request.header("If-None-Match", "*"),
+            (None, true) => request,
+        };
+        let response = match request.send(content) {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!(url = %config.url, error = %e, "WebDAV server unreachable");
+                return Ok(Put::Unreachable(e.to_string()));
+            }
+        };
+
+        let status = response.status().as_u16();
+        tracing::debug!(url = %config.url, status, etag = ?self.etag, "uploaded the store");
+        match status {
+            200..=299 => Ok(Put::Saved(etag(&response))),
+            412 => Ok(Put::Conflict),
+            500.. => Ok(Put::Unreachable(format!("status {}", status))),
+            _ => Err(Error::Remote(format!("The WebDAV server refused to save {} (status {})", config.url, status))),
+        }
+    }
+
+    /// Refresh the cache with what the server has, without rewriting it when
+    /// nothing changed.
+    fn update_cache(&mut self, store: &ReminderStore) -> Result<()> {
+        let content = serde_json::to_string_pretty(store)
+            .map_err(|e| Error::Parse(format!("Failed to serialize reminders: {}", e)))?;
+        if fs::read_to_string(&self.cache_path).is_ok_and(|cached| cached == content) {
+            return Ok(());
+        }
+        self.cache.save(store)
+    }
+
+    /// Remember that the cache has changes to upload on the next save.
+    fn keep_pending(&self) -> Result<()> {
+        fs::write(&self.pending_path, "")
+            .map_err(|e| Error::io(format!("Failed to write {}", self.pending_path.display()), e))
+    }
+
+    fn upload_later(&self, reason: &str) -> Result<()> {
+        eprintln!("Warning: Could not reach the WebDAV server ({}); the change is kept in the local cache \
+                   and uploaded next time", reason);
+        self.keep_pending()
+    }
+}
+
+fn etag(response: &ureq::http::Response<ureq::Body>) -> Option<String> {
+    response.headers()
+        .get("ETag")
+        .and_then(|etag| etag.to_str().ok())
+        .map(str::to_string)
+}
+
+impl Storage for WebdavStorage {
+    #[tracing::instrument(name = "load_webdav", skip_all, fields(offline = self.offline))]
+    fn load(&mut self) -> Result<ReminderStore> {
+        let config = self.config()?;
+        if self.offline {
+            return self.cache.load();
+        }
+
+        match self.fetch(&config)? {
+            Fetched::Store(remote, etag) => {
+                self.exists = true;
+                self.etag = etag;
+                let store = if self.pending_path.exists() {
+                    let mut store = self.cache.load()?;
+                    merge::merge_stores(&mut store, &remote);
+                    store
+                } else {
+                    *remote
+                };
+                self.update_cache(&store)?;
+                Ok(store)
+            }
+            Fetched::Missing => {
+                // A new file on the server: whatever is cached goes up with
+                // the next save
+                self.exists = false;
+                self.etag = None;
+                self.cache.load()
+            }
+            Fetched::Unreachable(reason) => {
+                eprintln!("Warning: Could not reach the WebDAV server ({}), working from the local cache", reason);
+                self.offline = true;
+                self.cache.load()
+            }
+        }
+    }
+
+    #[tracing::instrument(name = "save_webdav", skip_all, fields(offline = self.offline))]
+    fn save(&mut self, store: &ReminderStore) -> Result<()> {
+        let config = self.config()?;
+        self.cache.save(store)?;
+        if self.offline {
+            return self.keep_pending();
+        }
+
+        let mut store = store.clone();
+        for _ in 0..MAX_SAVE_ATTEMPTS {
+            match self.put(&config, &store)? {
+                Put::Saved(etag) => {
+                    self.exists = true;
+                    self.etag = etag;
+                    if self.pending_path.exists() {
+                        fs::remove_file(&self.pending_path)
+                            .map_err(|e| Error::io(format!("Failed to remove {}", self.pending_path.display()), e))?;
+                    }
+                    return Ok(());
+                }
+                Put::Unreachable(reason) => return self.upload_later(&reason),
+                // Another device saved since loading: merge with its version
+                Put::Conflict => match self.fetch(&config)? {
+                    Fetched::Store(remote, etag) => {
+                        tracing::info!(url = %config.url, "changed on the server since loading, merging");
+                        merge::merge_stores(&mut store, &remote);
+                        self.exists = true;
+                        self.etag = etag;
+                        self.cache.save(&store)?;
+                    }
+                    Fetched::Missing => {
+                        self.exists = false;
+                        self.etag = None;
+                    }
+                    Fetched::Unreachable(reason) => return self.upload_later(&reason),
+                },
+            }
+        }
+        Err(Error::Remote(format!("{} kept changing on the WebDAV server while saving; try again", config.url)))
+    }
+
+    /// An unreadable cache is recovered like a local store; saving the
+    /// recovered store merges it with the server's
+    fn recover(&self) -> Result<Option<(PathBuf, ReminderStore)>> {
+        self.cache.recover()
+    }
+}
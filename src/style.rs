@@ -0,0 +1,123 @@
+// src/style.rs
+use colored::{Color, ColoredString, Colorize};
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+use reminder::config::{ColorChoice, Config};
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Colors used for each kind of output.
+pub struct Theme {
+    pub id: Color,
+    pub due: Color,
+    pub upcoming: Color,
+    pub completed: Color,
+    pub tags: Color,
+    pub priority: Color,
+}
+
+impl Theme {
+    /// Built-in theme by name, `None` for an unknown name.
+    fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Theme {
+                id: Color::BrightCyan,
+                due: Color::BrightRed,
+                upcoming: Color::BrightYellow,
+                completed: Color::BrightGreen,
+                tags: Color::BrightMagenta,
+                priority: Color::BrightYellow,
+            }),
+            "light" => Some(Theme {
+                id: Color::Blue,
+                due: Color::Red,
+                upcoming: Color::Yellow,
+                completed: Color::Green,
+                tags: Color::Magenta,
+                priority: Color::Red,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The configured theme with the `[colors]` overrides applied. Unknown
+    /// names are reported and fall back to the default colors.
+    fn from_config(config: &Config) -> Self {
+        let mut theme = Theme::named(&config.theme).unwrap_or_else(|| {
+            eprintln!("Warning: Unknown theme '{}', using the default one", config.theme);
+            Theme::named("default").expect("Default theme exists")
+        });
+
+        let colors = &config.colors;
+        let overrides = [
+            (&colors.id, &mut theme.id),
+            (&colors.due, &mut theme.due),
+            (&colors.upcoming, &mut theme.upcoming),
+            (&colors.completed, &mut theme.completed),
+            (&colors.tags, &mut theme.tags),
+            (&colors.priority, &mut theme.priority),
+        ];
+        for (name, color) in overrides {
+            let Some(name) = name else { continue };
+            match name.parse() {
+                Ok(parsed) => *color = parsed,
+                Err(_) => eprintln!("Warning: Unknown color '{}' in config, using the theme's", name),
+            }
+        }
+
+        theme
+    }
+}
+
+/// Decide whether to color the output and load the theme. Returns whether
+/// color is enabled.
+pub fn init(choice: ColorChoice, config: &Config) -> bool {
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+    };
+    colored::control::set_override(enabled);
+    let _ = THEME.set(Theme::from_config(config));
+    enabled
+}
+
+fn theme() -> &'static Theme {
+    THEME.get_or_init(|| Theme::named("default").expect("Default theme exists"))
+}
+
+pub fn id(text: impl ToString) -> ColoredString {
+    text.to_string().color(theme().id).bold()
+}
+
+pub fn due(text: &str) -> ColoredString {
+    text.color(theme().due)
+}
+
+pub fn upcoming(text: &str) -> ColoredString {
+    text.color(theme().upcoming)
+}
+
+pub fn completed(text: &str) -> ColoredString {
+    text.color(theme().completed)
+}
+
+pub fn tags(text: &str) -> ColoredString {
+    text.color(theme().tags)
+}
+
+pub fn priority(text: impl ToString) -> ColoredString {
+    text.to_string().color(theme().priority)
+}
+
+/// A line a diff removes.
+pub fn removed(text: &str) -> ColoredString {
+    text.red()
+}
+
+/// A line a diff adds.
+pub fn added(text: &str) -> ColoredString {
+    text.green()
+}
@@ -0,0 +1,192 @@
+// src/sync.rs
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use reminder::config::SyncConfig;
+use reminder::merge::{self, MergeSummary};
+use reminder::storage::{self, Storage};
+use reminder::{clock, crypto, dates, Error, ReminderStore, Result};
+
+/// The only files tracked in the data directory; backups, the undo history
+/// and the lock file stay local.
+const GITIGNORE: &str = "*\n!.gitignore\n!reminders.json\n";
+const STORE_FILE: &str = "reminders.json";
+const REMOTE: &str = "origin";
+
+/// What a sync did, from the local store's point of view.
+#[derive(Serialize)]
+pub struct SyncSummary {
+    /// Whether local changes were committed.
+    pub committed: bool,
+    pub pulled: Pulled,
+    pub pushed: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Pulled {
+    /// The remote had nothing new.
+    Nothing,
+    /// The remote was strictly ahead and its store was taken as is.
+    FastForward,
+    /// Both sides changed, and the stores were merged.
+    Merged(MergeSummary),
+}
+
+struct Repo<'a> {
+    dir: &'a Path,
+}
+
+impl Repo<'_> {
+    fn command(&self, args: &[&str]) -> Result<Output> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(self.dir)
+            .args(args)
+            .output()
+            .map_err(|e| Error::io("Failed to run git", e))?;
+        // Remote URLs may hold credentials
+        let shown: Vec<&str> = args.iter().map(|arg| if arg.contains("://") { "<url>" } else { arg }).collect();
+        tracing::debug!(args = ?shown, status = %output.status, "ran git");
+        Ok(output)
+    }
+
+    /// Run git, failing with its error output when it exits unsuccessfully.
+    /// Returns the output as is, e.g. the encrypted store for `git show`.
+    fn run_raw(&self, args: &[&str]) -> Result<Vec<u8>> {
+        let output = self.command(args)?;
+        if !output.status.success() {
+            return Err(Error::Invalid(format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(output.stdout)
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String> {
+        self.run_raw(args).map(|output| String::from_utf8_lossy(&output).into_owned())
+    }
+
+    /// Run git for its exit status alone, e.g. for `merge-base --is-ancestor`.
+    fn check(&self, args: &[&str]) -> Result<bool> {
+        Ok(self.command(args)?.status.success())
+    }
+
+    /// Run a git command that creates commits, as a "reminder" identity when
+    /// the user has no git identity configured.
+    fn run_as_committer(&self, args: &[&str]) -> Result<String> {
+        let mut with_identity = Vec::new();
+        if !self.check(&["config", "user.email"])? {
+            with_identity.extend(["-c", "user.name=reminder", "-c", "user.email=reminder@localhost"]);
+        }
+        with_identity.extend(args);
+        self.run(&with_identity)
+    }
+
+    fn commit(&self, message: &str) -> Result<()> {
+        self.run_as_committer(&["commit", "--quiet", "--no-verify", "-m", message]).map(drop)
+    }
+}
+
+/// Commit the data directory to git, bring in what was pushed to the
+/// configured remote since the last sync, and push the result. When both
+/// sides changed, the stores are merged with [`merge::merge_stores`]: the
+/// union of reminders is kept and the latest review wins for each one.
+///
+/// The store is expected to be locked and loaded; it is replaced with the
+/// synced store.
+pub fn run(storage: &mut dyn Storage, store: &mut ReminderStore, data_dir: &Path, config: &SyncConfig) -> Result<SyncSummary> {
+    let repo = Repo { dir: data_dir };
+    init(&repo, data_dir, config)?;
+
+    // The .gitignore makes sure there is a first commit even before anything
+    // was stored, so there always is a HEAD to merge into
+    repo.run(&["add", ".gitignore"])?;
+    if data_dir.join(STORE_FILE).exists() {
+        repo.run(&["add", STORE_FILE])?;
+    }
+    let committed = !repo.check(&["diff", "--cached", "--quiet"])?;
+    if committed {
+        repo.commit(&format!("Sync reminders at {}", dates::local(clock::now()).format("%Y-%m-%d %H:%M")))?;
+    }
+
+    let remote_is_empty = remote_is_empty(&repo, config)?;
+    let pulled = if remote_is_empty { Pulled::Nothing } else { pull(&repo, storage, store, config)? };
+
+    let pushed = remote_is_empty || !repo.check(&["merge-base", "--is-ancestor", "HEAD", "FETCH_HEAD"])?;
+    if pushed {
+        repo.run(&["push", "--quiet", REMOTE, &format!("HEAD:refs/heads/{}", config.branch)])?;
+    }
+
+    *store = storage.load()?;
+    tracing::info!(committed, pulled = ?pulled, pushed, branch = %config.branch, "synced");
+    Ok(SyncSummary { committed, pulled, pushed })
+}
+
+/// Turn the data directory into a git repository for the remote, if it
+/// isn't one.
+fn init(repo: &Repo, data_dir: &Path, config: &SyncConfig) -> Result<()> {
+    if !data_dir.join(".git").exists() {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| Error::io(format!("Failed to create data directory {}", data_dir.display()), e))?;
+        repo.run(&["init", "--quiet", "--initial-branch", &config.branch])?;
+        fs::write(data_dir.join(".gitignore"), GITIGNORE)
+            .map_err(|e| Error::io("Failed to write .gitignore", e))?;
+    }
+
+    if repo.check(&["remote", "get-url", REMOTE])? {
+        repo.run(&["remote", "set-url", REMOTE, &config.remote])?;
+    } else {
+        repo.run(&["remote", "add", REMOTE, &config.remote])?;
+    }
+    Ok(())
+}
+
+/// Whether the remote branch doesn't exist yet, so there is nothing to
+/// fetch and everything to push.
+fn remote_is_empty(repo: &Repo, config: &SyncConfig) -> Result<bool> {
+    let output = repo.command(&["ls-remote", "--exit-code", "--heads", REMOTE, &config.branch])?;
+    match output.status.code() {
+        Some(0) => Ok(false),
+        Some(2) => Ok(true),
+        _ => Err(Error::Invalid(format!(
+            "Failed to reach remote {}: {}",
+            config.remote,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))),
+    }
+}
+
+fn pull(repo: &Repo, storage: &mut dyn Storage, store: &ReminderStore, config: &SyncConfig) -> Result<Pulled> {
+    repo.run(&["fetch", "--quiet", REMOTE, &config.branch])?;
+    if repo.check(&["merge-base", "--is-ancestor", "FETCH_HEAD", "HEAD"])? {
+        return Ok(Pulled::Nothing);
+    }
+    if repo.check(&["merge-base", "--is-ancestor", "HEAD", "FETCH_HEAD"])? {
+        repo.run(&["merge", "--quiet", "--ff-only", "FETCH_HEAD"])?;
+        return Ok(Pulled::FastForward);
+    }
+
+    let remote_file = format!("FETCH_HEAD:{}", STORE_FILE);
+    let remote = if repo.check(&["cat-file", "-e", &remote_file])? {
+        let content = crypto::open(repo.run_raw(&["show", &remote_file])?)?;
+        storage::parse_store(&content, &format!("the remote {}", STORE_FILE))?
+    } else {
+        ReminderStore::default()
+    };
+    let mut merged = store.clone();
+    let summary = merge::merge_stores(&mut merged, &remote);
+
+    // Record the merge in git but write the merged store ourselves, instead
+    // of letting git merge the JSON line by line. Machines that started
+    // syncing separately have unrelated histories at first.
+    repo.run_as_committer(&["merge", "--quiet", "--no-commit", "--allow-unrelated-histories", "--strategy", "ours", "FETCH_HEAD"])?;
+    storage.save(&merged)?;
+    repo.run(&["add", STORE_FILE])?;
+    repo.commit(&format!("Merge reminders from {}/{}", REMOTE, config.branch))?;
+    Ok(Pulled::Merged(summary))
+}
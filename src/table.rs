@@ -0,0 +1,175 @@
+// src/table.rs
+use clap::ValueEnum;
+use colored::{ColoredString, Colorize};
+use std::io::{self, IsTerminal};
+
+use reminder::{clock, dates, get_trimmed_content, Reminder};
+
+use crate::{format_duration_until, pager, style};
+
+/// Narrowest the content and tags columns get when fitting the table to the
+/// terminal.
+const MIN_FLEXIBLE_WIDTH: usize = 10;
+const COLUMN_GAP: &str = "  ";
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    Id,
+    Status,
+    Reviews,
+    Due,
+    Priority,
+    Deck,
+    Tags,
+    Content,
+}
+
+/// Columns shown by `list --table` when `--columns` isn't given.
+pub const DEFAULT_COLUMNS: [Column; 6] =
+    [Column::Id, Column::Status, Column::Reviews, Column::Due, Column::Tags, Column::Content];
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Id => "ID",
+            Column::Status => "STATUS",
+            Column::Reviews => "REVIEWS",
+            Column::Due => "DUE",
+            Column::Priority => "PRIORITY",
+            Column::Deck => "DECK",
+            Column::Tags => "TAGS",
+            Column::Content => "CONTENT",
+        }
+    }
+
+    /// Content and tags are cut to make the table fit; the other columns are
+    /// short and always shown in full.
+    fn is_flexible(self) -> bool {
+        matches!(self, Column::Tags | Column::Content)
+    }
+
+    fn cell(self, reminder: &Reminder, trim: Option<usize>) -> String {
+        match self {
+            Column::Id => reminder.id.to_string(),
+            Column::Status => status(reminder).to_string(),
+            Column::Reviews => reminder.review_count.to_string(),
+            Column::Due if !reminder.is_scheduled() => "-".to_string(),
+            Column::Due => format_duration_until(reminder.next_review),
+            Column::Priority => reminder.priority.to_string(),
+            Column::Deck => reminder.deck.clone().unwrap_or_default(),
+            Column::Tags => reminder.tags.join(", "),
+            Column::Content => {
+                let content = reminder.content.split_whitespace().collect::<Vec<_>>().join(" ");
+                get_trimmed_content(&content, trim)
+            }
+        }
+    }
+
+    /// Color a cell that has already been padded to its column width.
+    fn colorize(self, reminder: &Reminder, cell: &str) -> ColoredString {
+        match self {
+            Column::Id => style::id(cell),
+            Column::Status | Column::Due => match status(reminder) {
+                "completed" => style::completed(cell),
+                "due" => style::due(cell),
+                _ => style::upcoming(cell),
+            },
+            Column::Priority => style::priority(cell),
+            Column::Tags => style::tags(cell),
+            _ => cell.normal(),
+        }
+    }
+}
+
+fn status(reminder: &Reminder) -> &'static str {
+    if reminder.completed {
+        "completed"
+    } else if reminder.paused_at.is_some() {
+        "paused"
+    } else if dates::is_due(reminder.next_review, clock::now()) {
+        "due"
+    } else {
+        "active"
+    }
+}
+
+/// Width of the terminal, or `None` when not printing to one.
+fn terminal_width() -> Option<usize> {
+    if let Some(width) = pager::paged_width() {
+        return Some(width);
+    }
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_size::terminal_size().map(|(width, _)| width.0 as usize)
+}
+
+/// Print reminders as an aligned table with one row per reminder, shrinking
+/// the content and tags columns to fit the terminal.
+pub fn print_table(reminders: &[&Reminder], columns: &[Column], trim: Option<usize>) {
+    let rows: Vec<Vec<String>> = reminders.iter()
+        .map(|reminder| columns.iter().map(|column| column.cell(reminder, trim)).collect())
+        .collect();
+
+    let mut widths: Vec<usize> = columns.iter().enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .chain([column.header().len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    if let Some(available) = terminal_width() {
+        fit_widths(&mut widths, columns, available);
+    }
+
+    let header: Vec<String> = columns.iter().zip(&widths)
+        .map(|(column, width)| pad(column.header(), *width))
+        .collect();
+    println!("{}", header.join(COLUMN_GAP).trim_end().bold());
+
+    for (reminder, row) in reminders.iter().zip(rows) {
+        let cells: Vec<String> = columns.iter().zip(&widths).zip(row)
+            .enumerate()
+            .map(|(index, ((column, width), cell))| {
+                // Padding the last column would only add trailing spaces
+                let cell = if index + 1 == columns.len() { fit(&cell, *width) } else { pad(&cell, *width) };
+                column.colorize(reminder, &cell).to_string()
+            })
+            .collect();
+        println!("{}", cells.join(COLUMN_GAP));
+    }
+}
+
+/// Shrink the flexible columns, widest first, until the table fits in
+/// `available` characters or they reach their minimum width.
+fn fit_widths(widths: &mut [usize], columns: &[Column], available: usize) {
+    let total = |widths: &[usize]| widths.iter().sum::<usize>() + COLUMN_GAP.len() * widths.len().saturating_sub(1);
+
+    while total(widths) > available {
+        let widest = columns.iter().enumerate()
+            .filter(|(index, column)| column.is_flexible() && widths[*index] > MIN_FLEXIBLE_WIDTH)
+            .max_by_key(|(index, _)| widths[*index])
+            .map(|(index, _)| index);
+        match widest {
+            Some(index) => widths[index] -= 1,
+            None => break,
+        }
+    }
+}
+
+/// Cut `text` to `width` characters, marking the cut with an ellipsis.
+fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!("{:<width$}", fit(text, width), width = width)
+}
@@ -0,0 +1,251 @@
+// src/telegram.rs
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::thread;
+use std::time::Instant;
+
+use reminder::config::{Config, TelegramConfig};
+use reminder::storage::Storage;
+use reminder::{audit, duration, lock, undo, Error, Reminder, ReminderStore, Result};
+
+use crate::format_duration_until;
+
+/// Longest a `getUpdates` request waits for new messages.
+const LONG_POLL_SECONDS: u64 = 30;
+/// Wait after a failed request before trying again.
+const ERROR_BACKOFF: std::time::Duration = std::time::Duration::from_secs(5);
+
+const HELP: &str = "Commands:\n\
+                    /due - list the reminders due now\n\
+                    /review ID [GRADE] - mark a reminder as reviewed\n\
+                    /snooze ID DURATION - postpone a reminder, e.g. /snooze 12 2d";
+
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    description: Option<String>,
+    result: Option<T>,
+}
+
+#[derive(Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    chat: Chat,
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+struct Bot {
+    agent: ureq::Agent,
+    config: TelegramConfig,
+}
+
+impl Bot {
+    fn new(config: TelegramConfig) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(std::time::Duration::from_secs(LONG_POLL_SECONDS + 10)))
+            .build()
+            .into();
+        Bot { agent, config }
+    }
+
+    fn call<T: for<'de> Deserialize<'de>>(&self, method: &str, body: serde_json::Value) -> Result<T> {
+        let url = format!("{}/bot{}/{}", self.config.api_url, self.config.token, method);
+        let api_error = |e: String| Error::Notify(format!("Telegram {} failed: {}", method, e));
+
+        let mut response = self.agent.post(&url)
+            .header("Content-Type", "application/json")
+            .send(body.to_string())
+            .map_err(|e| api_error(e.to_string()))?;
+        let text = response.body_mut().read_to_string().map_err(|e| api_error(e.to_string()))?;
+        let response: ApiResponse<T> = serde_json::from_str(&text).map_err(|e| api_error(e.to_string()))?;
+
+        match response.result {
+            Some(result) if response.ok => Ok(result),
+            _ => Err(api_error(response.description.unwrap_or_else(|| "no result".to_string()))),
+        }
+    }
+
+    fn send(&self, text: &str) -> Result<()> {
+        self.call::<serde_json::Value>("sendMessage", serde_json::json!({
+            "chat_id": self.config.chat_id,
+            "text": text,
+            "parse_mode": "HTML",
+        }))
+        .map(|_| ())
+    }
+
+    fn updates(&self, offset: i64, timeout: u64) -> Result<Vec<Update>> {
+        self.call("getUpdates", serde_json::json!({
+            "offset": offset,
+            "timeout": timeout,
+            "allowed_updates": ["message"],
+        }))
+    }
+}
+
+/// Push due reminders to the configured chat every `interval` and answer
+/// commands sent from it. Like the daemon, the store is only locked while a
+/// check or command runs.
+pub fn run(storage: &mut dyn Storage, config: &Config, interval: Duration) -> Result<()> {
+    let telegram = config.telegram.clone()
+        .ok_or_else(|| Error::Invalid("No [telegram] section in the config file".to_string()))?;
+    let bot = Bot::new(telegram);
+    let interval = interval.to_std().unwrap_or(std::time::Duration::from_secs(60));
+
+    // Skip messages sent while the bot wasn't running
+    let mut offset = bot.updates(-1, 0)?.last().map_or(0, |update| update.update_id + 1);
+    let mut notified: HashSet<(u32, DateTime<Utc>)> = HashSet::new();
+    let mut next_check = Instant::now();
+
+    println!("Telegram bot running, checking for due reminders every {} seconds (Ctrl+C to stop)",
+             interval.as_secs());
+
+    loop {
+        if Instant::now() >= next_check {
+            if let Err(e) = push_due(&bot, storage, &mut notified) {
+                tracing::error!(error = %e, "failed to push the due reminders");
+                eprintln!("Error: {}", e);
+            }
+            next_check = Instant::now() + interval;
+        }
+
+        let timeout = next_check.saturating_duration_since(Instant::now()).as_secs().min(LONG_POLL_SECONDS);
+        let updates = match bot.updates(offset, timeout) {
+            Ok(updates) => updates,
+            Err(e) => {
+                tracing::warn!(error = %e, retry_in = ?ERROR_BACKOFF, "failed to fetch updates");
+                eprintln!("Error: {}", e);
+                thread::sleep(ERROR_BACKOFF);
+                continue;
+            }
+        };
+
+        for update in updates {
+            offset = offset.max(update.update_id + 1);
+            let Some(message) = update.message else { continue };
+            if message.chat.id != bot.config.chat_id {
+                continue;
+            }
+            let Some(text) = message.text else { continue };
+            tracing::info!(command = text.trim(), "received a command");
+
+            let reply = handle_command(storage, config, text.trim()).unwrap_or_else(|e| format!("Error: {}", escape(&e.to_string())));
+            if let Err(e) = bot.send(&reply) {
+                eprintln!("Error: {}", e);
+            }
+        }
+    }
+}
+
+/// Send a message for each reminder that has become due since the last check.
+fn push_due(bot: &Bot, storage: &mut dyn Storage, notified: &mut HashSet<(u32, DateTime<Utc>)>) -> Result<()> {
+    let store = {
+        let _lock = lock::acquire()?;
+        storage.load()?
+    };
+    let due = store.get_due_reminders();
+
+    for reminder in &due {
+        if notified.contains(&(reminder.id, reminder.next_review)) {
+            continue;
+        }
+        bot.send(&format!(
+            "Reminder {} is due:\n{}\n\n/review {} · /snooze {} 1d",
+            reminder.id,
+            format_reminder(reminder),
+            reminder.id,
+            reminder.id
+        ))?;
+        notified.insert((reminder.id, reminder.next_review));
+    }
+
+    notified.retain(|(id, next_review)| due.iter().any(|r| r.id == *id && r.next_review == *next_review));
+    Ok(())
+}
+
+/// Run a command from the chat against the store and return the reply.
+fn handle_command(storage: &mut dyn Storage, config: &Config, text: &str) -> Result<String> {
+    let mut words = text.split_whitespace();
+    // Commands may be addressed to the bot in groups, as in /review@my_bot
+    let command = words.next().unwrap_or_default().split('@').next().unwrap_or_default();
+    let args: Vec<&str> = words.collect();
+    let parse_id = |arg: Option<&&str>| {
+        arg.ok_or_else(|| Error::Invalid("Missing reminder ID".to_string()))?
+            .parse::<u32>()
+            .map_err(|_| Error::Parse(format!("Invalid ID '{}'", arg.unwrap_or(&""))))
+    };
+
+    let _lock = lock::acquire()?;
+    let mut store = storage.load()?;
+    let before = store.clone();
+
+    let reply = match command {
+        "/due" => {
+            let due = store.get_due_reminders();
+            if due.is_empty() {
+                return Ok("No reminders due for review!".to_string());
+            }
+            due.iter()
+                .map(|r| format!("<b>{}</b> {}", r.id, escape(&r.content)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        "/review" => {
+            let id = parse_id(args.first())?;
+            let grade = args.get(1)
+                .map(|grade| grade.parse::<u8>().map_err(|_| Error::Parse(format!("Invalid grade '{}'", grade))))
+                .transpose()?;
+            store.review_reminder(id, grade, config)?;
+            save(storage, &store, &before, text)?;
+
+            let reminder = &store.reminders[&id];
+            if reminder.completed {
+                format!("Reminder {} completed! 🎉", id)
+            } else {
+                format!("Reminder {} reviewed, next review {}", id, format_duration_until(reminder.next_review))
+            }
+        }
+        "/snooze" => {
+            let id = parse_id(args.first())?;
+            let duration = duration::parse_duration(args.get(1).copied().unwrap_or("1d"))?;
+            store.snooze_reminder(id, duration)?;
+            save(storage, &store, &before, text)?;
+            format!("Reminder {} snoozed, next review {}", id,
+                    format_duration_until(store.reminders[&id].next_review))
+        }
+        _ => HELP.to_string(),
+    };
+
+    Ok(reply)
+}
+
+fn save(storage: &mut dyn Storage, store: &ReminderStore, before: &ReminderStore, command: &str) -> Result<()> {
+    let command = format!("reminder telegram {}", command);
+    undo::record(before, &command)?;
+    audit::record(before, store, &command)?;
+    storage.save(store)
+}
+
+/// Content and, hidden behind a spoiler, the answer of a reminder.
+fn format_reminder(reminder: &Reminder) -> String {
+    match &reminder.answer {
+        Some(answer) => format!("{}\n<tg-spoiler>{}</tg-spoiler>", escape(&reminder.content), escape(answer)),
+        None => escape(&reminder.content),
+    }
+}
+
+/// Escape text for Telegram's HTML parse mode.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,126 @@
+// src/testing.rs
+//! Helpers for exercising the library in tests, behind the `testing`
+//! feature. A [`Harness`] holds a store in a [`MemoryStorage`], never the
+//! data directory, and stops the clock at a fixed time that only moves when
+//! it is told to, so adding, reviewing and scheduling come out the same on
+//! every run:
+//!
+//! ```
+//! use reminder::testing::Harness;
+//!
+//! let mut harness = Harness::new();
+//! let id = harness.add("Learn Rust ownership concepts");
+//! assert!(harness.due().is_empty());
+//! // The first interval is a day, give or take the fuzz
+//! harness.advance_days(2);
+//! assert_eq!(harness.due(), vec![id]);
+//! harness.review(id, None).unwrap();
+//! assert!(harness.due().is_empty());
+//! ```
+
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::clock::{self, Clock, ManualClock};
+use crate::config::Config;
+use crate::dates;
+use crate::error::Result;
+use crate::storage::{MemoryStorage, Storage};
+use crate::{NewReminder, Reminder, ReminderStore};
+
+/// Held by the harness alive, as the clock is the process's: tests using a
+/// harness each wait for the one before to finish.
+static IN_USE: Mutex<()> = Mutex::new(());
+
+/// A store, its config and a stopped clock, starting at [`Harness::START`].
+pub struct Harness {
+    pub store: ReminderStore,
+    pub storage: MemoryStorage,
+    pub config: Config,
+    clock: ManualClock,
+    _in_use: MutexGuard<'static, ()>,
+}
+
+impl Harness {
+    /// Where the clock of a new harness stands: a Monday, 09:00 UTC.
+    pub const START: &'static str = "2024-01-01T09:00:00Z";
+
+    /// A harness with an empty store and the default config.
+    pub fn new() -> Self {
+        Harness::with_config(Config::default())
+    }
+
+    pub fn with_config(config: Config) -> Self {
+        let in_use = IN_USE.lock().unwrap_or_else(PoisonError::into_inner);
+        // Dates are shown and days counted in UTC, whatever the machine's
+        // time zone
+        dates::set_utc_offset(FixedOffset::east_opt(0).expect("Zero is a valid offset"));
+        let start = DateTime::parse_from_rfc3339(Harness::START).expect("START is a valid time");
+        let clock = ManualClock::new(start.with_timezone(&Utc));
+        clock::set(Arc::new(clock.clone()));
+        Harness { store: ReminderStore::default(), storage: MemoryStorage::new(), config, clock, _in_use: in_use }
+    }
+
+    /// The time on the harness's clock.
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    pub fn set_now(&mut self, now: DateTime<Utc>) {
+        self.clock.set(now);
+    }
+
+    pub fn advance(&mut self, by: Duration) {
+        self.clock.advance(by);
+    }
+
+    pub fn advance_days(&mut self, days: i64) {
+        self.advance(Duration::days(days));
+    }
+
+    /// Add a reminder with nothing but its content, returning its ID.
+    pub fn add(&mut self, content: &str) -> u32 {
+        self.add_new(NewReminder { content: content.to_string(), ..NewReminder::default() })
+    }
+
+    pub fn add_new(&mut self, new: NewReminder) -> u32 {
+        self.store.add_reminder(new, &self.config)
+    }
+
+    /// Review `id` now, with a recall grade for the graded algorithms.
+    pub fn review(&mut self, id: u32, grade: Option<u8>) -> Result<()> {
+        self.store.review_reminder(id, grade, &self.config)
+    }
+
+    /// IDs of the reminders due now, in the order `check` shows them.
+    pub fn due(&self) -> Vec<u32> {
+        self.store.get_due_reminders().iter().map(|reminder| reminder.id).collect()
+    }
+
+    /// The active reminder `id`.
+    ///
+    /// # Panics
+    /// When there is none, as a test expecting it has failed.
+    pub fn reminder(&self, id: u32) -> &Reminder {
+        self.store.reminders.get(&id).unwrap_or_else(|| panic!("No active reminder with ID {}", id))
+    }
+
+    /// Save the store and load it back, as the next command would see it.
+    pub fn reload(&mut self) -> Result<()> {
+        self.storage.save(&self.store)?;
+        self.store = self.storage.load()?;
+        Ok(())
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Harness::new()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        clock::reset();
+    }
+}
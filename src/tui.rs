@@ -0,0 +1,458 @@
+// src/tui.rs
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use reminder::config::Config;
+use reminder::scheduler::{self, Rating};
+use reminder::search::Query;
+use reminder::storage::Storage;
+use reminder::{clock, dates, duration, Error, Reminder, ReminderChanges, ReminderStore, Result};
+
+use crate::{editor, format_duration_until, graph, launcher, save_store};
+
+/// Snooze duration used when the prompt is left empty.
+const DEFAULT_SNOOZE: &str = "1d";
+
+/// Most recent reviews shown in the detail pane.
+const HISTORY_SHOWN: usize = 5;
+
+const HELP: &str = "j/k move  Tab due/all  / search  Enter answer  r review  1-4 again/hard/good/easy  s snooze  e edit  o open link  x remove  q quit";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum View {
+    /// Reminders due now, within the daily limit
+    Due,
+    All,
+}
+
+/// What the keys do at the moment.
+enum Mode {
+    Normal,
+    Search,
+    Snooze(String),
+    Grade,
+    ConfirmRemove,
+}
+
+struct App<'a> {
+    store: &'a mut ReminderStore,
+    storage: &'a mut dyn Storage,
+    config: &'a Config,
+    view: View,
+    mode: Mode,
+    query: String,
+    /// Reminders in the list, in the order shown.
+    ids: Vec<u32>,
+    list: ListState,
+    reveal: bool,
+    /// Outcome of the last action, and whether it failed.
+    message: Option<(String, bool)>,
+    quit: bool,
+}
+
+/// Run the full-screen interface until the user quits. Like `session`, it
+/// keeps the store loaded and saves it after every change.
+pub fn run(store: &mut ReminderStore, storage: &mut dyn Storage, config: &Config) -> Result<()> {
+    let mut terminal = ratatui::try_init().map_err(|e| Error::io("Failed to set up the terminal", e))?;
+    let mut app = App {
+        store,
+        storage,
+        config,
+        view: View::Due,
+        mode: Mode::Normal,
+        query: String::new(),
+        ids: Vec::new(),
+        list: ListState::default(),
+        reveal: false,
+        message: None,
+        quit: false,
+    };
+    app.refresh();
+    if app.ids.is_empty() {
+        app.view = View::All;
+        app.refresh();
+    }
+
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App<'_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame)).map_err(|e| Error::io("Failed to draw", e))?;
+            let event = event::read().map_err(|e| Error::io("Failed to read input", e))?;
+            if let Event::Key(key) = event {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key, terminal)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Recompute the list for the current view and search, keeping the
+    /// selected reminder selected if it is still listed.
+    fn refresh(&mut self) {
+        let selected = self.selected();
+        let query = Query::new(&self.query, false).expect("Substring queries are always valid");
+        let reminders = match self.view {
+            View::Due => self.store.apply_daily_limit(self.store.get_due_reminders(), self.config).0,
+            View::All => self.store.get_all_reminders(),
+        };
+        self.ids = reminders.into_iter().filter(|r| query.matches(r)).map(|r| r.id).collect();
+
+        let index = selected
+            .and_then(|id| self.ids.iter().position(|other| *other == id))
+            .or_else(|| self.list.selected().map(|index| index.min(self.ids.len().saturating_sub(1))))
+            .or(Some(0));
+        self.list.select(index.filter(|_| !self.ids.is_empty()));
+    }
+
+    fn selected(&self) -> Option<u32> {
+        self.list.selected().and_then(|index| self.ids.get(index)).copied()
+    }
+
+    fn selected_reminder(&self) -> Option<&Reminder> {
+        self.selected().and_then(|id| self.store.reminders.get(&id))
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        if self.ids.is_empty() {
+            return;
+        }
+        let current = self.list.selected().unwrap_or(0) as isize;
+        let index = (current + offset).clamp(0, self.ids.len() as isize - 1) as usize;
+        self.list.select(Some(index));
+        self.reveal = false;
+    }
+
+    /// Apply a change to the store and save it, reporting the outcome.
+    fn change(&mut self, action: impl FnOnce(&mut ReminderStore) -> Result<String>) {
+        let before = self.store.clone();
+        let result = action(self.store).and_then(|message| {
+            save_store(self.storage, self.store, &before)?;
+            Ok(message)
+        });
+        match result {
+            Ok(message) => self.message = Some((message, false)),
+            Err(e) => {
+                *self.store = before;
+                self.message = Some((e.to_string(), true));
+            }
+        }
+        self.reveal = false;
+        self.refresh();
+    }
+
+    fn review(&mut self, id: u32, grade: Option<u8>) {
+        let config = self.config;
+        self.change(|store| {
+            store.review_reminder(id, grade, config)?;
+            let reminder = &store.reminders[&id];
+            Ok(if reminder.completed {
+                format!("Reminder {} completed!", id)
+            } else {
+                format!("Reminder {} reviewed, next review {}", id, format_duration_until(reminder.next_review))
+            })
+        });
+    }
+
+    /// Edit the content in the user's editor, leaving the full-screen
+    /// interface while it runs.
+    fn edit(&mut self, id: u32, terminal: &mut DefaultTerminal) -> Result<()> {
+        let content = self.store.reminders[&id].content.clone();
+        ratatui::restore();
+        let edited = editor::edit_text(&content);
+        *terminal = ratatui::try_init().map_err(|e| Error::io("Failed to set up the terminal", e))?;
+
+        match edited {
+            Ok(edited) if edited == content => self.message = Some(("Nothing changed".to_string(), false)),
+            Ok(edited) => self.change(|store| {
+                store.edit_reminder(id, ReminderChanges { content: Some(edited), ..Default::default() })?;
+                Ok(format!("Reminder {} updated", id))
+            }),
+            Err(e) => self.message = Some((e.to_string(), true)),
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, terminal: &mut DefaultTerminal) -> Result<()> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return Ok(());
+        }
+
+        match &mut self.mode {
+            Mode::Search => match key.code {
+                KeyCode::Enter => self.mode = Mode::Normal,
+                KeyCode::Esc => {
+                    self.query.clear();
+                    self.mode = Mode::Normal;
+                    self.refresh();
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.refresh();
+                }
+                KeyCode::Char(c) => {
+                    self.query.push(c);
+                    self.refresh();
+                }
+                _ => {}
+            },
+            Mode::Snooze(input) => match key.code {
+                KeyCode::Enter => {
+                    let input = if input.trim().is_empty() { DEFAULT_SNOOZE.to_string() } else { input.trim().to_string() };
+                    self.mode = Mode::Normal;
+                    if let Some(id) = self.selected() {
+                        self.change(|store| {
+                            store.snooze_reminder(id, duration::parse_duration(&input)?)?;
+                            Ok(format!("Reminder {} snoozed for {}", id, input))
+                        });
+                    }
+                }
+                KeyCode::Esc => self.mode = Mode::Normal,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Mode::Grade => {
+                let grade = match key.code {
+                    KeyCode::Enter => Some(None),
+                    KeyCode::Char(c @ '0'..='5') => c.to_digit(10).map(|grade| Some(grade as u8)),
+                    _ => None,
+                };
+                self.mode = Mode::Normal;
+                if let (Some(grade), Some(id)) = (grade, self.selected()) {
+                    self.review(id, grade);
+                }
+            }
+            Mode::ConfirmRemove => {
+                self.mode = Mode::Normal;
+                if let (KeyCode::Char('y'), Some(id)) = (key.code, self.selected()) {
+                    self.change(|store| {
+                        store.remove_reminder(id)?;
+                        Ok(format!("Reminder {} moved to the trash", id))
+                    });
+                }
+            }
+            Mode::Normal => self.handle_normal_key(key, terminal)?,
+        }
+        Ok(())
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent, terminal: &mut DefaultTerminal) -> Result<()> {
+        let page = terminal.size().map(|size| size.height.saturating_sub(4) as isize).unwrap_or(10);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('j') | KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(page),
+            KeyCode::PageUp => self.move_selection(-page),
+            KeyCode::Char('g') | KeyCode::Home => self.move_selection(isize::MIN / 2),
+            KeyCode::Char('G') | KeyCode::End => self.move_selection(isize::MAX / 2),
+            KeyCode::Tab => {
+                self.view = if self.view == View::Due { View::All } else { View::Due };
+                self.refresh();
+            }
+            KeyCode::Char('/') => self.mode = Mode::Search,
+            KeyCode::Enter | KeyCode::Char(' ') => self.reveal = !self.reveal,
+            _ => {}
+        }
+
+        let Some(reminder) = self.selected_reminder() else {
+            return Ok(());
+        };
+        let id = reminder.id;
+        match key.code {
+            KeyCode::Char('r') if reminder.completed => {
+                self.message = Some((format!("Reminder {} is already completed", id), true));
+            }
+            KeyCode::Char('r') if reminder.is_graded() => self.mode = Mode::Grade,
+            KeyCode::Char('r') => self.review(id, None),
+            KeyCode::Char(key @ '1'..='4') => {
+                let rating = match key {
+                    '1' => Rating::Again,
+                    '2' => Rating::Hard,
+                    '3' => Rating::Good,
+                    _ => Rating::Easy,
+                };
+                self.review(id, Some(rating.grade()));
+            }
+            KeyCode::Char('s') => self.mode = Mode::Snooze(String::new()),
+            KeyCode::Char('e') => self.edit(id, terminal)?,
+            KeyCode::Char('o') => {
+                let opened = match reminder.urls().first() {
+                    Some(url) => launcher::open(url.as_ref()).map(|()| format!("Opened {}", url)),
+                    None => Err(Error::Invalid(format!("Reminder {} has no web address in it", id))),
+                };
+                self.message = Some(match opened {
+                    Ok(message) => (message, false),
+                    Err(e) => (e.to_string(), true),
+                });
+            }
+            KeyCode::Char('x') | KeyCode::Delete => self.mode = Mode::ConfirmRemove,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([Constraint::Length(1), Constraint::Min(3), Constraint::Length(1)])
+            .areas(frame.area());
+        let [list_area, detail_area] = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .areas(body);
+
+        frame.render_widget(Paragraph::new(self.header()), header);
+
+        let items: Vec<ListItem> = self.ids.iter()
+            .map(|id| list_item(&self.store.reminders[id]))
+            .collect();
+        let title = match self.view {
+            View::Due => format!(" Due ({}) ", self.ids.len()),
+            View::All => format!(" All ({}) ", self.ids.len()),
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let detail = match self.selected_reminder() {
+            Some(reminder) => detail_lines(self.store, reminder, self.reveal),
+            None if self.view == View::Due => vec![Line::from("No reminders due for review!")],
+            None => vec![Line::from("No reminders found!")],
+        };
+        frame.render_widget(
+            Paragraph::new(detail)
+                .block(Block::default().borders(Borders::ALL).title(" Details "))
+                .wrap(Wrap { trim: false }),
+            detail_area,
+        );
+
+        frame.render_widget(Paragraph::new(self.footer()), footer);
+    }
+
+    fn header(&self) -> Line<'static> {
+        let tab = |label: &'static str, view: View| {
+            if self.view == view {
+                Span::styled(label, Style::default().add_modifier(Modifier::REVERSED))
+            } else {
+                Span::raw(label)
+            }
+        };
+        let mut spans = vec![Span::styled("reminder ", Style::default().add_modifier(Modifier::BOLD)),
+                             tab(" Due ", View::Due), Span::raw(" "), tab(" All ", View::All)];
+        if matches!(self.mode, Mode::Search) || !self.query.is_empty() {
+            spans.push(Span::raw(format!("   Search: {}", self.query)));
+            if matches!(self.mode, Mode::Search) {
+                spans.push(Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)));
+            }
+        }
+        Line::from(spans)
+    }
+
+    fn footer(&self) -> Line<'static> {
+        let id = self.selected().unwrap_or_default();
+        match &self.mode {
+            Mode::Search => Line::from("Type to filter, Enter to keep the filter, Esc to clear it"),
+            Mode::Snooze(input) => Line::from(format!("Snooze reminder {} for [{}]: {}_", id, DEFAULT_SNOOZE, input)),
+            Mode::Grade => Line::from(format!(
+                "Grade 0-5 for reminder {} (Enter for {}, any other key to cancel)",
+                id,
+                scheduler::DEFAULT_GRADE
+            )),
+            Mode::ConfirmRemove => Line::from(format!("Remove reminder {}? [y/N]", id)),
+            Mode::Normal => match &self.message {
+                Some((message, true)) => Line::styled(message.clone(), Style::default().fg(Color::Red)),
+                Some((message, false)) => Line::styled(message.clone(), Style::default().fg(Color::Green)),
+                None => Line::styled(HELP, Style::default().fg(Color::DarkGray)),
+            },
+        }
+    }
+}
+
+fn due_color(reminder: &Reminder) -> Color {
+    if reminder.completed {
+        Color::Green
+    } else if reminder.paused_at.is_some() {
+        Color::DarkGray
+    } else if dates::is_due(reminder.next_review, clock::now()) {
+        Color::Red
+    } else {
+        Color::Yellow
+    }
+}
+
+fn list_item(reminder: &Reminder) -> ListItem<'static> {
+    let first_line = reminder.content.lines().next().unwrap_or_default().to_string();
+    ListItem::new(Line::from(vec![
+        Span::styled(format!("{:>4} ", reminder.id), Style::default().fg(Color::Cyan)),
+        Span::raw(first_line),
+    ]))
+}
+
+fn detail_lines(store: &ReminderStore, reminder: &Reminder, reveal: bool) -> Vec<Line<'static>> {
+    let when = if reminder.completed {
+        "completed".to_string()
+    } else if let Some(paused_at) = reminder.paused_at {
+        format!("paused since {}", dates::local(paused_at).format("%Y-%m-%d %H:%M"))
+    } else {
+        format!("next review {} ({})", format_duration_until(reminder.next_review), dates::local(reminder.next_review).format("%Y-%m-%d %H:%M"))
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(format!("ID {}", reminder.id), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw("  "),
+            Span::styled(when, Style::default().fg(due_color(reminder))),
+        ]),
+        Line::from(format!("{} review(s), {} algorithm, {} priority", reminder.review_count, reminder.algorithm, reminder.priority)),
+    ];
+    if let Some(deck) = &reminder.deck {
+        lines.push(Line::from(format!("Deck: {}", deck)));
+    }
+    if !reminder.tags.is_empty() {
+        lines.push(Line::styled(format!("Tags: {}", reminder.tags.join(", ")), Style::default().fg(Color::Magenta)));
+    }
+    if !reminder.attachments.is_empty() {
+        lines.push(Line::from(format!("Attachments: {}", reminder.attachments.join(", "))));
+    }
+    if let Some(recurrence) = &reminder.recurrence {
+        lines.push(Line::from(format!("Repeats: {}", recurrence)));
+    }
+    if let Some(related) = graph::related_line(store, reminder.id) {
+        lines.push(Line::from(format!("Related: {}", related)));
+    }
+
+    lines.push(Line::default());
+    lines.extend(reminder.content.lines().map(|line| Line::from(line.to_string())));
+
+    if let Some(answer) = &reminder.answer {
+        lines.push(Line::default());
+        if reveal {
+            lines.push(Line::styled("Answer:", Style::default().add_modifier(Modifier::BOLD)));
+            lines.extend(answer.lines().map(|line| Line::from(line.to_string())));
+        } else {
+            lines.push(Line::styled("Press Enter to reveal the answer", Style::default().fg(Color::DarkGray)));
+        }
+    }
+
+    if !reminder.history.is_empty() {
+        lines.push(Line::default());
+        lines.push(Line::styled("Recent reviews:", Style::default().add_modifier(Modifier::BOLD)));
+        for event in reminder.history.iter().rev().take(HISTORY_SHOWN) {
+            let grade = event.grade.map(|grade| format!(", grade {}", grade)).unwrap_or_default();
+            lines.push(Line::from(format!("  {}{}", dates::local(event.timestamp).format("%Y-%m-%d %H:%M"), grade)));
+        }
+    }
+    lines
+}
@@ -0,0 +1,78 @@
+// src/undo.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{clock, crypto};
+use crate::error::{Error, Result};
+use crate::storage;
+use crate::ReminderStore;
+
+/// How many operations can be undone in a row.
+const MAX_UNDO_ENTRIES: usize = 10;
+
+/// Snapshot of the store taken right before a mutating command ran.
+#[derive(Serialize, Deserialize)]
+pub struct UndoEntry {
+    pub command: String,
+    pub timestamp: DateTime<Utc>,
+    #[serde(deserialize_with = "storage::migrations::deserialize")]
+    pub store: ReminderStore,
+}
+
+fn get_journal_path() -> PathBuf {
+    storage::companion_path("undo.json", "undo.json")
+}
+
+fn load_journal() -> Vec<UndoEntry> {
+    fs::read(get_journal_path())
+        .ok()
+        .and_then(|content| crypto::open(content).ok())
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Write the journal, encrypted like the store it holds snapshots of.
+fn save_journal(journal: &[UndoEntry]) -> Result<()> {
+    let content = serde_json::to_string(journal)
+        .map_err(|e| Error::Parse(format!("Failed to serialize undo journal: {}", e)))?;
+    storage::write_atomic(&get_journal_path(), &crypto::seal(content.as_bytes())?)
+}
+
+/// Write the journal again, e.g. after encryption was turned on or off.
+pub fn rewrite_journal() -> Result<()> {
+    let journal = load_journal();
+    if journal.is_empty() {
+        return Ok(());
+    }
+    save_journal(&journal)
+}
+
+/// Remember the state of the store before `command` changed it.
+pub fn record(before: &ReminderStore, command: &str) -> Result<()> {
+    let mut journal = load_journal();
+    journal.push(UndoEntry {
+        command: command.to_string(),
+        timestamp: clock::now(),
+        store: before.clone(),
+    });
+
+    let excess = journal.len().saturating_sub(MAX_UNDO_ENTRIES);
+    journal.drain(..excess);
+    save_journal(&journal)
+}
+
+/// Take the most recent snapshot off the journal.
+pub fn pop() -> Result<UndoEntry> {
+    let mut journal = load_journal();
+    let entry = journal.pop().ok_or(Error::NothingToUndo)?;
+    save_journal(&journal)?;
+    Ok(entry)
+}
+
+/// The command line of this invocation, used to describe undo entries.
+pub fn current_command() -> String {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    format!("reminder {}", args.join(" "))
+}
@@ -0,0 +1,104 @@
+// src/watch.rs
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::io::{self, IsTerminal, Write};
+use std::thread;
+
+use reminder::storage::Storage;
+use reminder::{clock, dates, lock, Reminder};
+
+use crate::graph;
+use crate::schedule;
+use crate::style;
+use crate::format_next_review;
+
+/// Options of `reminder watch`.
+pub struct WatchOptions<'a> {
+    pub interval: Duration,
+    pub tags: &'a [String],
+    pub deck: Option<&'a str>,
+    pub trim: Option<usize>,
+    /// Ring the terminal bell when reminders become due
+    pub beep: bool,
+}
+
+/// Keep showing the due reminders, reloading the store every interval. On a
+/// terminal the list is redrawn in place and the reminders that became due
+/// since the last refresh are marked; otherwise only those are printed, one
+/// line each.
+pub fn run(storage: &mut dyn Storage, options: &WatchOptions) {
+    let sleep_for = options.interval.to_std()
+        .unwrap_or(std::time::Duration::from_secs(10));
+    let redraw = io::stdout().is_terminal();
+    let mut seen: Option<HashSet<(u32, DateTime<Utc>)>> = None;
+
+    loop {
+        // A failed load is retried on the next refresh, as in the daemon
+        let store = match lock::acquire().and_then(|_lock| storage.load()) {
+            Ok(store) => store,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                thread::sleep(sleep_for);
+                continue;
+            }
+        };
+        let due: Vec<&Reminder> = store.get_due_reminders()
+            .into_iter()
+            .filter(|r| r.has_tags(options.tags) && r.in_deck(options.deck))
+            .collect();
+        let current: HashSet<(u32, DateTime<Utc>)> = due.iter().map(|r| (r.id, r.next_review)).collect();
+        // Whatever was due when watching started isn't news
+        let new: HashSet<u32> = match &seen {
+            Some(seen) => current.difference(seen).map(|(id, _)| *id).collect(),
+            None => HashSet::new(),
+        };
+
+        if redraw {
+            print_list(&due, &new, options);
+        } else {
+            for reminder in due.iter().filter(|r| new.contains(&r.id)) {
+                println!(
+                    "[{}] Reminder {} is due: {}",
+                    dates::local(clock::now()).format("%Y-%m-%d %H:%M"),
+                    reminder.id,
+                    graph::label(reminder, options.trim)
+                );
+            }
+        }
+        if options.beep && !new.is_empty() {
+            print!("\x07");
+        }
+        let _ = io::stdout().flush();
+
+        seen = Some(current);
+        thread::sleep(sleep_for);
+    }
+}
+
+fn print_list(due: &[&Reminder], new: &HashSet<u32>, options: &WatchOptions) {
+    // Clear the screen and move to its top left corner
+    print!("\x1b[2J\x1b[H");
+    println!(
+        "Due reminders at {}, refreshing every {} (Ctrl+C to stop)",
+        dates::local(clock::now()).format("%H:%M:%S"),
+        schedule::describe(options.interval)
+    );
+    println!("{}", "=".repeat(50));
+    if due.is_empty() {
+        println!("No reminders due for review!");
+        return;
+    }
+    for reminder in due {
+        let marker = if new.contains(&reminder.id) { style::due("new ") } else { "    ".into() };
+        println!(
+            "{}{} {} ({})",
+            marker,
+            style::id(reminder.id),
+            graph::label(reminder, options.trim),
+            format_next_review(reminder)
+        );
+    }
+    if !new.is_empty() {
+        println!("\n{} reminder(s) just became due", new.len());
+    }
+}
@@ -0,0 +1,156 @@
+// Front-end for `reminder serve`, talking to the same REST API.
+let view = "due";
+
+const list = document.getElementById("reminders");
+const message = document.getElementById("message");
+
+async function api(method, path, body) {
+  const response = await fetch(path, {
+    method,
+    headers: { "Content-Type": "application/json" },
+    body: body === undefined ? undefined : JSON.stringify(body),
+  });
+  const data = await response.json();
+  if (!response.ok) {
+    throw new Error(data.error || response.statusText);
+  }
+  return data;
+}
+
+function show(text, isError) {
+  message.textContent = text;
+  message.className = isError ? "error" : "";
+  message.hidden = false;
+}
+
+function whenDue(reminder) {
+  if (reminder.completed) {
+    return "completed";
+  }
+  const due = new Date(reminder.next_review);
+  return due <= new Date() ? "due now" : "due " + due.toLocaleString();
+}
+
+function element(tag, className, text) {
+  const node = document.createElement(tag);
+  if (className) node.className = className;
+  if (text !== undefined) node.textContent = text;
+  return node;
+}
+
+function renderReminder(reminder) {
+  const item = element("li");
+
+  const meta = [`#${reminder.id}`, whenDue(reminder), `${reminder.review_count} review(s)`];
+  if (reminder.priority !== "normal") meta.push(reminder.priority);
+  if (reminder.deck) meta.push(`deck ${reminder.deck}`);
+  if (reminder.tags.length) meta.push(reminder.tags.join(", "));
+  const metaLine = element("div", "meta", meta.join(" · "));
+  if (!reminder.completed && new Date(reminder.next_review) <= new Date()) {
+    metaLine.classList.add("due");
+  }
+  item.append(metaLine, element("div", "content", reminder.content));
+
+  if (reminder.answer) {
+    const answer = element("div", "answer", reminder.answer);
+    answer.hidden = true;
+    const reveal = element("button", "", "Show answer");
+    reveal.onclick = () => {
+      answer.hidden = !answer.hidden;
+      reveal.textContent = answer.hidden ? "Show answer" : "Hide answer";
+    };
+    item.append(reveal, answer);
+  }
+
+  const actions = element("div", "actions");
+  if (!reminder.completed) {
+    if (reminder.algorithm === "simple") {
+      actions.append(button("Reviewed", () => review(reminder.id)));
+    } else {
+      for (const [label, grade] of [["Again", 1], ["Hard", 3], ["Good", 4], ["Easy", 5]]) {
+        actions.append(button(label, () => review(reminder.id, grade)));
+      }
+    }
+
+    const duration = element("input");
+    duration.value = "1d";
+    duration.title = "Snooze duration, e.g. 2h, 3d, 1w";
+    actions.append(duration, button("Snooze", () => snooze(reminder.id, duration.value)));
+  }
+  actions.append(button("Remove", () => remove(reminder.id)));
+  item.append(actions);
+
+  return item;
+}
+
+function button(label, onClick) {
+  const node = element("button", "", label);
+  node.onclick = onClick;
+  return node;
+}
+
+async function refresh() {
+  try {
+    const reminders = await api("GET", view === "due" ? "/due" : "/reminders");
+    list.replaceChildren(...reminders.map(renderReminder));
+    if (!reminders.length) {
+      list.append(element("li", "meta", view === "due" ? "No reminders due for review!" : "No reminders yet."));
+    }
+  } catch (error) {
+    show(error.message, true);
+  }
+}
+
+async function run(action, success) {
+  try {
+    const result = await action();
+    show(success(result), false);
+    await refresh();
+  } catch (error) {
+    show(error.message, true);
+  }
+}
+
+function review(id, grade) {
+  run(() => api("POST", `/reminders/${id}/review`, grade === undefined ? {} : { grade }),
+      (reminder) => reminder.completed ? `Reminder ${id} completed!` : `Reminder ${id} reviewed`);
+}
+
+function snooze(id, duration) {
+  run(() => api("POST", `/reminders/${id}/snooze`, { duration }), () => `Reminder ${id} snoozed for ${duration}`);
+}
+
+function remove(id) {
+  if (confirm(`Remove reminder ${id}?`)) {
+    run(() => api("DELETE", `/reminders/${id}`), () => `Reminder ${id} removed`);
+  }
+}
+
+document.getElementById("add").onsubmit = (event) => {
+  event.preventDefault();
+  const form = event.target;
+  const field = (name) => form.elements[name].value.trim();
+  const body = {
+    content: field("content"),
+    tags: field("tags").split(",").map((tag) => tag.trim()).filter(Boolean),
+    priority: field("priority"),
+  };
+  for (const name of ["answer", "deck", "due"]) {
+    if (field(name)) body[name] = field(name);
+  }
+
+  run(() => api("POST", "/reminders", body), (reminder) => {
+    form.reset();
+    return `Added reminder ${reminder.id}`;
+  });
+};
+
+for (const tab of document.querySelectorAll("nav button")) {
+  tab.onclick = () => {
+    view = tab.dataset.view;
+    document.querySelectorAll("nav button").forEach((other) => other.classList.toggle("active", other === tab));
+    refresh();
+  };
+}
+
+refresh();
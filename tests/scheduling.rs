@@ -0,0 +1,220 @@
+// tests/scheduling.rs
+//! Adding, reviewing and scheduling through the `testing` harness, on a
+//! stopped clock and without fuzz, so every due time is exact.
+
+use chrono::{DateTime, Duration, Utc};
+use reminder::config::{Completion, Config};
+use reminder::recurrence::Recurrence;
+use reminder::scheduler::{Algorithm, MAX_INTERVAL};
+use reminder::{dates, duration};
+use reminder::simulate;
+use reminder::storage::migrations::STORE_VERSION;
+use reminder::storage::{MemoryStorage, Storage};
+use reminder::testing::Harness;
+use reminder::{NewReminder, ReminderStore};
+
+fn harness() -> Harness {
+    Harness::with_config(Config { fuzz: 0.0, ..Config::default() })
+}
+
+#[test]
+fn new_reminders_come_due_after_the_first_interval() {
+    let mut harness = harness();
+    let id = harness.add("Learn Rust ownership concepts");
+    assert_eq!(harness.reminder(id).next_review, harness.now() + Duration::days(1));
+
+    harness.advance(Duration::hours(23));
+    assert!(harness.due().is_empty());
+    harness.advance(Duration::hours(1));
+    assert_eq!(harness.due(), vec![id]);
+}
+
+#[test]
+fn reviews_climb_the_ladder_and_complete_it() {
+    let mut harness = harness();
+    let id = harness.add("Borrowing rules");
+
+    for days in [3, 7, 30] {
+        harness.advance_days(harness.reminder(id).interval as i64);
+        harness.review(id, None).unwrap();
+        let reminder = harness.reminder(id);
+        assert_eq!(reminder.interval, days);
+        assert_eq!(reminder.next_review, harness.now() + Duration::days(days as i64));
+    }
+    harness.advance_days(30);
+    harness.review(id, None).unwrap();
+    assert!(harness.reminder(id).completed);
+    assert!(harness.due().is_empty());
+}
+
+#[test]
+fn reminders_past_the_ladder_keep_coming_back_when_they_never_complete() {
+    let config = Config { fuzz: 0.0, completion: Completion::Never, ..Config::default() };
+    let mut harness = Harness::with_config(config);
+    let id = harness.add("Stretch");
+    // Through the ladder of 1, 3, 7 and 30 days, then doubling the last
+    for days in [3, 7, 30, 60, 120] {
+        harness.review(id, None).unwrap();
+        assert_eq!(harness.reminder(id).interval, days);
+    }
+    assert!(!harness.reminder(id).completed);
+}
+
+#[test]
+fn a_failed_sm2_review_starts_over() {
+    let mut harness = harness();
+    let id = harness.add_new(NewReminder {
+        content: "What does Rc do?".to_string(),
+        algorithm: Some(Algorithm::Sm2),
+        ..NewReminder::default()
+    });
+    harness.review(id, Some(5)).unwrap();
+    harness.review(id, Some(5)).unwrap();
+    assert_eq!(harness.reminder(id).interval, 6);
+
+    harness.review(id, Some(1)).unwrap();
+    let reminder = harness.reminder(id);
+    assert_eq!((reminder.interval, reminder.repetitions), (1, 0));
+    assert_eq!(reminder.next_review, harness.now() + Duration::days(1));
+}
+
+#[test]
+fn intervals_stop_growing_at_a_hundred_years() {
+    let config = Config { fuzz: 0.0, completion: Completion::Never, ..Config::default() };
+    let mut harness = Harness::with_config(config);
+    let easy = harness.add_new(NewReminder {
+        content: "What does Box do?".to_string(),
+        algorithm: Some(Algorithm::Sm2),
+        ..NewReminder::default()
+    });
+    let stretch = harness.add("Stretch");
+    for _ in 0..40 {
+        harness.review(easy, Some(5)).unwrap();
+        harness.review(stretch, None).unwrap();
+    }
+    assert_eq!(harness.reminder(easy).interval, MAX_INTERVAL);
+    assert_eq!(harness.reminder(stretch).interval, MAX_INTERVAL);
+
+    // Near the end of representable time even that is too far
+    harness.set_now(DateTime::<Utc>::MAX_UTC - Duration::days(30));
+    let before = harness.reminder(easy).clone();
+    assert!(harness.review(easy, Some(5)).is_err());
+    assert_eq!(harness.reminder(easy).review_count, before.review_count);
+    assert_eq!(harness.reminder(easy).next_review, before.next_review);
+}
+
+#[test]
+fn snoozing_moves_an_overdue_reminder_from_now() {
+    let mut harness = harness();
+    let id = harness.add("Call the bank");
+    harness.advance_days(3);
+    harness.store.snooze_reminder(id, Duration::hours(2)).unwrap();
+    assert_eq!(harness.reminder(id).next_review, harness.now() + Duration::hours(2));
+    assert!(harness.due().is_empty());
+}
+
+#[test]
+fn durations_and_snoozes_too_long_to_represent_are_refused() {
+    let mut harness = harness();
+    let id = harness.add("Call the bank");
+    assert!(duration::parse_duration("99999999999999d").is_err());
+    assert!(duration::parse_duration("9999999999999999w").is_err());
+
+    let before = harness.reminder(id).next_review;
+    let far = duration::parse_duration("300000y").unwrap();
+    assert!(harness.store.snooze_reminder(id, far).is_err());
+    assert_eq!(harness.reminder(id).next_review, before);
+}
+
+#[test]
+fn vacations_shifting_reviews_past_the_last_representable_time_are_refused() {
+    let mut harness = harness();
+    let id = harness.add("Water the plants");
+    let before = harness.reminder(id).next_review;
+    let now = harness.now();
+    assert!(harness.store.take_vacation(now, DateTime::<Utc>::MAX_UTC - Duration::hours(1)).is_err());
+    assert_eq!(harness.reminder(id).next_review, before);
+    assert!(harness.store.vacations.is_empty());
+}
+
+#[test]
+fn since_windows_reaching_before_the_first_representable_time_are_refused() {
+    let harness = harness();
+    assert!(dates::parse_since("300000y", harness.now()).is_err());
+    assert_eq!(dates::parse_since("2w", harness.now()).unwrap(), harness.now() - Duration::weeks(2));
+    assert!(dates::parse_since("2024-03-01", harness.now()).is_ok());
+}
+
+#[test]
+fn repeat_periods_past_the_last_representable_time_are_refused() {
+    assert!(Recurrence::parse("every 300000y").is_err());
+    assert!(Recurrence::parse("every 99999999999999d").is_err());
+    assert!(Recurrence::parse("every 2w").is_ok());
+}
+
+#[test]
+fn reminders_wait_for_their_prerequisites() {
+    let mut harness = harness();
+    let basics = harness.add("Ownership");
+    let advanced = harness.add("Lifetimes");
+    harness.store.depend(advanced, basics, Some(1)).unwrap();
+    harness.advance_days(1);
+    assert_eq!(harness.due(), vec![basics]);
+
+    harness.review(basics, None).unwrap();
+    assert_eq!(harness.due(), vec![advanced]);
+}
+
+#[test]
+fn the_schedule_survives_saving_and_loading() {
+    let mut harness = harness();
+    let id = harness.add("Traits");
+    harness.review(id, None).unwrap();
+    let before = harness.reminder(id).clone();
+
+    harness.reload().unwrap();
+    assert_eq!(harness.storage.saves(), 1);
+    let after = harness.reminder(id);
+    assert_eq!(
+        (after.next_review, after.interval, after.review_count),
+        (before.next_review, before.interval, before.review_count)
+    );
+    assert_eq!(after.history.len(), 1);
+}
+
+#[test]
+fn stores_without_a_format_version_are_upgraded() {
+    let mut storage = MemoryStorage::new();
+    let store = ReminderStore { version: 0, next_id: 7, ..ReminderStore::default() };
+    storage.save(&store).unwrap();
+
+    let loaded = storage.load().unwrap();
+    assert_eq!(loaded.version, STORE_VERSION);
+    assert_eq!(loaded.next_id, 7);
+}
+
+#[test]
+fn stores_from_a_newer_version_are_refused() {
+    let store = ReminderStore { version: STORE_VERSION + 1, ..ReminderStore::default() };
+    let mut storage = MemoryStorage::with_store(&store).unwrap();
+    assert!(storage.load().is_err());
+}
+
+#[test]
+fn simulations_project_the_same_workload_without_changing_the_store() {
+    let mut harness = harness();
+    for number in 0..5 {
+        harness.add(&format!("Reminder {}", number));
+    }
+    let run = || simulate::run(&harness.store, &harness.config, 30, 2);
+    let (first, second) = (run(), run());
+
+    assert_eq!(first.days.len(), 30);
+    assert!(first.total_reviews() > 0);
+    assert_eq!(first.total_reviews(), second.total_reviews());
+    assert_eq!(first.active + first.completed, 5 + 30 * 2);
+    assert_eq!(harness.store.reminders.len(), 5);
+    assert!(harness.store.reminders.values().all(|reminder| reminder.review_count == 0));
+    // The harness's clock is back in place
+    assert_eq!(harness.now(), harness.reminder(0).created_at);
+}